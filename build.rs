@@ -0,0 +1,556 @@
+// Build script: generates typed OCSF structs when the `typed-events` feature is enabled
+//
+// The schema version is taken from `OCSF_TYPED_VERSION` (defaults to the newest bundled
+// stable release) and must exist under `data/ocsf-schema/`.
+
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+const DEFAULT_TYPED_VERSION: &str = "1.4.0";
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=OCSF_TYPED_VERSION");
+
+    if std::env::var_os("CARGO_FEATURE_TYPED_EVENTS").is_none() {
+        return;
+    }
+
+    let version =
+        std::env::var("OCSF_TYPED_VERSION").unwrap_or_else(|_| DEFAULT_TYPED_VERSION.to_string());
+    let path = format!("data/ocsf-schema/{version}.json");
+    println!("cargo:rerun-if-changed={path}");
+
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("OCSF_TYPED_VERSION={version}: cannot read {path}: {e}"));
+    let schema: Value =
+        serde_json::from_str(&content).unwrap_or_else(|e| panic!("Invalid schema {path}: {e}"));
+
+    let code = Generator::new(&schema).generate(&version);
+    let out = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("ocsf_typed.rs");
+    std::fs::write(&out, code).unwrap_or_else(|e| panic!("Cannot write {}: {e}", out.display()));
+}
+
+/// A class or object whose attributes become struct fields
+struct Owner<'a> {
+    name: &'a str,
+    type_name: String,
+    caption: Option<&'a str>,
+    attributes: &'a Map<String, Value>,
+    /// `(class_uid, category_uid)` for event classes
+    class: Option<(i64, i64)>,
+}
+
+/// Enum member id -> caption
+type EnumValues = BTreeMap<i64, String>;
+
+/// A generated `_id` enum, shared by every owner with an identical value set
+struct EnumDef {
+    name: String,
+    values: EnumValues,
+    aliases: BTreeSet<String>,
+}
+
+struct Generator<'a> {
+    types: HashMap<&'a str, &'a str>,
+    objects: BTreeMap<&'a str, &'a Map<String, Value>>,
+    owners: Vec<Owner<'a>>,
+    /// Objects reachable from each object, for boxing recursive fields
+    reachable: HashMap<&'a str, BTreeSet<&'a str>>,
+    /// `(owner type name, attribute)` -> enum type name used for the field
+    enum_fields: HashMap<(String, String), String>,
+    enums: Vec<EnumDef>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(schema: &'a Value) -> Self {
+        let section = |key: &str| {
+            schema
+                .get(key)
+                .and_then(|v| v.as_object())
+                .map(|m| m.iter().collect::<BTreeMap<_, _>>())
+                .unwrap_or_default()
+        };
+
+        let types = section("types")
+            .into_iter()
+            .map(|(name, def)| {
+                let base = def.get("type").and_then(|t| t.as_str()).unwrap_or(name);
+                (name.as_str(), base)
+            })
+            .collect();
+
+        let attributes = |def: &'a Value| def.get("attributes").and_then(|a| a.as_object());
+        let objects: BTreeMap<&str, &Map<String, Value>> = section("objects")
+            .into_iter()
+            .filter_map(|(name, def)| Some((name.as_str(), attributes(def)?)))
+            .collect();
+
+        let mut owners = Vec::new();
+        for (name, def) in section("classes") {
+            if name == "base_event" {
+                continue;
+            }
+            let Some(attrs) = attributes(def) else {
+                continue;
+            };
+            let uid = def.get("uid").and_then(|v| v.as_i64()).unwrap_or_default();
+            let category_uid = def
+                .get("category_uid")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(uid / 1000);
+            owners.push(Owner {
+                name,
+                type_name: pascal_case(name),
+                caption: def.get("caption").and_then(|c| c.as_str()),
+                attributes: attrs,
+                class: Some((uid, category_uid)),
+            });
+        }
+        for (name, def) in section("objects") {
+            let Some(attrs) = attributes(def) else {
+                continue;
+            };
+            if attrs.is_empty() {
+                continue;
+            }
+            owners.push(Owner {
+                name,
+                type_name: pascal_case(name),
+                caption: def.get("caption").and_then(|c| c.as_str()),
+                attributes: attrs,
+                class: None,
+            });
+        }
+
+        let mut generator = Self {
+            types,
+            objects,
+            owners,
+            reachable: HashMap::new(),
+            enum_fields: HashMap::new(),
+            enums: Vec::new(),
+        };
+        generator.compute_reachability();
+        generator.collect_enums();
+        generator
+    }
+
+    fn compute_reachability(&mut self) {
+        let objects = self.objects.clone();
+        let edges: HashMap<&str, Vec<&str>> = objects
+            .iter()
+            .map(|(name, attrs)| {
+                let targets = attrs
+                    .values()
+                    .filter_map(|a| a.get("object_type").and_then(|t| t.as_str()))
+                    .filter(|t| objects.contains_key(t))
+                    .collect();
+                (*name, targets)
+            })
+            .collect();
+
+        for start in objects.keys() {
+            let mut seen = BTreeSet::new();
+            let mut stack = vec![*start];
+            while let Some(node) = stack.pop() {
+                for next in edges.get(node).into_iter().flatten() {
+                    if seen.insert(*next) {
+                        stack.push(next);
+                    }
+                }
+            }
+            self.reachable.insert(start, seen);
+        }
+    }
+
+    fn collect_enums(&mut self) {
+        // attribute name -> distinct value sets -> owners using that set
+        let mut by_attr: BTreeMap<&str, Vec<(EnumValues, BTreeSet<String>)>> = BTreeMap::new();
+
+        for owner in &self.owners {
+            for (attr_name, attr) in owner.attributes {
+                let Some(values) = self.enum_values(attr_name, attr) else {
+                    continue;
+                };
+                let sets = by_attr.entry(attr_name).or_default();
+                match sets.iter_mut().find(|(v, _)| *v == values) {
+                    Some((_, owners)) => {
+                        owners.insert(owner.type_name.clone());
+                    }
+                    None => sets.push((values, BTreeSet::from([owner.type_name.clone()]))),
+                }
+            }
+        }
+
+        for (attr_name, sets) in by_attr {
+            let shared = sets.len() == 1;
+            for (values, owners) in sets {
+                let name = if shared {
+                    pascal_case(attr_name)
+                } else {
+                    format!("{}{}", owners.first().unwrap(), pascal_case(attr_name))
+                };
+                let mut aliases = BTreeSet::new();
+                for owner in &owners {
+                    let field_type = if shared {
+                        name.clone()
+                    } else {
+                        let alias = format!("{owner}{}", pascal_case(attr_name));
+                        if alias != name {
+                            aliases.insert(alias.clone());
+                        }
+                        alias
+                    };
+                    self.enum_fields
+                        .insert((owner.clone(), attr_name.to_string()), field_type);
+                }
+                self.enums.push(EnumDef {
+                    name,
+                    values,
+                    aliases,
+                });
+            }
+        }
+    }
+
+    /// Integer `_id` enums become Rust enums; other enums keep their base type
+    fn enum_values(&self, attr_name: &str, attr: &Value) -> Option<EnumValues> {
+        if !attr_name.ends_with("_id") || self.rust_scalar(attr) != "i32" {
+            return None;
+        }
+        let values = attr.get("enum")?.as_object()?;
+        let parsed: EnumValues = values
+            .iter()
+            .filter_map(|(key, value)| {
+                let caption = value.get("caption").and_then(|c| c.as_str()).unwrap_or(key);
+                Some((key.parse().ok()?, caption.to_string()))
+            })
+            .collect();
+        (!parsed.is_empty()).then_some(parsed)
+    }
+
+    fn base_type(&self, data_type: &'a str) -> &'a str {
+        self.types.get(data_type).copied().unwrap_or(data_type)
+    }
+
+    fn rust_scalar(&self, attr: &Value) -> &'static str {
+        let data_type = attr
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or("string_t");
+        match self.base_type(data_type) {
+            "integer_t" => "i32",
+            "long_t" => "i64",
+            "float_t" => "f64",
+            "boolean_t" => "bool",
+            "json_t" => "serde_json::Value",
+            _ => "String",
+        }
+    }
+
+    fn field_type(&self, owner: &Owner, attr_name: &str, attr: &Value) -> String {
+        let item = match attr.get("object_type").and_then(|t| t.as_str()) {
+            Some(object) => match self.objects.get(object) {
+                Some(attrs) if !attrs.is_empty() => {
+                    let name = pascal_case(object);
+                    // Box recursive references (e.g. process.parent_process)
+                    let recursive = owner.class.is_none()
+                        && self
+                            .reachable
+                            .get(object)
+                            .is_some_and(|r| object == owner.name || r.contains(owner.name));
+                    if recursive && !is_array(attr) {
+                        format!("Box<{name}>")
+                    } else {
+                        name
+                    }
+                }
+                _ => "serde_json::Value".to_string(),
+            },
+            None => self
+                .enum_fields
+                .get(&(owner.type_name.clone(), attr_name.to_string()))
+                .cloned()
+                .unwrap_or_else(|| self.rust_scalar(attr).to_string()),
+        };
+
+        if is_array(attr) {
+            format!("Vec<{item}>")
+        } else {
+            item
+        }
+    }
+
+    fn generate(&self, version: &str) -> String {
+        let mut out = String::new();
+        writeln!(out, "// @generated by build.rs from OCSF schema {version}").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "/// OCSF schema version the types were generated from").unwrap();
+        writeln!(out, "pub const SCHEMA_VERSION: &str = {version:?};").unwrap();
+
+        for def in &self.enums {
+            self.write_enum(&mut out, def);
+        }
+        for owner in &self.owners {
+            self.write_struct(&mut out, owner);
+        }
+        out
+    }
+
+    fn write_enum(&self, out: &mut String, def: &EnumDef) {
+        let mut variants: Vec<(i64, String, &str)> = Vec::new();
+        let mut used = BTreeSet::new();
+        for (id, caption) in &def.values {
+            let mut variant = variant_name(caption);
+            if !used.insert(variant.clone()) {
+                variant = format!("{variant}{id}");
+                used.insert(variant.clone());
+            }
+            variants.push((*id, variant, caption));
+        }
+        let name = &def.name;
+
+        writeln!(out).unwrap();
+        writeln!(out, "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]").unwrap();
+        writeln!(out, "pub enum {name} {{").unwrap();
+        for (id, variant, caption) in &variants {
+            writeln!(out, "    /// {} ({id})", doc_text(caption)).unwrap();
+            writeln!(out, "    {variant},").unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+
+        writeln!(out).unwrap();
+        writeln!(out, "impl {name} {{").unwrap();
+        writeln!(out, "    pub const fn id(self) -> i32 {{").unwrap();
+        writeln!(out, "        match self {{").unwrap();
+        for (id, variant, _) in &variants {
+            writeln!(out, "            Self::{variant} => {id},").unwrap();
+        }
+        writeln!(out, "        }}").unwrap();
+        writeln!(out, "    }}").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "    pub const fn caption(self) -> &'static str {{").unwrap();
+        writeln!(out, "        match self {{").unwrap();
+        for (_, variant, caption) in &variants {
+            writeln!(out, "            Self::{variant} => {caption:?},").unwrap();
+        }
+        writeln!(out, "        }}").unwrap();
+        writeln!(out, "    }}").unwrap();
+        writeln!(out, "}}").unwrap();
+
+        writeln!(out).unwrap();
+        writeln!(out, "impl TryFrom<i32> for {name} {{").unwrap();
+        writeln!(out, "    type Error = UnknownEnumValue;").unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "    fn try_from(value: i32) -> Result<Self, UnknownEnumValue> {{"
+        )
+        .unwrap();
+        writeln!(out, "        match value {{").unwrap();
+        for (id, variant, _) in &variants {
+            writeln!(out, "            {id} => Ok(Self::{variant}),").unwrap();
+        }
+        writeln!(
+            out,
+            "            _ => Err(UnknownEnumValue {{ enum_name: {name:?}, value }}),"
+        )
+        .unwrap();
+        writeln!(out, "        }}").unwrap();
+        writeln!(out, "    }}").unwrap();
+        writeln!(out, "}}").unwrap();
+
+        writeln!(out).unwrap();
+        writeln!(out, "impl_id_serde!({name});").unwrap();
+
+        for alias in &def.aliases {
+            writeln!(out, "pub type {alias} = {name};").unwrap();
+        }
+    }
+
+    fn write_struct(&self, out: &mut String, owner: &Owner) {
+        let mut names: Vec<&String> = owner.attributes.keys().collect();
+        names.sort();
+
+        let mut fields = String::new();
+        let mut required_fields = Vec::new();
+        let mut optional_fields = Vec::new();
+        for attr_name in names {
+            let attr = &owner.attributes[attr_name];
+            let ty = self.field_type(owner, attr_name, attr);
+            let required = attr.get("requirement").and_then(|r| r.as_str()) == Some("required")
+                && attr.get("profile").is_none_or(|p| p.is_null())
+                && attr.get("@deprecated").is_none();
+
+            if let Some(caption) = attr.get("caption").and_then(|c| c.as_str()) {
+                writeln!(fields, "    /// {}", doc_text(caption)).unwrap();
+            }
+            let (ident, rename) = field_ident(attr_name);
+            if let Some(rename) = rename {
+                writeln!(fields, "    #[serde(rename = {rename:?})]").unwrap();
+            }
+            if required {
+                writeln!(fields, "    pub {ident}: {ty},").unwrap();
+                required_fields.push((ident, ty));
+            } else {
+                writeln!(
+                    fields,
+                    "    #[serde(default, skip_serializing_if = \"Option::is_none\")]"
+                )
+                .unwrap();
+                writeln!(fields, "    pub {ident}: Option<{ty}>,").unwrap();
+                optional_fields.push(ident);
+            }
+        }
+
+        let name = &owner.type_name;
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "/// {} (`{}`)",
+            doc_text(owner.caption.unwrap_or(owner.name)),
+            owner.name
+        )
+        .unwrap();
+        let default = if required_fields.is_empty() {
+            ", Default"
+        } else {
+            ""
+        };
+        writeln!(
+            out,
+            "#[derive(Debug, Clone, PartialEq{default}, serde::Serialize, serde::Deserialize)]"
+        )
+        .unwrap();
+        writeln!(out, "pub struct {name} {{").unwrap();
+        out.push_str(&fields);
+        writeln!(out, "}}").unwrap();
+
+        if let Some((uid, category_uid)) = owner.class {
+            writeln!(out).unwrap();
+            writeln!(out, "impl OcsfClass for {name} {{").unwrap();
+            writeln!(out, "    const NAME: &'static str = {:?};", owner.name).unwrap();
+            writeln!(out, "    const CLASS_UID: i32 = {uid};").unwrap();
+            writeln!(out, "    const CATEGORY_UID: i32 = {category_uid};").unwrap();
+            writeln!(out, "}}").unwrap();
+        }
+
+        if !required_fields.is_empty() {
+            self.write_constructor(out, owner, &required_fields, &optional_fields);
+        }
+    }
+
+    /// `new()` taking the required attributes; event classes derive their classification
+    fn write_constructor(
+        &self,
+        out: &mut String,
+        owner: &Owner,
+        required: &[(String, String)],
+        optional: &[String],
+    ) {
+        let activity_enum = required
+            .iter()
+            .any(|(ident, ty)| ident == "activity_id" && ty != "i32");
+        let type_uid_long = required
+            .iter()
+            .any(|(ident, ty)| ident == "type_uid" && ty == "i64");
+        let derived = |ident: &str| -> Option<&str> {
+            owner.class?;
+            match ident {
+                "class_uid" => Some("Self::CLASS_UID"),
+                "category_uid" => Some("Self::CATEGORY_UID"),
+                // `type_uid` is `long_t` since 1.1.0 and `integer_t` before
+                "type_uid" if activity_enum && type_uid_long => {
+                    Some("i64::from(Self::CLASS_UID) * 100 + i64::from(activity_id.id())")
+                }
+                "type_uid" if activity_enum => Some("Self::CLASS_UID * 100 + activity_id.id()"),
+                _ => None,
+            }
+        };
+
+        let params: Vec<String> = required
+            .iter()
+            .filter(|(ident, _)| derived(ident).is_none())
+            .map(|(ident, ty)| format!("{ident}: {ty}"))
+            .collect();
+
+        writeln!(out).unwrap();
+        writeln!(out, "impl {} {{", owner.type_name).unwrap();
+        writeln!(out, "    pub fn new({}) -> Self {{", params.join(", ")).unwrap();
+        writeln!(out, "        Self {{").unwrap();
+        for (ident, _) in required {
+            match derived(ident) {
+                Some(expr) => writeln!(out, "            {ident}: {expr},").unwrap(),
+                None => writeln!(out, "            {ident},").unwrap(),
+            }
+        }
+        for ident in optional {
+            writeln!(out, "            {ident}: None,").unwrap();
+        }
+        writeln!(out, "        }}").unwrap();
+        writeln!(out, "    }}").unwrap();
+        writeln!(out, "}}").unwrap();
+    }
+}
+
+fn is_array(attr: &Value) -> bool {
+    attr.get("is_array")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// `win/reg_key` -> `WinRegKey`
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            first.to_string() + chars.as_str()
+        })
+        .collect()
+}
+
+/// `OAUTH 2.0` -> `Oauth20`
+fn variant_name(caption: &str) -> String {
+    let name: String = caption
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let lower = w.to_ascii_lowercase();
+            let mut chars = lower.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            first.to_string() + chars.as_str()
+        })
+        .collect();
+    match name.chars().next() {
+        None => "Value".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("V{name}"),
+        _ if name == "Self" => "SelfValue".to_string(),
+        _ => name,
+    }
+}
+
+/// Rust identifier for an attribute, plus the serde rename when it differs
+fn field_ident(name: &str) -> (String, Option<&str>) {
+    match name {
+        "self" | "super" | "crate" => (format!("{name}_"), Some(name)),
+        _ if KEYWORDS.contains(&name) => (format!("r#{name}"), None),
+        _ => (name.to_string(), None),
+    }
+}
+
+fn doc_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
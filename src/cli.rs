@@ -0,0 +1,94 @@
+use anyhow::Result;
+
+use crate::ocsf::mapping::{UnmappedOptions, UnmappedPolicy};
+use crate::tools::{convert_log_file_impl, ConvertLogFileRequest};
+
+const CONVERT_USAGE: &str = "Usage: ocsf-mcp-server convert --input <file> --output <file> \
+    (--pack <name> | --mapping <file>) [--version <version>] [--skip-invalid] [--max-errors <n>] \
+    [--unmapped keep_all|keep_none|allowlist] [--allow <path>[,<path>...]] [--raw-data]";
+
+/// `convert`: stream a log file through a mapping and print the totals as JSON
+pub async fn convert(args: &[String]) -> Result<()> {
+    let mut request = ConvertLogFileRequest {
+        version: None,
+        mapping: None,
+        pack: None,
+        input_path: String::new(),
+        output_path: String::new(),
+        skip_invalid: None,
+        unmapped: None,
+        max_errors: None,
+    };
+    // Any of the unmapped flags overrides the mapping's `unmapped`, as the tool's parameter does
+    let mut keep = None;
+    let mut unmapped: Option<UnmappedOptions> = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("{} needs a value\n{}", arg, CONVERT_USAGE))
+        };
+        match arg.as_str() {
+            "--input" | "-i" => request.input_path = value()?,
+            "--output" | "-o" => request.output_path = value()?,
+            "--pack" => request.pack = Some(value()?),
+            "--mapping" => {
+                let path = value()?;
+                let mapping = std::fs::read_to_string(&path)
+                    .map_err(|e| anyhow::anyhow!("Cannot read '{}': {}", path, e))?;
+                request.mapping = Some(mapping);
+            }
+            "--version" => request.version = Some(value()?),
+            "--skip-invalid" => request.skip_invalid = Some(true),
+            "--max-errors" => {
+                let count = value()?;
+                request.max_errors = Some(
+                    count
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("Invalid --max-errors '{}'", count))?,
+                );
+            }
+            "--unmapped" => {
+                let policy = value()?;
+                keep = Some(match policy.as_str() {
+                    "keep_all" => UnmappedPolicy::All,
+                    "keep_none" => UnmappedPolicy::None,
+                    "allowlist" => UnmappedPolicy::Allowlist,
+                    _ => anyhow::bail!("Invalid --unmapped '{}'\n{}", policy, CONVERT_USAGE),
+                });
+                unmapped.get_or_insert_default();
+            }
+            "--allow" => {
+                let paths = value()?;
+                unmapped
+                    .get_or_insert_default()
+                    .allow
+                    .extend(paths.split(',').map(|p| p.trim().to_string()));
+            }
+            "--raw-data" => {
+                unmapped.get_or_insert_default().raw_data = true;
+            }
+            "--help" | "-h" => {
+                println!("{}", CONVERT_USAGE);
+                return Ok(());
+            }
+            other => anyhow::bail!("Unknown argument '{}'\n{}", other, CONVERT_USAGE),
+        }
+    }
+    if request.input_path.is_empty() || request.output_path.is_empty() {
+        anyhow::bail!("--input and --output are required\n{}", CONVERT_USAGE);
+    }
+    if let Some(mut unmapped) = unmapped {
+        // `--allow` alone means an allowlist
+        unmapped.keep = keep.unwrap_or(if unmapped.allow.is_empty() {
+            UnmappedPolicy::All
+        } else {
+            UnmappedPolicy::Allowlist
+        });
+        request.unmapped = Some(unmapped);
+    }
+
+    println!("{}", convert_log_file_impl(request).await?);
+    Ok(())
+}
@@ -2,6 +2,7 @@ use anyhow::Result;
 use rmcp::{transport::stdio, ServiceExt};
 use tracing_subscriber::{self, EnvFilter};
 
+mod cli;
 mod ocsf;
 mod templates;
 mod tools;
@@ -17,6 +18,12 @@ async fn main() -> Result<()> {
         .with_ansi(false)
         .init();
 
+    // `convert` runs a one-off file conversion instead of the server
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("convert") {
+        return cli::convert(&args[1..]).await;
+    }
+
     tracing::info!("Starting OCSF MCP Server");
 
     // Create an instance of our OCSF server
@@ -0,0 +1,309 @@
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+use super::event::OcsfEvent;
+use super::schema::{Attribute, EnumValue, EventClass, OcsfSchema};
+use super::validation::{validate_value_with_schema, ErrorType, ValidationReport};
+
+/// Schema-checked construction of an `OcsfEvent`
+///
+/// Attributes are checked against the class (and, for objects, the object) definition and
+/// coerced to their OCSF type. Setting an enum `_id` also sets its caption sibling, and
+/// `type_uid` is derived from `activity_id` unless set explicitly.
+///
+/// ```ignore
+/// let (event, report) = OcsfEventBuilder::new(&schema, "authentication")?
+///     .set("activity_id", "Logon")?
+///     .set("user", json!({"name": "john.doe"}))?
+///     .build()?;
+/// ```
+pub struct OcsfEventBuilder<'a> {
+    schema: &'a OcsfSchema,
+    class: &'a EventClass,
+    event: OcsfEvent,
+    explicit_type_uid: bool,
+}
+
+impl<'a> OcsfEventBuilder<'a> {
+    pub fn new(schema: &'a OcsfSchema, event_class: &str) -> anyhow::Result<Self> {
+        let class = schema
+            .get_event_class(event_class)
+            .ok_or_else(|| anyhow::anyhow!("Event class '{}' not found", event_class))?;
+
+        let mut builder = Self {
+            schema,
+            class,
+            event: OcsfEvent::new(class, &schema.version),
+            explicit_type_uid: false,
+        };
+        for name in ["class_uid", "category_uid", "activity_id", "severity_id"] {
+            builder.set_sibling(name);
+        }
+        Ok(builder)
+    }
+
+    /// Continue building from an existing event (e.g. a synthesized one)
+    pub fn from_event(schema: &'a OcsfSchema, event: OcsfEvent) -> anyhow::Result<Self> {
+        let class = schema
+            .get_event_class_by_uid(event.class_uid)
+            .ok_or_else(|| anyhow::anyhow!("class_uid {} not found", event.class_uid))?;
+
+        Ok(Self {
+            schema,
+            class,
+            event,
+            explicit_type_uid: false,
+        })
+    }
+
+    /// Set a top-level attribute, rejecting unknown attributes and uncoercible values
+    ///
+    /// Classes without attribute definitions (the minimal embedded schema) accept any value.
+    pub fn set(mut self, name: &str, value: impl Into<Value>) -> anyhow::Result<Self> {
+        let value = value.into();
+        let value = if self.class.attributes.is_empty() {
+            value
+        } else {
+            let attr = self.class.attributes.get(name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Attribute '{}' is not defined for event class '{}'",
+                    name,
+                    self.class.name
+                )
+            })?;
+            self.coerce(attr, value, name)?
+        };
+
+        self.event.set_field(name.to_string(), value)?;
+        self.set_sibling(name);
+        if name == "type_uid" {
+            self.explicit_type_uid = true;
+        }
+        Ok(self)
+    }
+
+    /// Set every attribute of a JSON object
+    pub fn set_fields(self, fields: Map<String, Value>) -> anyhow::Result<Self> {
+        fields
+            .into_iter()
+            .try_fold(self, |builder, (name, value)| builder.set(&name, value))
+    }
+
+    /// Finish the event and validate it against the schema, including required attributes
+    pub fn build(mut self) -> anyhow::Result<(OcsfEvent, ValidationReport)> {
+        if !self.explicit_type_uid {
+            let (type_uid, caption) = self.schema.type_uid_for(self.class, self.event.activity_id);
+            self.event.type_uid = type_uid;
+            if let Some(caption) = caption {
+                self.event
+                    .fields
+                    .insert("type_name".to_string(), json!(caption));
+            }
+        }
+
+        let mut report =
+            validate_value_with_schema(&serde_json::to_value(&self.event)?, self.schema);
+
+        let mut names: Vec<&String> = self.class.attributes.keys().collect();
+        names.sort();
+        for name in names {
+            let attr = &self.class.attributes[name];
+            let in_scope = attr
+                .profile
+                .as_ref()
+                .is_none_or(|p| self.event.metadata.profiles.contains(p));
+            if attr.requirement.as_deref() == Some("required")
+                && in_scope
+                && self.event.get_field(name).is_none()
+            {
+                report.add_error(
+                    name.clone(),
+                    format!(
+                        "Missing required attribute '{}' of class '{}'",
+                        name, self.class.name
+                    ),
+                    ErrorType::MissingRequired,
+                );
+            }
+        }
+        if !report.is_valid {
+            report.summary = format!("Validation failed with {} error(s)", report.errors.len());
+        }
+
+        Ok((self.event, report))
+    }
+
+    /// Set the caption sibling of a top-level enum attribute from its current value
+    fn set_sibling(&mut self, name: &str) {
+        let Some(attr) = self.class.attributes.get(name) else {
+            return;
+        };
+        if let Some(value) = self.event.get_field(name) {
+            let mut fields = std::mem::take(&mut self.event.fields);
+            apply_sibling(attr, &value, &mut fields);
+            self.event.fields = fields;
+        }
+    }
+
+    fn coerce(&self, attr: &Attribute, value: Value, path: &str) -> anyhow::Result<Value> {
+        if attr.is_array {
+            let items = match value {
+                Value::Array(items) => items,
+                single => vec![single],
+            };
+            return items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| self.coerce_item(attr, item, &format!("{path}[{i}]")))
+                .collect::<anyhow::Result<Vec<_>>>()
+                .map(Value::Array);
+        }
+        self.coerce_item(attr, value, path)
+    }
+
+    fn coerce_item(&self, attr: &Attribute, value: Value, path: &str) -> anyhow::Result<Value> {
+        if let Some(object_type) = &attr.object_type {
+            return self.coerce_object(object_type, value, path);
+        }
+
+        let data_type = attr.data_type.as_deref().unwrap_or("string_t");
+        let invalid =
+            |value: &Value| anyhow::anyhow!("Cannot use {} as {} for '{}'", value, data_type, path);
+
+        if let Some(values) = &attr.enum_values
+            && matches!(self.schema.base_type(data_type), "integer_t" | "long_t")
+        {
+            return enum_id(values, &value)
+                .ok_or_else(|| anyhow::anyhow!("{} is not a valid value for '{}'", value, path));
+        }
+
+        let coerced = match (self.schema.base_type(data_type), &value) {
+            ("json_t", _) => Some(value.clone()),
+            ("integer_t" | "long_t", _) if data_type == "timestamp_t" => match &value {
+                Value::String(s) => chrono::DateTime::parse_from_rfc3339(s)
+                    .ok()
+                    .map(|dt| json!(dt.timestamp_millis()))
+                    .or_else(|| s.parse::<i64>().ok().map(|n| json!(n))),
+                other => as_integer(other).map(|n| json!(n)),
+            },
+            ("integer_t" | "long_t", Value::String(s)) => {
+                s.trim().parse::<i64>().ok().map(|n| json!(n))
+            }
+            ("integer_t" | "long_t", other) => as_integer(other).map(|n| json!(n)),
+            ("float_t", Value::Number(_)) => Some(value.clone()),
+            ("float_t", Value::String(s)) => s.trim().parse::<f64>().ok().map(|n| json!(n)),
+            ("boolean_t", Value::Bool(_)) => Some(value.clone()),
+            ("boolean_t", Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+                "true" | "yes" | "1" => Some(json!(true)),
+                "false" | "no" | "0" => Some(json!(false)),
+                _ => None,
+            },
+            ("boolean_t", Value::Number(n)) => match n.as_i64() {
+                Some(1) => Some(json!(true)),
+                Some(0) => Some(json!(false)),
+                _ => None,
+            },
+            (_, Value::String(_)) => Some(value.clone()),
+            (_, Value::Number(n)) => Some(json!(n.to_string())),
+            (_, Value::Bool(b)) => Some(json!(b.to_string())),
+            _ => None,
+        };
+
+        coerced.ok_or_else(|| invalid(&value))
+    }
+
+    fn coerce_object(&self, object_type: &str, value: Value, path: &str) -> anyhow::Result<Value> {
+        let Value::Object(fields) = value else {
+            anyhow::bail!("'{}' must be a {} object, got {}", path, object_type, value);
+        };
+        // Generic objects (e.g. `unmapped`) have no attribute definitions
+        let attributes = match self.schema.objects.get(object_type) {
+            Some(object) if !object.attributes.is_empty() => &object.attributes,
+            _ => return Ok(Value::Object(fields)),
+        };
+
+        let mut coerced = Map::new();
+        for (name, value) in fields {
+            let attr = attributes.get(&name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Attribute '{}.{}' is not defined for object '{}'",
+                    path,
+                    name,
+                    object_type
+                )
+            })?;
+            let value = self.coerce(attr, value, &format!("{path}.{name}"))?;
+            coerced.insert(name, value);
+        }
+
+        // Fill enum siblings after all keys so an explicit caption for `Other` survives
+        let mut names: Vec<String> = coerced.keys().cloned().collect();
+        names.sort();
+        for name in names {
+            let value = coerced[&name].clone();
+            apply_sibling(&attributes[&name], &value, &mut coerced);
+        }
+
+        Ok(Value::Object(coerced))
+    }
+}
+
+/// Resolve an enum member from its id or (case-insensitive) caption
+pub(crate) fn enum_id(values: &HashMap<String, EnumValue>, value: &Value) -> Option<Value> {
+    let key = match value {
+        Value::Number(n) => n.as_i64()?.to_string(),
+        Value::String(s) if values.contains_key(s.trim()) => s.trim().to_string(),
+        Value::String(s) => values
+            .iter()
+            .find(|(_, v)| {
+                v.caption
+                    .as_deref()
+                    .is_some_and(|c| c.eq_ignore_ascii_case(s.trim()))
+            })
+            .map(|(k, _)| k.clone())?,
+        _ => return None,
+    };
+    if !values.contains_key(&key) {
+        return None;
+    }
+    key.parse::<i64>().ok().map(|n| json!(n))
+}
+
+fn as_integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n
+            .as_i64()
+            .or_else(|| n.as_f64().filter(|f| f.fract() == 0.0).map(|f| f as i64)),
+        _ => None,
+    }
+}
+
+/// Set the caption sibling for an enum value; `Other` (99) keeps a caller-supplied caption
+fn apply_sibling(attr: &Attribute, value: &Value, fields: &mut Map<String, Value>) {
+    let (Some(values), Some(sibling)) = (&attr.enum_values, &attr.sibling) else {
+        return;
+    };
+    if value.as_i64() == Some(99) && fields.contains_key(sibling) {
+        return;
+    }
+
+    let caption = |v: &Value| {
+        let key = match v {
+            Value::Number(n) => n.to_string(),
+            Value::String(s) => s.clone(),
+            _ => return None,
+        };
+        values.get(&key).and_then(|e| e.caption.clone())
+    };
+    let sibling_value = match value {
+        Value::Array(items) => items
+            .iter()
+            .map(caption)
+            .collect::<Option<Vec<_>>>()
+            .map(|c| json!(c)),
+        single => caption(single).map(|c| json!(c)),
+    };
+    if let Some(sibling_value) = sibling_value {
+        fields.insert(sibling.clone(), sibling_value);
+    }
+}
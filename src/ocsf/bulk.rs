@@ -0,0 +1,177 @@
+use rand::distr::weighted::WeightedIndex;
+use rand::distr::Distribution;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use super::event::OcsfEvent;
+use super::schema::OcsfSchema;
+use super::synthetic::{random_uuid, EventSynthesizer, SyntheticOptions};
+
+/// Default time window start (2025-01-01T00:00:00Z) so unseeded runs never depend on the clock
+pub const DEFAULT_WINDOW_START_MS: i64 = 1_735_689_600_000;
+/// Default time window length (24 hours)
+pub const DEFAULT_WINDOW_MS: i64 = 86_400_000;
+
+/// Options for reproducible bulk event generation
+#[derive(Debug, Clone)]
+pub struct BulkOptions {
+    pub classes: Vec<String>,
+    /// Relative weight per class (same order as `classes`); uniform when empty
+    pub weights: Vec<f64>,
+    pub count: usize,
+    pub seed: u64,
+    /// Inclusive start of the event time window (epoch milliseconds)
+    pub start_ms: i64,
+    /// Exclusive end of the event time window (epoch milliseconds)
+    pub end_ms: i64,
+    pub profiles: Vec<String>,
+}
+
+impl Default for BulkOptions {
+    fn default() -> Self {
+        Self {
+            classes: Vec::new(),
+            weights: Vec::new(),
+            count: 0,
+            seed: 0,
+            start_ms: DEFAULT_WINDOW_START_MS,
+            end_ms: DEFAULT_WINDOW_START_MS + DEFAULT_WINDOW_MS,
+            profiles: Vec::new(),
+        }
+    }
+}
+
+/// Totals for a bulk generation run
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BulkSummary {
+    pub generated: usize,
+    pub seed: u64,
+    pub per_class: BTreeMap<String, usize>,
+}
+
+impl BulkSummary {
+    /// Count one generated event under its class name
+    pub fn record(&mut self, schema: &OcsfSchema, event: &OcsfEvent) {
+        let class = schema
+            .get_event_class_by_uid(event.class_uid)
+            .map_or_else(|| event.class_uid.to_string(), |ec| ec.name.clone());
+        self.generated += 1;
+        *self.per_class.entry(class).or_default() += 1;
+    }
+}
+
+/// Iterator over seeded synthetic events with monotonically increasing timestamps
+///
+/// The same schema, options and seed always yield the same events in the same order,
+/// including `metadata.uid` and every timestamp.
+pub struct BulkGenerator<'a> {
+    schema: &'a OcsfSchema,
+    options: BulkOptions,
+    rng: ChaCha8Rng,
+    distribution: WeightedIndex<f64>,
+    index: usize,
+}
+
+impl<'a> BulkGenerator<'a> {
+    pub fn new(schema: &'a OcsfSchema, options: BulkOptions) -> anyhow::Result<Self> {
+        if options.classes.is_empty() {
+            anyhow::bail!("At least one event class is required");
+        }
+        for class in &options.classes {
+            if schema.get_event_class(class).is_none() {
+                anyhow::bail!("Event class '{}' not found", class);
+            }
+        }
+        if options.end_ms <= options.start_ms {
+            anyhow::bail!("Time window end must be after its start");
+        }
+
+        let weights = if options.weights.is_empty() {
+            vec![1.0; options.classes.len()]
+        } else if options.weights.len() == options.classes.len() {
+            options.weights.clone()
+        } else {
+            anyhow::bail!(
+                "Got {} weights for {} event classes",
+                options.weights.len(),
+                options.classes.len()
+            );
+        };
+        let distribution = WeightedIndex::new(&weights)
+            .map_err(|e| anyhow::anyhow!("Invalid class weights: {}", e))?;
+
+        Ok(Self {
+            schema,
+            rng: ChaCha8Rng::seed_from_u64(options.seed),
+            options,
+            distribution,
+            index: 0,
+        })
+    }
+
+    /// Spread events evenly across the window, with jitter inside each slot
+    fn next_time_ms(&mut self) -> i64 {
+        let span = (self.options.end_ms - self.options.start_ms) as f64;
+        let slot = span / self.options.count as f64;
+        let offset = slot * self.index as f64 + self.rng.random_range(0.0..1.0) * slot;
+        self.options.start_ms + offset as i64
+    }
+
+    /// Write all remaining events as NDJSON, also returning the first `sample_size` of them
+    pub fn write_ndjson_sampled<W: Write>(
+        &mut self,
+        writer: &mut W,
+        sample_size: usize,
+    ) -> anyhow::Result<(BulkSummary, Vec<OcsfEvent>)> {
+        let mut summary = BulkSummary {
+            seed: self.options.seed,
+            ..Default::default()
+        };
+        let mut sample = Vec::new();
+        let schema = self.schema;
+        for event in self.by_ref() {
+            let event = event?;
+            serde_json::to_writer(&mut *writer, &event)?;
+            writer.write_all(b"\n")?;
+            summary.record(schema, &event);
+            if sample.len() < sample_size {
+                sample.push(event);
+            }
+        }
+        writer.flush()?;
+        Ok((summary, sample))
+    }
+}
+
+impl Iterator for BulkGenerator<'_> {
+    type Item = anyhow::Result<OcsfEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.options.count {
+            return None;
+        }
+
+        let class = self.options.classes[self.distribution.sample(&mut self.rng)].clone();
+        let time_ms = self.next_time_ms();
+        self.index += 1;
+
+        let synthesizer = EventSynthesizer::new(
+            self.schema,
+            SyntheticOptions {
+                profiles: self.options.profiles.clone(),
+                time_ms,
+                ..Default::default()
+            },
+        );
+        let event = synthesizer
+            .generate(&class, &mut self.rng)
+            .map(|mut event| {
+                event.metadata.uid = Some(random_uuid(&mut self.rng));
+                event
+            });
+        Some(event)
+    }
+}
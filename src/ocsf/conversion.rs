@@ -0,0 +1,614 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use super::mapping::{lookup, MappingDefinition, MappingEngine};
+use super::parsers::{audit_event_id, parse_input, parse_line, LogFormat, ParsedRecord};
+use super::schema::OcsfSchema;
+
+/// Record errors kept in a conversion summary by default; later ones are only counted
+pub const DEFAULT_MAX_ERRORS: usize = 20;
+
+/// Largest record text (a line, or the lines of one document or event) held in memory; a
+/// larger record is yielded as an error and skipped
+pub const MAX_RECORD_BYTES: usize = 16 * 1024 * 1024;
+
+const EVENT_END: &str = "</Event>";
+
+/// Compression of a log file
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Recognize gzip and zstd data by their magic bytes
+    pub fn detect(header: &[u8]) -> Self {
+        if header.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if header.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    /// The compression a path's extension asks for: `.gz` or `.zst`
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst" | "zstd") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// Open a log file, decompressing gzip (concatenated members included) and zstd
+pub fn open_log(path: &Path) -> anyhow::Result<(Box<dyn BufRead + Send>, Compression)> {
+    let file =
+        File::open(path).map_err(|e| anyhow::anyhow!("Cannot open '{}': {}", path.display(), e))?;
+    let mut reader = BufReader::new(file);
+    let compression = Compression::detect(reader.fill_buf()?);
+    let reader: Box<dyn BufRead + Send> = match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader))),
+        Compression::Zstd => Box::new(BufReader::new(zstd::stream::read::Decoder::with_buffer(
+            reader,
+        )?)),
+    };
+    Ok((reader, compression))
+}
+
+/// A record read from a log stream
+#[derive(Debug, Clone)]
+pub struct StreamRecord {
+    /// 1-based line the record starts on
+    pub line: usize,
+    pub record: Value,
+    /// The text the record was parsed from; `None` for JSON, which is mapped as is
+    pub raw: Option<String>,
+}
+
+/// A record that could not be read or mapped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Reads the records of a log stream one at a time
+///
+/// Only the record being read is held in memory: a line, the lines of one auditd event or
+/// Windows `<Event>`, or one JSON document, and none larger than `MAX_RECORD_BYTES`. A JSON
+/// array document is parsed whole before its items are yielded, so an export such as
+/// CloudTrail's `{"Records": [...]}` must fit the limit too. CSV rows are read under the first row and Zeek TSV
+/// rows under the latest `#` header. JSON is NDJSON or pretty-printed documents (an object or
+/// array whose first line ends with its opening bracket), unwrapped from `records_path`. A
+/// record that fails to parse is yielded as an error and reading goes on.
+pub struct RecordReader<R> {
+    reader: R,
+    format: LogFormat,
+    records_path: Option<String>,
+    line: usize,
+    /// The CSV header row, or the Zeek directives of the current log
+    header: String,
+    /// A record spanning lines: the line it starts on and its text so far
+    pending: Option<(usize, String)>,
+    /// The auditd event being collected in `pending`
+    event: Option<String>,
+    max_record_bytes: usize,
+    /// Lines of an oversized document or event are being skipped up to its end
+    discarding: bool,
+    queue: VecDeque<Result<StreamRecord, RecordError>>,
+    error: Option<io::Error>,
+    done: bool,
+}
+
+impl<R: BufRead> RecordReader<R> {
+    pub fn new(reader: R, format: LogFormat, records_path: Option<String>) -> Self {
+        Self {
+            reader,
+            format,
+            records_path,
+            line: 0,
+            header: String::new(),
+            pending: None,
+            event: None,
+            max_record_bytes: MAX_RECORD_BYTES,
+            discarding: false,
+            queue: VecDeque::new(),
+            error: None,
+            done: false,
+        }
+    }
+
+    /// Override `MAX_RECORD_BYTES`
+    #[allow(dead_code)]
+    pub fn with_max_record_bytes(mut self, max_record_bytes: usize) -> Self {
+        self.max_record_bytes = max_record_bytes;
+        self
+    }
+
+    /// Lines read so far
+    pub fn lines_read(&self) -> usize {
+        self.line
+    }
+
+    /// The read error that ended the stream early, if any
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+
+    /// The next line without its line ending; invalid UTF-8 is replaced rather than failing
+    ///
+    /// A line over the record limit fails (with the record it belongs to) and reads as empty.
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut buffer = Vec::new();
+        let limit = self.max_record_bytes as u64 + 1;
+        if io::Read::take(&mut self.reader, limit).read_until(b'\n', &mut buffer)? == 0 {
+            return Ok(None);
+        }
+        self.line += 1;
+        if buffer.len() as u64 == limit && !buffer.ends_with(b"\n") {
+            // Skip the rest of the line without holding it
+            loop {
+                let available = self.reader.fill_buf()?;
+                if available.is_empty() {
+                    break;
+                }
+                let (used, found) = match available.iter().position(|&b| b == b'\n') {
+                    Some(i) => (i + 1, true),
+                    None => (available.len(), false),
+                };
+                self.reader.consume(used);
+                if found {
+                    break;
+                }
+            }
+            let start = self.pending.as_ref().map_or(self.line, |(start, _)| *start);
+            self.oversized(start);
+            return Ok(Some(String::new()));
+        }
+        let line = String::from_utf8_lossy(&buffer);
+        Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+    }
+
+    /// Read until a record is queued or the input ends
+    fn fill(&mut self) -> io::Result<()> {
+        while self.queue.is_empty() && !self.done {
+            match self.read_line()? {
+                Some(line) => self.push_line(line),
+                None => {
+                    self.done = true;
+                    self.flush();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fail the record starting at `start` for exceeding the limit, and skip what is left of
+    /// a multi-line one
+    fn oversized(&mut self, start: usize) {
+        self.discarding = self.pending.take().is_some()
+            && matches!(self.format, LogFormat::Json | LogFormat::WindowsXml);
+        self.event = None;
+        self.fail(
+            start,
+            format!(
+                "Record is larger than {} bytes and was skipped",
+                self.max_record_bytes
+            ),
+        );
+    }
+
+    fn push_line(&mut self, line: String) {
+        let number = self.line;
+        if self.discarding {
+            // Pretty-printed documents close at the start of a line
+            self.discarding = match self.format {
+                LogFormat::WindowsXml => !line.contains(EVENT_END),
+                _ => !line.starts_with(['}', ']']),
+            };
+            return;
+        }
+        if self.format == LogFormat::Json {
+            return self.push_json(number, line);
+        }
+        if self.format == LogFormat::WindowsXml {
+            return self.push_windows(number, line);
+        }
+        if line.trim().is_empty() {
+            return;
+        }
+        match self.format {
+            LogFormat::Csv if self.header.is_empty() => self.header = line,
+            LogFormat::Csv => {
+                let row = format!("{}\n{}", self.header, line);
+                self.push_parsed(number, parse_input(&row, Some(LogFormat::Csv)));
+            }
+            LogFormat::Auditd => {
+                let Some(event) = audit_event_id(&line) else {
+                    return self.fail(number, "Not an auditd record".to_string());
+                };
+                if self.event.as_ref() == Some(&event)
+                    && let Some((start, text)) = &mut self.pending
+                {
+                    text.push('\n');
+                    text.push_str(&line);
+                    if text.len() > self.max_record_bytes {
+                        let start = *start;
+                        self.oversized(start);
+                    }
+                    return;
+                }
+                self.flush();
+                self.event = Some(event);
+                self.pending = Some((number, line));
+            }
+            // `#separator` starts a new log
+            LogFormat::Zeek if line.starts_with("#separator") => self.header = line + "\n",
+            LogFormat::Zeek if line.starts_with('#') => {
+                self.header.push_str(&line);
+                self.header.push('\n');
+            }
+            LogFormat::Zeek if line.trim_start().starts_with('{') => {
+                self.push_parsed(number, parse_line(LogFormat::Zeek, &line).map(|r| vec![r]));
+            }
+            LogFormat::Zeek if !self.header.contains("#fields") => {
+                self.fail(number, "Zeek data before a #fields header".to_string())
+            }
+            LogFormat::Zeek => {
+                let row = format!("{}{}", self.header, line);
+                self.push_parsed(number, parse_input(&row, Some(LogFormat::Zeek)));
+            }
+            format => self.push_parsed(number, parse_line(format, &line).map(|r| vec![r])),
+        }
+    }
+
+    fn push_json(&mut self, number: usize, line: String) {
+        if let Some((start, mut text)) = self.pending.take() {
+            text.push('\n');
+            text.push_str(&line);
+            if text.len() > self.max_record_bytes {
+                self.pending = Some((start, text));
+                return self.oversized(start);
+            }
+            // Pretty-printed documents close at the start of a line
+            if !line.starts_with(['}', ']']) {
+                self.pending = Some((start, text));
+                return;
+            }
+            match serde_json::from_str(&text) {
+                Ok(document) => self.push_document(start, document),
+                Err(e) if e.is_eof() => self.pending = Some((start, text)),
+                Err(e) => self.fail(start, format!("Invalid JSON: {}", e)),
+            }
+            return;
+        }
+        if line.trim().is_empty() {
+            return;
+        }
+        match serde_json::from_str(&line) {
+            Ok(document) => self.push_document(number, document),
+            Err(e) if e.is_eof() && line.trim_end().ends_with(['{', '[']) => {
+                self.pending = Some((number, line))
+            }
+            Err(e) => self.fail(number, format!("Invalid JSON: {}", e)),
+        }
+    }
+
+    /// Queue the records of a JSON document: an array's items, each unwrapped from `records_path`
+    fn push_document(&mut self, line: usize, document: Value) {
+        let documents = match document {
+            Value::Array(items) => items,
+            document => vec![document],
+        };
+        for document in documents {
+            let records = match self.records_path.as_deref().map(|p| lookup(&document, p)) {
+                Some(Some(Value::Array(inner))) => inner.clone(),
+                _ => vec![document],
+            };
+            self.queue.extend(records.into_iter().map(|record| {
+                Ok(StreamRecord {
+                    line,
+                    record,
+                    raw: None,
+                })
+            }));
+        }
+    }
+
+    /// Collect `<Event>` elements, which may span lines or share one; JSON renderings are read
+    /// a line at a time, and what lies outside events (declarations, the `<Events>` root) is
+    /// skipped
+    fn push_windows(&mut self, number: usize, line: String) {
+        let (start, mut text, offset) = match self.pending.take() {
+            Some((start, mut text)) => {
+                text.push('\n');
+                (start, text, 0)
+            }
+            None if line.trim_start().starts_with('{') => {
+                let record = parse_line(LogFormat::WindowsXml, &line).map(|r| vec![r]);
+                return self.push_parsed(number, record);
+            }
+            None => match event_start(&line) {
+                // Padding keeps the columns of error positions
+                Some(at) => (number, " ".repeat(at), at),
+                None => return,
+            },
+        };
+        let rest = &line[offset..];
+        let Some(end) = rest.rfind(EVENT_END).map(|i| i + EVENT_END.len()) else {
+            text.push_str(rest);
+            let oversized = text.len() > self.max_record_bytes;
+            self.pending = Some((start, text));
+            if oversized {
+                self.oversized(start);
+            }
+            return;
+        };
+        text.push_str(&rest[..end]);
+        self.push_parsed(start, parse_input(&text, Some(LogFormat::WindowsXml)));
+        // Another event may open on the line the last one closed on
+        if let Some(at) = event_start(&rest[end..]) {
+            let at = offset + end + at;
+            self.pending = Some((number, format!("{}{}", " ".repeat(at), &line[at..])));
+        }
+    }
+
+    /// Parse the record spanning lines, if any
+    fn flush(&mut self) {
+        let Some((start, text)) = self.pending.take() else {
+            return;
+        };
+        self.event = None;
+        match self.format {
+            LogFormat::Json => match serde_json::from_str(&text) {
+                Ok(document) => self.push_document(start, document),
+                Err(e) => self.fail(start, format!("Invalid JSON: {}", e)),
+            },
+            format => self.push_parsed(start, parse_input(&text, Some(format))),
+        }
+    }
+
+    /// Queue parsed records; `start` is the line their text starts on (for CSV and Zeek rows,
+    /// the line of the row itself, as the header in front of it is only context)
+    fn push_parsed(&mut self, start: usize, parsed: anyhow::Result<Vec<ParsedRecord>>) {
+        let records = match parsed {
+            Ok(records) => records,
+            Err(e) => return self.fail(start, e.to_string()),
+        };
+        let in_context = matches!(self.format, LogFormat::Csv | LogFormat::Zeek);
+        self.queue.extend(records.into_iter().map(|record| {
+            Ok(StreamRecord {
+                line: if in_context {
+                    start
+                } else {
+                    start + record.line - 1
+                },
+                record: record.to_record(),
+                raw: Some(record.raw),
+            })
+        }));
+    }
+
+    fn fail(&mut self, line: usize, message: String) {
+        self.queue.push_back(Err(RecordError { line, message }));
+    }
+}
+
+impl<R: BufRead> Iterator for RecordReader<R> {
+    type Item = Result<StreamRecord, RecordError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.fill() {
+            self.error = Some(e);
+            self.done = true;
+            self.pending = None;
+        }
+        self.queue.pop_front()
+    }
+}
+
+/// Where the first `<Event>` element of a line opens
+fn event_start(line: &str) -> Option<usize> {
+    ["<Event>", "<Event "]
+        .iter()
+        .filter_map(|tag| line.find(tag))
+        .min()
+}
+
+/// Options for converting a log stream
+#[derive(Debug, Clone)]
+pub struct ConvertOptions {
+    /// Leave events that fail validation out of the output; they are still counted
+    pub skip_invalid: bool,
+    /// Record errors kept in the summary; later ones are only counted
+    pub max_errors: usize,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            skip_invalid: false,
+            max_errors: DEFAULT_MAX_ERRORS,
+        }
+    }
+}
+
+/// Totals for a conversion run
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ConversionSummary {
+    /// Compression detected on the input
+    pub input_compression: Compression,
+    pub line_count: usize,
+    pub record_count: usize,
+    /// Records mapped to an event, valid or not
+    pub converted_count: usize,
+    /// Converted events that failed validation
+    pub invalid_count: usize,
+    /// Records that could not be parsed or mapped
+    pub failed_count: usize,
+    /// Records left out because no class rule matched a `skip_unmatched` mapping
+    pub skipped_count: usize,
+    /// Events written to the output
+    pub written_count: usize,
+    /// Source fields no rule mapped, over all converted records
+    pub unmapped_field_count: usize,
+    /// Converted events per class name
+    pub per_class: BTreeMap<String, usize>,
+    /// The first record errors, in input order
+    pub errors: Vec<RecordError>,
+}
+
+/// Map every record of a log stream, writing the events as NDJSON
+///
+/// Records are read in the mapping's `format` (JSON when it has none) and written as they are
+/// mapped, so memory use is bounded by the largest record rather than the input; records over
+/// `MAX_RECORD_BYTES` fail without being held.
+pub fn convert_stream<R: BufRead, W: Write>(
+    schema: &OcsfSchema,
+    definition: &MappingDefinition,
+    reader: R,
+    writer: &mut W,
+    options: &ConvertOptions,
+) -> anyhow::Result<ConversionSummary> {
+    let engine = MappingEngine::new(schema, definition)?;
+    let format = definition.format.unwrap_or(LogFormat::Json);
+    let mut records = RecordReader::new(reader, format, definition.records_path.clone());
+    let mut summary = ConversionSummary::default();
+
+    for source in records.by_ref() {
+        summary.record_count += 1;
+        let mapped = source.and_then(|source| {
+            if engine.skips(&source.record) {
+                return Ok(None);
+            }
+            engine
+                .apply_source(&source.record, source.raw.as_deref())
+                .map(Some)
+                .map_err(|e| RecordError {
+                    line: source.line,
+                    message: e.to_string(),
+                })
+        });
+        let mapped = match mapped {
+            Ok(Some(mapped)) => mapped,
+            Ok(None) => {
+                summary.skipped_count += 1;
+                continue;
+            }
+            Err(error) => {
+                summary.failed_count += 1;
+                if summary.errors.len() < options.max_errors {
+                    summary.errors.push(error);
+                }
+                continue;
+            }
+        };
+
+        summary.converted_count += 1;
+        summary.unmapped_field_count += mapped.unmapped_count;
+        let class = schema
+            .get_event_class_by_uid(mapped.event.class_uid)
+            .map_or_else(|| mapped.event.class_uid.to_string(), |ec| ec.name.clone());
+        *summary.per_class.entry(class).or_default() += 1;
+        if !mapped.validation.is_valid {
+            summary.invalid_count += 1;
+            if options.skip_invalid {
+                continue;
+            }
+        }
+        serde_json::to_writer(&mut *writer, &mapped.event)?;
+        writer.write_all(b"\n")?;
+        summary.written_count += 1;
+    }
+
+    summary.line_count = records.lines_read();
+    if let Some(e) = records.take_error() {
+        anyhow::bail!("Read error after line {}: {}", summary.line_count, e);
+    }
+    writer.flush()?;
+    Ok(summary)
+}
+
+/// Convert a log file to an OCSF NDJSON file, compressed when its path ends in `.gz` or `.zst`
+pub fn convert_file(
+    schema: &OcsfSchema,
+    definition: &MappingDefinition,
+    input: &Path,
+    output: &Path,
+    options: &ConvertOptions,
+) -> anyhow::Result<ConversionSummary> {
+    let (reader, compression) = open_log(input)?;
+    // Creating the output would truncate the input before it is read
+    if output.exists() && output.canonicalize()? == input.canonicalize()? {
+        anyhow::bail!("The output path is the input file");
+    }
+    let mut writer = Output::create(output)?;
+    let mut summary = convert_stream(schema, definition, reader, &mut writer, options)?;
+    writer.finish()?;
+    summary.input_compression = compression;
+    Ok(summary)
+}
+
+/// An output file, compressed as its extension asks
+enum Output {
+    Plain(BufWriter<File>),
+    Gzip(flate2::write::GzEncoder<BufWriter<File>>),
+    Zstd(zstd::stream::write::Encoder<'static, BufWriter<File>>),
+}
+
+impl Output {
+    fn create(path: &Path) -> anyhow::Result<Self> {
+        let file = File::create(path)
+            .map_err(|e| anyhow::anyhow!("Cannot create '{}': {}", path.display(), e))?;
+        let file = BufWriter::new(file);
+        Ok(match Compression::from_path(path) {
+            Compression::None => Output::Plain(file),
+            Compression::Gzip => Output::Gzip(flate2::write::GzEncoder::new(
+                file,
+                flate2::Compression::default(),
+            )),
+            Compression::Zstd => Output::Zstd(zstd::stream::write::Encoder::new(file, 0)?),
+        })
+    }
+
+    /// Write the compressed stream's trailer and flush the file
+    fn finish(self) -> io::Result<()> {
+        let mut file = match self {
+            Output::Plain(file) => file,
+            Output::Gzip(encoder) => encoder.finish()?,
+            Output::Zstd(encoder) => encoder.finish()?,
+        };
+        file.flush()
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Plain(file) => file.write(buf),
+            Output::Gzip(encoder) => encoder.write(buf),
+            Output::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Plain(file) => file.flush(),
+            Output::Gzip(encoder) => encoder.flush(),
+            Output::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
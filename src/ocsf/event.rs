@@ -1,10 +1,31 @@
 use serde::{Deserialize, Serialize};
-use serde_json::{Map, Value};
+use serde_json::{json, Map, Value};
+
+use super::schema::EventClass;
+
+/// Top-level attributes that `OcsfEvent` stores as typed fields instead of in `fields`
+const ENVELOPE_ATTRIBUTES: &[&str] = &[
+    "class_uid",
+    "category_uid",
+    "type_uid",
+    "activity_id",
+    "severity_id",
+];
 
 /// Generic OCSF Event structure
+///
+/// The classification attributes every class inherits from `base_event` are typed fields;
+/// all other attributes live in `fields`. Use `set_field` rather than inserting into
+/// `fields` directly so classification attributes land in the right place.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OcsfEvent {
-    /// Metadata about the event class
+    pub class_uid: u32,
+    pub category_uid: u32,
+    /// `class_uid * 100 + activity_id`
+    pub type_uid: i64,
+    pub activity_id: i64,
+    pub severity_id: i64,
+    /// The schema's `metadata` object
     pub metadata: EventMetadata,
     /// Event-specific fields
     #[serde(flatten)]
@@ -13,39 +34,112 @@ pub struct OcsfEvent {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventMetadata {
+    /// OCSF schema version the event conforms to
     pub version: String,
-    pub product: Option<ProductInfo>,
-    pub uid: String,
-    pub event_class: String,
-    pub category_uid: u32,
-    pub class_uid: u32,
+    pub product: ProductInfo,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+    /// Time the event was logged (epoch milliseconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logged_time: Option<i64>,
+    /// Profiles the event's attributes are drawn from
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_uid: Option<String>,
+    /// Other `metadata` attributes (e.g. `log_name`, `tenant_uid`)
+    #[serde(flatten)]
+    pub fields: Map<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProductInfo {
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub vendor_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
 }
 
+impl Default for ProductInfo {
+    /// This server, for events it creates itself
+    fn default() -> Self {
+        Self {
+            name: "ocsf-mcp".to_string(),
+            vendor_name: "ocsf-mcp".to_string(),
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        }
+    }
+}
+
 impl OcsfEvent {
-    pub fn new(event_class: &str, class_uid: u32, category_uid: u32) -> Self {
+    /// Create an event of `event_class` for the given schema version
+    ///
+    /// Activity and severity start as `0` (Unknown) with a matching `type_uid`.
+    pub fn new(event_class: &EventClass, version: &str) -> Self {
         use uuid::Uuid;
         Self {
+            class_uid: event_class.uid,
+            category_uid: event_class.resolved_category_uid(),
+            type_uid: i64::from(event_class.uid) * 100,
+            activity_id: 0,
+            severity_id: 0,
             metadata: EventMetadata {
-                version: "1.7.0-dev".to_string(),
-                product: None,
-                uid: Uuid::new_v4().to_string(),
-                event_class: event_class.to_string(),
-                category_uid,
-                class_uid,
+                version: version.to_string(),
+                product: ProductInfo::default(),
+                uid: Some(Uuid::new_v4().to_string()),
+                logged_time: None,
+                profiles: Vec::new(),
+                correlation_uid: None,
+                fields: Map::new(),
             },
             fields: Map::new(),
         }
     }
 
-    pub fn set_field(&mut self, key: String, value: Value) {
-        self.fields.insert(key, value);
+    /// Set a top-level attribute, routing classification attributes and `metadata`
+    /// to their typed fields
+    pub fn set_field(&mut self, key: String, value: Value) -> anyhow::Result<()> {
+        if ENVELOPE_ATTRIBUTES.contains(&key.as_str()) {
+            let n = value
+                .as_i64()
+                .ok_or_else(|| anyhow::anyhow!("'{}' must be an integer, got {}", key, value))?;
+            match key.as_str() {
+                "class_uid" => self.class_uid = u32::try_from(n)?,
+                "category_uid" => self.category_uid = u32::try_from(n)?,
+                "type_uid" => self.type_uid = n,
+                "activity_id" => self.activity_id = n,
+                _ => self.severity_id = n,
+            }
+        } else if key == "metadata" {
+            // Merge so a partial metadata object keeps the version and product
+            let Value::Object(values) = value else {
+                anyhow::bail!("'metadata' must be an object");
+            };
+            let mut metadata = serde_json::to_value(&self.metadata)?;
+            if let Some(current) = metadata.as_object_mut() {
+                current.extend(values);
+            }
+            self.metadata = serde_json::from_value(metadata)
+                .map_err(|e| anyhow::anyhow!("Invalid metadata: {}", e))?;
+        } else {
+            self.fields.insert(key, value);
+        }
+        Ok(())
+    }
+
+    /// Look up a top-level attribute, including the typed classification attributes
+    pub fn get_field(&self, key: &str) -> Option<Value> {
+        match key {
+            "class_uid" => Some(json!(self.class_uid)),
+            "category_uid" => Some(json!(self.category_uid)),
+            "type_uid" => Some(json!(self.type_uid)),
+            "activity_id" => Some(json!(self.activity_id)),
+            "severity_id" => Some(json!(self.severity_id)),
+            "metadata" => serde_json::to_value(&self.metadata).ok(),
+            _ => self.fields.get(key).cloned(),
+        }
     }
 
     pub fn to_json(&self) -> anyhow::Result<String> {
@@ -65,22 +159,33 @@ pub struct EventExample {
 impl EventExample {
     pub fn authentication_success() -> Self {
         let json = r#"{
+  "class_uid": 3002,
+  "class_name": "Authentication",
+  "category_uid": 3,
+  "category_name": "Identity & Access Management",
+  "activity_id": 1,
+  "activity_name": "Logon",
+  "type_uid": 300201,
+  "type_name": "Authentication: Logon",
+  "severity_id": 1,
+  "severity": "Informational",
+  "time": 1736937000000,
   "metadata": {
-    "version": "1.7.0-dev",
-    "event_class": "authentication",
-    "category_uid": 3,
-    "class_uid": 3002,
+    "version": "1.4.0",
+    "product": {
+      "name": "Gatekeeper IdP",
+      "vendor_name": "Globex"
+    },
     "uid": "123e4567-e89b-12d3-a456-426614174000"
   },
-  "time": "2025-01-15T10:30:00Z",
   "user": {
     "name": "john.doe",
     "uid": "1001"
   },
-  "auth_protocol": "OAuth2",
-  "status": "success",
-  "activity_id": 1,
-  "activity_name": "Logon"
+  "auth_protocol_id": 6,
+  "auth_protocol": "OAUTH 2.0",
+  "status_id": 1,
+  "status": "Success"
 }"#;
 
         Self {
@@ -93,24 +198,34 @@ impl EventExample {
 
     pub fn authentication_failure() -> Self {
         let json = r#"{
+  "class_uid": 3002,
+  "class_name": "Authentication",
+  "category_uid": 3,
+  "category_name": "Identity & Access Management",
+  "activity_id": 1,
+  "activity_name": "Logon",
+  "type_uid": 300201,
+  "type_name": "Authentication: Logon",
+  "severity_id": 3,
+  "severity": "Medium",
+  "time": 1736937300000,
   "metadata": {
-    "version": "1.7.0-dev",
-    "event_class": "authentication",
-    "category_uid": 3,
-    "class_uid": 3002,
+    "version": "1.4.0",
+    "product": {
+      "name": "Active Directory",
+      "vendor_name": "Microsoft"
+    },
     "uid": "223e4567-e89b-12d3-a456-426614174001"
   },
-  "time": "2025-01-15T10:35:00Z",
   "user": {
     "name": "attacker",
     "uid": "unknown"
   },
+  "auth_protocol_id": 99,
   "auth_protocol": "LDAP",
-  "status": "failure",
-  "status_detail": "Invalid credentials",
-  "activity_id": 1,
-  "activity_name": "Logon",
-  "severity": "medium"
+  "status_id": 2,
+  "status": "Failure",
+  "status_detail": "Invalid credentials"
 }"#;
 
         Self {
@@ -123,25 +238,44 @@ impl EventExample {
 
     pub fn process_start() -> Self {
         let json = r#"{
+  "class_uid": 1007,
+  "class_name": "Process Activity",
+  "category_uid": 1,
+  "category_name": "System Activity",
+  "activity_id": 1,
+  "activity_name": "Launch",
+  "type_uid": 100701,
+  "type_name": "Process Activity: Launch",
+  "severity_id": 1,
+  "severity": "Informational",
+  "time": 1736938800000,
   "metadata": {
-    "version": "1.7.0-dev",
-    "event_class": "process_activity",
-    "category_uid": 1,
-    "class_uid": 1007,
+    "version": "1.4.0",
+    "product": {
+      "name": "Auditd",
+      "vendor_name": "Linux"
+    },
     "uid": "323e4567-e89b-12d3-a456-426614174002"
   },
-  "time": "2025-01-15T11:00:00Z",
+  "device": {
+    "hostname": "web-01",
+    "type_id": 1
+  },
   "process": {
     "name": "nginx",
     "pid": 1234,
     "uid": "501",
-    "cmd_line": "/usr/sbin/nginx -c /etc/nginx/nginx.conf"
+    "cmd_line": "/usr/sbin/nginx -c /etc/nginx/nginx.conf",
+    "parent_process": {
+      "name": "systemd",
+      "pid": 1
+    }
   },
-  "activity_id": 1,
-  "activity_name": "Launch",
-  "parent_process": {
-    "name": "systemd",
-    "pid": 1
+  "actor": {
+    "process": {
+      "name": "systemd",
+      "pid": 1
+    }
   }
 }"#;
 
@@ -0,0 +1,370 @@
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead};
+use std::path::Path;
+
+use super::conversion::{open_log, MAX_RECORD_BYTES};
+use super::schema::OcsfSchema;
+use super::validation::{validate_event_with_schema, ValidationReport};
+
+/// Failing examples kept per rule by default
+pub const DEFAULT_MAX_EXAMPLES: usize = 3;
+/// NDJSON lines validated together; one batch is held in memory at a time
+const BATCH_LINES: usize = 4096;
+/// The rule of records that are not JSON
+pub const INVALID_JSON_RULE: &str = "InvalidJson";
+
+/// Options for validating a file of events
+#[derive(Debug, Clone)]
+pub struct FileValidationOptions {
+    /// Failing examples kept per rule, in input order
+    pub max_examples: usize,
+}
+
+impl Default for FileValidationOptions {
+    fn default() -> Self {
+        Self {
+            max_examples: DEFAULT_MAX_EXAMPLES,
+        }
+    }
+}
+
+/// One error of a failing event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureExample {
+    /// 0-based position of the event in the file
+    pub index: usize,
+    /// 1-based line of the event, for NDJSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_class: Option<String>,
+    pub field: String,
+    /// RFC 6901 JSON pointer of the attribute within the event
+    pub pointer: String,
+    pub message: String,
+}
+
+/// Validation results aggregated over a file of events
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FileValidationReport {
+    pub event_count: usize,
+    pub valid_count: usize,
+    pub invalid_count: usize,
+    /// Share of valid events, in percent
+    pub pass_rate: f64,
+    pub error_count: usize,
+    pub warning_count: usize,
+    /// Errors per rule: the validator's error type, or `InvalidJson`
+    pub errors_by_rule: BTreeMap<String, usize>,
+    /// Errors per event class name (`unknown` when the class cannot be told)
+    pub errors_by_class: BTreeMap<String, usize>,
+    /// Errors per attribute path
+    pub errors_by_field: BTreeMap<String, usize>,
+    /// The first failing examples of each rule
+    pub examples: BTreeMap<String, Vec<FailureExample>>,
+}
+
+/// The validation of one event of a file
+#[derive(Debug)]
+pub struct EventResult {
+    /// 0-based position of the event in the file
+    pub index: usize,
+    /// 1-based line of the event, for NDJSON
+    pub line: Option<usize>,
+    /// The event's report, or why it could not be read
+    pub result: Result<ValidationReport, String>,
+}
+
+impl FileValidationReport {
+    fn add(&mut self, checked: EventResult, options: &FileValidationOptions) {
+        self.event_count += 1;
+        let example =
+            |report: &mut Self, rule: &str, class: Option<&String>, field, pointer, message| {
+                *report.errors_by_rule.entry(rule.to_string()).or_default() += 1;
+                let class_name = class.map_or("unknown", String::as_str);
+                *report
+                    .errors_by_class
+                    .entry(class_name.to_string())
+                    .or_default() += 1;
+                let examples = report.examples.entry(rule.to_string()).or_default();
+                if examples.len() < options.max_examples {
+                    examples.push(FailureExample {
+                        index: checked.index,
+                        line: checked.line,
+                        event_class: class.cloned(),
+                        field,
+                        pointer,
+                        message,
+                    });
+                }
+            };
+        match checked.result {
+            Ok(result) if result.is_valid => {
+                self.valid_count += 1;
+                self.warning_count += result.warnings.len();
+            }
+            Ok(result) => {
+                self.invalid_count += 1;
+                self.warning_count += result.warnings.len();
+                self.error_count += result.errors.len();
+                for error in result.errors {
+                    *self.errors_by_field.entry(error.field.clone()).or_default() += 1;
+                    example(
+                        self,
+                        error.error_type.name(),
+                        result.event_class.as_ref(),
+                        error.field,
+                        error.pointer,
+                        error.message,
+                    );
+                }
+            }
+            Err(message) => {
+                self.invalid_count += 1;
+                self.error_count += 1;
+                example(
+                    self,
+                    INVALID_JSON_RULE,
+                    None,
+                    String::new(),
+                    String::new(),
+                    message,
+                );
+            }
+        }
+    }
+
+    fn finish(mut self) -> Self {
+        self.pass_rate = if self.event_count == 0 {
+            100.0
+        } else {
+            (self.valid_count as f64 * 10_000.0 / self.event_count as f64).round() / 100.0
+        };
+        self
+    }
+}
+
+/// Validate every event of an NDJSON file or JSON array file, gzip and zstd included
+pub fn validate_file(
+    schema: &OcsfSchema,
+    path: &Path,
+    options: &FileValidationOptions,
+) -> anyhow::Result<FileValidationReport> {
+    let (reader, _) = open_log(path)?;
+    validate_reader(schema, reader, options)
+}
+
+/// Validate every event of NDJSON or a JSON array, in parallel
+pub fn validate_reader<R: BufRead>(
+    schema: &OcsfSchema,
+    reader: R,
+    options: &FileValidationOptions,
+) -> anyhow::Result<FileValidationReport> {
+    let mut report = FileValidationReport::default();
+    check_events(schema, reader, |checked| report.add(checked, options))?;
+    Ok(report.finish())
+}
+
+/// Validate every event of NDJSON or a JSON array in parallel, visiting the results in order
+///
+/// Events are read in batches, so memory use does not grow with the input. Blank lines are
+/// skipped, and a line or array item that is not JSON, or is larger than `MAX_RECORD_BYTES`,
+/// counts as an invalid event.
+pub fn check_events<R: BufRead>(
+    schema: &OcsfSchema,
+    mut reader: R,
+    mut visit: impl FnMut(EventResult),
+) -> anyhow::Result<()> {
+    let (first, skipped_lines) = skip_whitespace(&mut reader)?;
+    let mut events = EventReader {
+        reader,
+        line: skipped_lines,
+        array: first == Some(b'['),
+        started: false,
+    };
+
+    let mut index = 0;
+    let mut batch: Vec<ReadEvent> = Vec::with_capacity(BATCH_LINES);
+    loop {
+        let event = events.next_event()?;
+        let done = event.is_none();
+        batch.extend(event);
+        if batch.len() == BATCH_LINES || (done && !batch.is_empty()) {
+            let checked: Vec<EventResult> = batch
+                .par_iter()
+                .enumerate()
+                .map(|(i, (line, text))| EventResult {
+                    index: index + i,
+                    line: *line,
+                    result: text.clone().and_then(|text| {
+                        validate_event_with_schema(&text, schema).map_err(|e| e.to_string())
+                    }),
+                })
+                .collect();
+            index += checked.len();
+            checked.into_iter().for_each(&mut visit);
+            batch.clear();
+        }
+        if done {
+            return Ok(());
+        }
+    }
+}
+
+/// An event's line (NDJSON only) and text, or why it was skipped
+type ReadEvent = (Option<usize>, Result<String, String>);
+
+/// Reads the events of NDJSON or of a JSON array one at a time, each up to `MAX_RECORD_BYTES`
+struct EventReader<R> {
+    reader: R,
+    /// Lines read so far
+    line: usize,
+    /// Whether the input is a JSON array rather than NDJSON
+    array: bool,
+    /// Whether the array's opening bracket was read, so the next item follows a comma
+    started: bool,
+}
+
+impl<R: BufRead> EventReader<R> {
+    fn next_event(&mut self) -> anyhow::Result<Option<ReadEvent>> {
+        if self.array {
+            return self.next_item();
+        }
+        loop {
+            let mut buffer = Vec::new();
+            if !self.read_until(&mut buffer, |b| b == b'\n', true)? {
+                return Ok(None);
+            }
+            self.line += 1;
+            let line = buffer.strip_suffix(b"\n").unwrap_or(&buffer);
+            let text = if line.len() > MAX_RECORD_BYTES {
+                Err(oversized())
+            } else {
+                match String::from_utf8_lossy(line) {
+                    text if text.trim().is_empty() => continue,
+                    text => Ok(text.into_owned()),
+                }
+            };
+            return Ok(Some((Some(self.line), text)));
+        }
+    }
+
+    /// The next item of the array, found without parsing it
+    fn next_item(&mut self) -> anyhow::Result<Option<ReadEvent>> {
+        // The opening bracket, or the comma after the previous item
+        let separator = self.skip_whitespace()?;
+        match separator {
+            Some(b'[') if !self.started => {}
+            Some(b',') if self.started => {}
+            Some(b']') if self.started => return Ok(None),
+            _ => anyhow::bail!("Invalid JSON array at line {}", self.line + 1),
+        }
+        self.reader.consume(1);
+        let first = self.skip_whitespace()?;
+        if !self.started && first == Some(b']') {
+            return Ok(None);
+        }
+        self.started = true;
+
+        let mut buffer = Vec::new();
+        let (mut depth, mut in_string, mut escaped) = (0usize, false, false);
+        let end = |b: u8| {
+            if in_string {
+                match b {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+                return false;
+            }
+            match b {
+                b'"' => in_string = true,
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' if depth > 0 => depth -= 1,
+                b',' | b']' if depth == 0 => return true,
+                _ if depth == 0 && b.is_ascii_whitespace() => return true,
+                _ => {}
+            }
+            false
+        };
+        self.read_until(&mut buffer, end, false)?;
+        let text = if buffer.len() > MAX_RECORD_BYTES {
+            Err(oversized())
+        } else {
+            Ok(String::from_utf8_lossy(&buffer).into_owned())
+        };
+        Ok(Some((None, text)))
+    }
+
+    /// Append bytes to `buffer` up to the first one `end` accepts (included when `inclusive`),
+    /// keeping at most one byte over `MAX_RECORD_BYTES`; false at the end of the input
+    fn read_until(
+        &mut self,
+        buffer: &mut Vec<u8>,
+        mut end: impl FnMut(u8) -> bool,
+        inclusive: bool,
+    ) -> io::Result<bool> {
+        let mut read = false;
+        loop {
+            let available = self.reader.fill_buf()?;
+            if available.is_empty() {
+                return Ok(read);
+            }
+            read = true;
+            let found = available.iter().position(|&b| end(b));
+            let used = match found {
+                Some(i) if inclusive => i + 1,
+                Some(i) => i,
+                None => available.len(),
+            };
+            // NDJSON counts a line per event instead
+            if self.array {
+                self.line += available[..used].iter().filter(|&&b| b == b'\n').count();
+            }
+            let room = (MAX_RECORD_BYTES + 1).saturating_sub(buffer.len());
+            buffer.extend_from_slice(&available[..used.min(room)]);
+            self.reader.consume(used);
+            if found.is_some() {
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Consume whitespace, returning the next byte without consuming it
+    fn skip_whitespace(&mut self) -> io::Result<Option<u8>> {
+        let (first, lines) = skip_whitespace(&mut self.reader)?;
+        self.line += lines;
+        Ok(first)
+    }
+}
+
+fn oversized() -> String {
+    format!(
+        "Event is larger than {} bytes and was skipped",
+        MAX_RECORD_BYTES
+    )
+}
+
+/// Consume leading whitespace, returning the first other byte and the line breaks skipped
+fn skip_whitespace<R: BufRead>(reader: &mut R) -> io::Result<(Option<u8>, usize)> {
+    let mut lines = 0;
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok((None, lines));
+        }
+        let skipped = buffer
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .unwrap_or(buffer.len());
+        let first = buffer.get(skipped).copied();
+        lines += buffer[..skipped].iter().filter(|&&b| b == b'\n').count();
+        reader.consume(skipped);
+        if first.is_some() {
+            return Ok((first, lines));
+        }
+    }
+}
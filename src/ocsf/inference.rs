@@ -0,0 +1,1200 @@
+use chrono::Datelike;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use super::mapping::{parse_timestamp_ms, CastType, FieldRule, MappingDefinition, Transform};
+use super::parsers::{parse_input, Fields, LogFormat, ParsedRecord};
+use super::schema::{Attribute, EventClass, OcsfSchema};
+
+/// Deepest attribute path considered, in segments (e.g. `process.parent_process.pid`)
+const MAX_PATH_DEPTH: usize = 3;
+
+/// Minimum score for a candidate to be reported as the mapping of a field
+pub const MIN_MAPPING_SCORE: f64 = 0.4;
+
+/// Attributes derived from the class and activity, never mapped from source fields
+const DERIVED_ATTRIBUTES: &[&str] = &[
+    "class_uid",
+    "class_name",
+    "category_uid",
+    "category_name",
+    "type_uid",
+    "type_name",
+];
+
+/// Whole source field names (lowercase, separators removed) with their OCSF meaning
+const FIELD_ALIASES: &[(&[&str], &[&str])] = &[
+    (
+        &["user", "username", "usr", "login", "account", "acct"],
+        &["user", "name"],
+    ),
+    (&["userid", "uid"], &["user", "uid"]),
+    (&["duser", "usrname"], &["user", "name"]),
+    (&["suser"], &["actor", "user", "name"]),
+    (&["shost"], &["src", "endpoint", "hostname"]),
+    (&["dhost"], &["dst", "endpoint", "hostname"]),
+    (&["smac", "srcmac"], &["src", "endpoint", "mac"]),
+    (&["dmac", "dstmac"], &["dst", "endpoint", "mac"]),
+    (
+        &[
+            "src",
+            "srcip",
+            "source",
+            "sourceip",
+            "srcaddr",
+            "clientip",
+            "client",
+            "remoteip",
+            "remoteaddr",
+            "ipsrc",
+            "identsrc",
+        ],
+        &["src", "endpoint", "ip"],
+    ),
+    (
+        &[
+            "dst",
+            "dstip",
+            "dest",
+            "destip",
+            "destination",
+            "destinationip",
+            "dstaddr",
+            "serverip",
+            "ipdst",
+        ],
+        &["dst", "endpoint", "ip"],
+    ),
+    (
+        &["sport", "spt", "srcport", "sourceport", "clientport"],
+        &["src", "endpoint", "port"],
+    ),
+    (
+        &[
+            "dport",
+            "dpt",
+            "dstport",
+            "destport",
+            "destinationport",
+            "serverport",
+        ],
+        &["dst", "endpoint", "port"],
+    ),
+    (
+        &[
+            "ts",
+            "time",
+            "timestamp",
+            "date",
+            "datetime",
+            "eventtime",
+            "timegenerated",
+            "rt",
+            "devtime",
+        ],
+        &["time"],
+    ),
+    (&["msg", "message"], &["message"]),
+    (
+        &["host", "hostname", "computer", "computername", "device"],
+        &["device", "hostname"],
+    ),
+    (&["pid", "processid", "dpid"], &["process", "pid"]),
+    (&["dproc"], &["process", "name"]),
+    (&["ppid", "parentpid"], &["parent", "process", "pid"]),
+    (
+        &["cmd", "command", "cmdline", "commandline"],
+        &["process", "cmd", "line"],
+    ),
+    (&["proc", "process", "processname"], &["process", "name"]),
+    (
+        &["exe", "image", "executable"],
+        &["process", "file", "path"],
+    ),
+    (&["file", "filename", "fname"], &["file", "name"]),
+    (&["path", "filepath"], &["file", "path"]),
+    (
+        &["hash", "md5", "sha1", "sha256", "filehash"],
+        &["file", "hashes", "value"],
+    ),
+    (&["email", "mail"], &["email", "addr"]),
+    (&["url", "uri", "request"], &["url", "string"]),
+    (&["result", "outcome", "status"], &["status"]),
+    (
+        &["level", "loglevel", "severity", "priority", "sev"],
+        &["severity"],
+    ),
+    (&["proto", "protocol"], &["protocol", "name"]),
+    (
+        &["method", "httpmethod", "requestmethod"],
+        &["http", "method"],
+    ),
+    (&["useragent", "ua"], &["user", "agent"]),
+];
+
+/// Single name tokens normalized to the vocabulary OCSF uses in attribute names
+const TOKEN_SYNONYMS: &[(&str, &[&str])] = &[
+    ("source", &["src"]),
+    ("dest", &["dst"]),
+    ("destination", &["dst"]),
+    ("id", &["uid"]),
+    ("username", &["user", "name"]),
+    ("ipaddr", &["ip"]),
+    ("ipaddress", &["ip"]),
+    ("timestamp", &["time"]),
+    ("msg", &["message"]),
+    ("cmdline", &["cmd", "line"]),
+    ("filename", &["file", "name"]),
+];
+
+/// Words in a sample that hint at a particular event class
+const CLASS_HINTS: &[(&str, &[&str])] = &[
+    (
+        "authentication",
+        &[
+            "login",
+            "logon",
+            "logged",
+            "logout",
+            "logoff",
+            "auth",
+            "authentication",
+            "password",
+            "sshd",
+            "mfa",
+            "signin",
+        ],
+    ),
+    (
+        "process_activity",
+        &[
+            "process", "exec", "execve", "spawn", "spawned", "pid", "cmd", "command", "launched",
+        ],
+    ),
+    (
+        "file_activity",
+        &[
+            "file", "path", "read", "write", "deleted", "created", "modified", "renamed",
+        ],
+    ),
+    (
+        "network_activity",
+        &[
+            "connection",
+            "connect",
+            "network",
+            "conn",
+            "bytes",
+            "packets",
+            "tcp",
+            "udp",
+            "flow",
+        ],
+    ),
+    (
+        "dns_activity",
+        &["dns", "query", "qname", "qtype", "nxdomain", "resolve"],
+    ),
+    (
+        "http_activity",
+        &[
+            "http",
+            "https",
+            "url",
+            "useragent",
+            "request",
+            "response",
+            "get",
+            "post",
+        ],
+    ),
+    (
+        "account_change",
+        &[
+            "useradd", "usermod", "userdel", "passwd", "account", "group",
+        ],
+    ),
+];
+
+static IPV4: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:\d{1,3}\.){3}\d{1,3}\b").unwrap());
+static MAC: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:[0-9A-Fa-f]{2}[:-]){5}[0-9A-Fa-f]{2}$").unwrap());
+static EMAIL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[\w.+-]+@[\w-]+(?:\.[\w-]+)+").unwrap());
+static UUID: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$")
+        .unwrap()
+});
+static HASH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(?:[0-9a-fA-F]{128}|[0-9a-fA-F]{64}|[0-9a-fA-F]{40}|[0-9a-fA-F]{32})\b").unwrap()
+});
+static URL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bhttps?://\S+").unwrap());
+static DATETIME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?|[A-Z][a-z]{2} +\d{1,2} \d{2}:\d{2}:\d{2})",
+    )
+    .unwrap()
+});
+static LEVEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(TRACE|DEBUG|INFO|NOTICE|WARN|WARNING|ERROR|CRITICAL|FATAL)\b").unwrap()
+});
+static USER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\buser(?:name)?[\s:=]+['"]?([\w.@\\-]+)"#).unwrap());
+static PORT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bport[\s:=]+(\d{1,5})\b").unwrap());
+static PID: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\bpid[\s:=\[]+(\d+)").unwrap());
+static SRC_IP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:from|src)\s*[=:]?\s*((?:\d{1,3}\.){3}\d{1,3})\b").unwrap()
+});
+static DST_IP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:to|dst)\s*[=:]?\s*((?:\d{1,3}\.){3}\d{1,3})\b").unwrap()
+});
+static PATH: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)(/[\w.-]+(?:/[\w.-]+)+)").unwrap());
+
+/// Shape of a sample value, used to match it against OCSF data types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueShape {
+    Ip,
+    Mac,
+    Email,
+    Url,
+    Uuid,
+    Hash,
+    EpochSeconds,
+    EpochMillis,
+    Timestamp,
+    Integer,
+    Float,
+    Boolean,
+    Path,
+    Text,
+}
+
+impl ValueShape {
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::Bool(_) => Self::Boolean,
+            Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+                (Some(i), _) => Self::of_integer(i),
+                (None, Some(f)) if (1e9..1e10).contains(&f) => Self::EpochSeconds,
+                _ => Self::Float,
+            },
+            Value::String(s) => Self::of_str(s.trim()),
+            Value::Array(items) => items.first().map(Self::of).unwrap_or(Self::Text),
+            _ => Self::Text,
+        }
+    }
+
+    fn of_integer(i: i64) -> Self {
+        match i {
+            1_000_000_000..=9_999_999_999 => Self::EpochSeconds,
+            1_000_000_000_000..=9_999_999_999_999 => Self::EpochMillis,
+            _ => Self::Integer,
+        }
+    }
+
+    fn of_str(s: &str) -> Self {
+        if s.parse::<std::net::IpAddr>().is_ok() {
+            Self::Ip
+        } else if MAC.is_match(s) {
+            Self::Mac
+        } else if UUID.is_match(s) {
+            Self::Uuid
+        } else if URL
+            .find(s)
+            .is_some_and(|m| m.start() == 0 && m.end() == s.len())
+        {
+            Self::Url
+        } else if EMAIL.find(s).is_some_and(|m| m.len() == s.len()) {
+            Self::Email
+        } else if HASH.find(s).is_some_and(|m| m.len() == s.len()) {
+            Self::Hash
+        } else if let Ok(i) = s.parse::<i64>() {
+            Self::of_integer(i)
+        } else if s.parse::<f64>().is_ok() {
+            Self::Float
+        } else if DATETIME.is_match(s) {
+            Self::Timestamp
+        } else if matches!(s, "true" | "false") {
+            Self::Boolean
+        } else if (s.starts_with('/') && s[1..].contains('/'))
+            || (s.len() > 2 && s.as_bytes()[1] == b':' && s.as_bytes()[2] == b'\\')
+        {
+            Self::Path
+        } else {
+            Self::Text
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ip => "ip",
+            Self::Mac => "mac",
+            Self::Email => "email",
+            Self::Url => "url",
+            Self::Uuid => "uuid",
+            Self::Hash => "hash",
+            Self::EpochSeconds => "epoch_seconds",
+            Self::EpochMillis => "epoch_millis",
+            Self::Timestamp => "timestamp",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Boolean => "boolean",
+            Self::Path => "path",
+            Self::Text => "text",
+        }
+    }
+}
+
+/// A field extracted from a sample log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleField {
+    pub name: String,
+    pub value: Value,
+    pub shape: ValueShape,
+    /// For free-text samples, a regex whose first group (or whole match) extracts the value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+/// A parsed sample: its fields plus lowercase words for class hints
+#[derive(Debug, Clone)]
+pub struct ParsedSample {
+    pub format: LogFormat,
+    pub fields: Vec<SampleField>,
+    pub words: HashSet<String>,
+}
+
+/// A scored OCSF attribute path for a source field
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PathCandidate {
+    pub path: String,
+    pub score: f64,
+}
+
+/// The inferred OCSF attribute for one source field
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FieldInference {
+    pub source_field: String,
+    pub sample_value: Value,
+    pub shape: ValueShape,
+    /// Best attribute path, if any scored above `MIN_MAPPING_SCORE`
+    pub ocsf_field: Option<String>,
+    pub confidence: f64,
+    pub transformation: Option<String>,
+    pub reasons: Vec<String>,
+    pub alternatives: Vec<PathCandidate>,
+}
+
+/// How well an event class fits a sample
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ClassScore {
+    pub name: String,
+    pub caption: String,
+    pub uid: u32,
+    pub score: f64,
+    /// Mean best-attribute score over the sample's fields
+    pub field_fit: f64,
+    /// Sample words hinting at this class
+    pub hints: Vec<String>,
+}
+
+/// Parse the first record of a sample, detecting its format
+pub fn parse_sample(sample: &str) -> ParsedSample {
+    let record = parse_input(sample, None)
+        .ok()
+        .and_then(|records| records.into_iter().next())
+        .unwrap_or_else(|| ParsedRecord {
+            format: LogFormat::Text,
+            line: 1,
+            fields: Fields::from_iter([("message".to_string(), json!(sample.trim()))]),
+            metadata: Map::new(),
+            raw: sample.trim().to_string(),
+        });
+    sample_from_record(&record)
+}
+
+/// Turn a parsed record into inference fields
+///
+/// Free-text messages (`text` lines and the syslog message) are mined for recognizable
+/// values, each with the regex a mapping uses to extract it from `message`.
+pub fn sample_from_record(record: &ParsedRecord) -> ParsedSample {
+    let message = record.fields.get("message").and_then(Value::as_str);
+    let structured = |skip: Option<&str>| {
+        without_patterns(
+            record
+                .fields
+                .iter()
+                .filter(|(name, _)| Some(name.as_str()) != skip)
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+        )
+    };
+    let raw: Vec<RawField> = match (record.format, message) {
+        (LogFormat::Text, Some(message)) => text_fields(message),
+        (LogFormat::Syslog, Some(message)) => {
+            let mut raw = structured(Some("message"));
+            for field in text_fields(message) {
+                if field.2.is_some() && !raw.iter().any(|(name, _, _)| *name == field.0) {
+                    raw.push(field);
+                }
+            }
+            raw
+        }
+        _ => structured(None),
+    };
+
+    let mut words: HashSet<String> = HashSet::new();
+    let mut add_words = |text: &str| {
+        words.extend(
+            text.split(|c: char| !c.is_ascii_alphanumeric())
+                .filter(|w| w.len() > 1)
+                .map(str::to_ascii_lowercase),
+        )
+    };
+    if let (LogFormat::Text | LogFormat::Syslog, Some(message)) = (record.format, message) {
+        add_words(message);
+    }
+    for (name, value, _) in &raw {
+        add_words(name);
+        if let Some(s) = value.as_str() {
+            add_words(s);
+        }
+    }
+
+    let fields = raw
+        .into_iter()
+        .map(|(name, value, pattern)| SampleField {
+            shape: ValueShape::of(&value),
+            name,
+            value,
+            pattern,
+        })
+        .collect();
+    ParsedSample {
+        format: record.format,
+        fields,
+        words,
+    }
+}
+
+fn without_patterns(fields: Vec<(String, Value)>) -> Vec<RawField> {
+    fields
+        .into_iter()
+        .map(|(name, value)| (name, value, None))
+        .collect()
+}
+
+/// A field name, its value and, for free text, the regex that extracts it
+type RawField = (String, Value, Option<String>);
+
+/// Pull recognizable values out of an unstructured line, naming them by what they look like
+///
+/// Only the first field of each name gets an extraction pattern; later ones (`ip_2`) can be
+/// found but not extracted by a mapping.
+fn text_fields(sample: &str) -> Vec<RawField> {
+    let mut fields: Vec<RawField> = Vec::new();
+    let mut push = |name: &str, value: &str, pattern: Option<&Regex>| {
+        let taken = |n: &str| fields.iter().any(|(existing, _, _)| existing == n);
+        let mut unique = name.to_string();
+        let mut i = 2;
+        while taken(&unique) {
+            unique = format!("{name}_{i}");
+            i += 1;
+        }
+        let pattern = pattern
+            .filter(|_| unique == name)
+            .map(|p| p.as_str().to_string());
+        fields.push((unique, Value::String(value.to_string()), pattern));
+    };
+
+    if let Some(m) = DATETIME.find(sample) {
+        push("timestamp", m.as_str(), Some(&DATETIME));
+    }
+    if let Some(c) = LEVEL.captures(sample) {
+        push("level", &c[1], Some(&LEVEL));
+    }
+    if let Some(c) = USER.captures(sample) {
+        push("user", &c[1], Some(&USER));
+    }
+    for (i, m) in IPV4.find_iter(sample).enumerate() {
+        let before = sample[..m.start()].trim_end().to_ascii_lowercase();
+        if before.ends_with("from") || before.ends_with("src") {
+            push("src_ip", m.as_str(), Some(&SRC_IP));
+        } else if before.ends_with("to") || before.ends_with("dst") {
+            push("dst_ip", m.as_str(), Some(&DST_IP));
+        } else {
+            push("ip", m.as_str(), (i == 0).then_some(&*IPV4));
+        }
+    }
+    if let Some(c) = PORT.captures(sample) {
+        push("port", &c[1], Some(&PORT));
+    }
+    if let Some(c) = PID.captures(sample) {
+        push("pid", &c[1], Some(&PID));
+    }
+    for m in EMAIL.find_iter(sample) {
+        push("email", m.as_str(), Some(&EMAIL));
+    }
+    for m in URL.find_iter(sample) {
+        push("url", m.as_str(), Some(&URL));
+    }
+    for m in HASH.find_iter(sample) {
+        push("hash", m.as_str(), Some(&HASH));
+    }
+    if let Some(c) = PATH.captures(sample) {
+        push("path", &c[1], Some(&PATH));
+    }
+    fields
+}
+
+/// A leaf attribute path of an event class
+struct Candidate<'a> {
+    path: String,
+    attr: &'a Attribute,
+    tokens: Vec<String>,
+}
+
+/// Scores source fields against the attribute paths of event classes
+pub struct FieldInferrer<'a> {
+    schema: &'a OcsfSchema,
+}
+
+impl<'a> FieldInferrer<'a> {
+    pub fn new(schema: &'a OcsfSchema) -> Self {
+        Self { schema }
+    }
+
+    /// Rank every event class of the schema by how well it fits the sample, best first
+    pub fn rank_classes(&self, sample: &ParsedSample) -> Vec<ClassScore> {
+        let mut ranking: Vec<ClassScore> = self
+            .schema
+            .classes
+            .values()
+            .filter(|ec| ec.name != "base_event")
+            .map(|ec| {
+                let candidates = self.candidates(ec);
+                let field_fit = if sample.fields.is_empty() {
+                    0.0
+                } else {
+                    sample
+                        .fields
+                        .iter()
+                        .map(|field| {
+                            candidates
+                                .iter()
+                                .map(|c| self.score(field, c, false).0)
+                                .fold(0.0, f64::max)
+                        })
+                        .sum::<f64>()
+                        / sample.fields.len() as f64
+                };
+                let hints = class_hints(ec, &sample.words);
+                let hint_score = (hints.len() as f64 / 2.0).min(1.0);
+                ClassScore {
+                    name: ec.name.clone(),
+                    caption: ec.caption.clone().unwrap_or_else(|| ec.name.clone()),
+                    uid: ec.uid,
+                    score: round(0.7 * field_fit + 0.3 * hint_score),
+                    field_fit: round(field_fit),
+                    hints,
+                }
+            })
+            .collect();
+
+        ranking.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.name.cmp(&b.name)));
+        ranking
+    }
+
+    /// Infer the best attribute path of `event_class` for each sample field
+    pub fn infer_fields(
+        &self,
+        event_class: &EventClass,
+        sample: &ParsedSample,
+    ) -> Vec<FieldInference> {
+        let candidates = self.candidates(event_class);
+
+        sample
+            .fields
+            .iter()
+            .map(|field| {
+                let mut scored: Vec<(f64, Vec<String>, &Candidate)> = candidates
+                    .iter()
+                    .filter_map(|c| {
+                        let (score, reasons) = self.score(field, c, true);
+                        (score > 0.0).then_some((score, reasons, c))
+                    })
+                    .collect();
+                scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.2.path.cmp(&b.2.path)));
+
+                let best = scored
+                    .first()
+                    .filter(|(score, _, _)| *score >= MIN_MAPPING_SCORE);
+                FieldInference {
+                    source_field: field.name.clone(),
+                    sample_value: field.value.clone(),
+                    shape: field.shape,
+                    ocsf_field: best.map(|(_, _, c)| c.path.clone()),
+                    confidence: best.map_or(0.0, |(score, _, _)| round(*score)),
+                    transformation: best.and_then(|(_, _, c)| self.transformation(field, c.attr)),
+                    reasons: best.map(|(_, r, _)| r.clone()).unwrap_or_default(),
+                    alternatives: scored
+                        .iter()
+                        .skip(usize::from(best.is_some()))
+                        .take(3)
+                        .map(|(score, _, c)| PathCandidate {
+                            path: c.path.clone(),
+                            score: round(*score),
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Leaf attribute paths of a class, following objects up to `MAX_PATH_DEPTH` segments
+    fn candidates(&self, event_class: &'a EventClass) -> Vec<Candidate<'a>> {
+        let mut out = Vec::new();
+        self.collect_paths(&event_class.attributes, "", 1, &mut out);
+        out
+    }
+
+    fn collect_paths(
+        &self,
+        attributes: &'a std::collections::HashMap<String, Attribute>,
+        prefix: &str,
+        depth: usize,
+        out: &mut Vec<Candidate<'a>>,
+    ) {
+        for (name, attr) in attributes {
+            if attr.deprecated.is_some()
+                || (depth == 1 && DERIVED_ATTRIBUTES.contains(&name.as_str()))
+            {
+                continue;
+            }
+            let path = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{prefix}.{name}")
+            };
+
+            if let Some(object_type) = &attr.object_type {
+                if depth < MAX_PATH_DEPTH
+                    && let Some(object) = self.schema.objects.get(object_type)
+                {
+                    self.collect_paths(&object.attributes, &path, depth + 1, out);
+                }
+                continue;
+            }
+
+            let mut tokens: Vec<String> = path.split('.').flat_map(name_tokens).collect();
+            // `status_id` and `status` describe the same thing; the shape decides between them
+            if attr.enum_values.is_some() && name.ends_with("_id") {
+                tokens.pop();
+            }
+            out.push(Candidate { path, attr, tokens });
+        }
+    }
+
+    /// Combined score of a field for a candidate path, with the reasons behind it
+    fn score(
+        &self,
+        field: &SampleField,
+        candidate: &Candidate,
+        describe: bool,
+    ) -> (f64, Vec<String>) {
+        let type_fit = self.type_fit(field, candidate.attr);
+        if type_fit == 0.0 {
+            return (0.0, Vec::new());
+        }
+
+        let source = source_tokens(&field.name);
+        let overlap = source
+            .iter()
+            .filter(|t| candidate.tokens.contains(t))
+            .count() as f64;
+        let name_fit = if source.is_empty() || candidate.tokens.is_empty() {
+            0.0
+        } else {
+            0.7 * overlap / source.len() as f64 + 0.3 * overlap / candidate.tokens.len() as f64
+        };
+
+        let mentioned: Vec<String> = if describe {
+            let text = format!(
+                "{} {}",
+                candidate.attr.caption.as_deref().unwrap_or_default(),
+                candidate.attr.description.as_deref().unwrap_or_default()
+            )
+            .to_ascii_lowercase();
+            raw_tokens(&field.name)
+                .into_iter()
+                .filter(|t| t.len() > 2 && text.contains(t.as_str()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let raw_count = raw_tokens(&field.name).len().max(1) as f64;
+        let description_fit = (mentioned.len() as f64 / raw_count).min(1.0);
+
+        // Without any name evidence only a distinctive type match counts
+        if name_fit == 0.0 && description_fit == 0.0 && type_fit < 1.0 {
+            return (0.0, Vec::new());
+        }
+
+        let score = 0.6 * name_fit + 0.3 * type_fit + 0.1 * description_fit;
+        let mut reasons = Vec::new();
+        if describe {
+            if name_fit > 0.0 {
+                reasons.push(format!("name similarity {:.2}", name_fit));
+            }
+            reasons.push(format!(
+                "{} value fits {}",
+                field.shape.as_str(),
+                candidate.attr.data_type.as_deref().unwrap_or("string_t")
+            ));
+            if let Some(word) = mentioned.first() {
+                reasons.push(format!("description mentions '{word}'"));
+            }
+        }
+        (score, reasons)
+    }
+
+    /// How well a value's shape fits an attribute's type, from 0 (incompatible) to 1
+    fn type_fit(&self, field: &SampleField, attr: &Attribute) -> f64 {
+        let data_type = attr.data_type.as_deref().unwrap_or("string_t");
+        let base = self.schema.base_type(data_type);
+
+        if let Some(values) = &attr.enum_values
+            && matches!(base, "integer_t" | "long_t")
+        {
+            return match field.shape {
+                ValueShape::Integer => {
+                    let id = field
+                        .value
+                        .as_i64()
+                        .map(|i| i.to_string())
+                        .or_else(|| field.value.as_str().map(|s| s.trim().to_string()));
+                    if id.is_some_and(|id| values.contains_key(&id)) {
+                        0.9
+                    } else {
+                        0.0
+                    }
+                }
+                ValueShape::Text => {
+                    if enum_caption_id(values, &field.value).is_some() {
+                        1.0
+                    } else {
+                        0.2
+                    }
+                }
+                _ => 0.0,
+            };
+        }
+
+        let string_like = base == "string_t";
+        match field.shape {
+            ValueShape::Ip => match data_type {
+                "ip_t" => 1.0,
+                "subnet_t" => 0.5,
+                "string_t" => 0.2,
+                _ => 0.0,
+            },
+            ValueShape::Mac => match data_type {
+                "mac_t" => 1.0,
+                "string_t" => 0.2,
+                _ => 0.0,
+            },
+            ValueShape::Email => match data_type {
+                "email_t" => 1.0,
+                "username_t" => 0.6,
+                "string_t" => 0.3,
+                _ => 0.0,
+            },
+            ValueShape::Url => match data_type {
+                "url_t" => 1.0,
+                "string_t" => 0.4,
+                _ => 0.0,
+            },
+            ValueShape::Uuid => match data_type {
+                "uuid_t" => 1.0,
+                "string_t" | "resource_uid_t" => 0.6,
+                _ => 0.0,
+            },
+            ValueShape::Hash => match data_type {
+                "file_hash_t" => 1.0,
+                "string_t" => 0.4,
+                _ => 0.0,
+            },
+            ValueShape::EpochSeconds | ValueShape::EpochMillis => match data_type {
+                "timestamp_t" => 1.0,
+                _ if matches!(base, "integer_t" | "long_t") => 0.3,
+                _ => 0.0,
+            },
+            ValueShape::Timestamp => match data_type {
+                "datetime_t" => 1.0,
+                "timestamp_t" => 0.9,
+                "string_t" => 0.2,
+                _ => 0.0,
+            },
+            ValueShape::Integer => match data_type {
+                "port_t" => {
+                    let port = field
+                        .value
+                        .as_i64()
+                        .or_else(|| field.value.as_str().and_then(|s| s.trim().parse().ok()));
+                    if port.is_some_and(|p| (0..=65535).contains(&p)) {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                }
+                "timestamp_t" => 0.0,
+                _ if matches!(base, "integer_t" | "long_t") => 0.8,
+                _ if base == "float_t" => 0.5,
+                "string_t" | "resource_uid_t" => 0.3,
+                _ => 0.0,
+            },
+            ValueShape::Float => match base {
+                "float_t" => 1.0,
+                "integer_t" | "long_t" if data_type != "timestamp_t" => 0.2,
+                "string_t" => 0.2,
+                _ => 0.0,
+            },
+            ValueShape::Boolean => match base {
+                "boolean_t" => 1.0,
+                "string_t" => 0.1,
+                _ => 0.0,
+            },
+            ValueShape::Path => match data_type {
+                "string_t" => 0.6,
+                "file_name_t" | "process_name_t" => 0.3,
+                _ => 0.0,
+            },
+            ValueShape::Text => match data_type {
+                "string_t" | "username_t" | "hostname_t" | "process_name_t" | "file_name_t"
+                | "resource_uid_t" => 0.6,
+                _ if string_like
+                    && !matches!(
+                        data_type,
+                        "ip_t"
+                            | "mac_t"
+                            | "email_t"
+                            | "url_t"
+                            | "uuid_t"
+                            | "file_hash_t"
+                            | "subnet_t"
+                            | "datetime_t"
+                    ) =>
+                {
+                    0.3
+                }
+                _ => 0.0,
+            },
+        }
+    }
+
+    /// Describe the conversion a value needs to fit an attribute, if any
+    fn transformation(&self, field: &SampleField, attr: &Attribute) -> Option<String> {
+        let data_type = attr.data_type.as_deref().unwrap_or("string_t");
+        let base = self.schema.base_type(data_type);
+
+        if let Some(values) = &attr.enum_values
+            && field.shape == ValueShape::Text
+        {
+            return Some(match enum_caption_id(values, &field.value) {
+                Some((id, caption)) => format!(
+                    "Map '{}' to {} ({})",
+                    field.value.as_str().unwrap_or_default(),
+                    id,
+                    caption
+                ),
+                None => "Map source values to enum ids; use 99 (Other) with the caption otherwise"
+                    .to_string(),
+            });
+        }
+
+        match (field.shape, data_type) {
+            (ValueShape::Timestamp, "timestamp_t") => {
+                Some("Parse timestamp to epoch milliseconds".to_string())
+            }
+            (ValueShape::EpochSeconds, "timestamp_t") => {
+                Some("Convert epoch seconds to milliseconds".to_string())
+            }
+            (ValueShape::Integer | ValueShape::EpochMillis, _)
+                if field.value.is_string() && matches!(base, "integer_t" | "long_t") =>
+            {
+                Some("Parse as integer".to_string())
+            }
+            (ValueShape::Integer | ValueShape::Float, _)
+                if base == "string_t" && field.value.is_number() =>
+            {
+                Some("Convert to string".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Turn inferred fields into a runnable mapping for `event_class`
+    ///
+    /// When several fields infer the same attribute the most confident one wins. Free-text
+    /// fields are extracted from `message` with their pattern; fields without one are left out.
+    pub fn build_mapping(
+        &self,
+        event_class: &EventClass,
+        sample: &ParsedSample,
+        inferences: &[FieldInference],
+    ) -> MappingDefinition {
+        let mut ranked: Vec<(usize, &FieldInference)> = inferences.iter().enumerate().collect();
+        ranked.sort_by(|a, b| {
+            b.1.confidence
+                .total_cmp(&a.1.confidence)
+                .then(a.0.cmp(&b.0))
+        });
+
+        let mut targets: HashSet<String> = HashSet::new();
+        let mut rules: Vec<(usize, FieldRule)> = Vec::new();
+        for (index, inference) in ranked {
+            let Some(target) = &inference.ocsf_field else {
+                continue;
+            };
+            let Some(field) = sample
+                .fields
+                .iter()
+                .find(|f| f.name == inference.source_field)
+            else {
+                continue;
+            };
+            let Some(attr) = attribute_at(self.schema, event_class, target) else {
+                continue;
+            };
+            let (source, extract) = match (sample.format, &field.pattern) {
+                (_, Some(pattern)) => (
+                    "message".to_string(),
+                    vec![Transform::Regex {
+                        pattern: pattern.clone(),
+                        group: None,
+                    }],
+                ),
+                (LogFormat::Text, None) => continue,
+                _ => (field.name.clone(), Vec::new()),
+            };
+            if !targets.insert(target.clone()) {
+                continue;
+            }
+
+            let convert = self.rule_transforms(field, attr);
+            // Keep the source text as the caption so `Other` (99) stays meaningful
+            let sibling = match (&attr.sibling, convert.last()) {
+                (Some(sibling), Some(Transform::Lookup { .. })) => {
+                    let path = match target.rsplit_once('.') {
+                        Some((parent, _)) => format!("{parent}.{sibling}"),
+                        None => sibling.clone(),
+                    };
+                    (attribute_at(self.schema, event_class, &path).is_some()
+                        && targets.insert(path.clone()))
+                    .then_some(path)
+                }
+                _ => None,
+            };
+
+            rules.push((
+                index,
+                FieldRule {
+                    target: target.clone(),
+                    source: Some(source.clone()),
+                    transforms: extract.iter().cloned().chain(convert).collect(),
+                    ..Default::default()
+                },
+            ));
+            if let Some(path) = sibling {
+                rules.push((
+                    index,
+                    FieldRule {
+                        target: path,
+                        source: Some(source),
+                        transforms: extract,
+                        ..Default::default()
+                    },
+                ));
+            }
+        }
+        rules.sort_by_key(|(index, _)| *index);
+
+        MappingDefinition {
+            name: Some(format!("{}_mapping", event_class.name)),
+            version: Some(self.schema.version.clone()),
+            format: Some(sample.format),
+            class: Some(event_class.name.clone()),
+            fields: rules.into_iter().map(|(_, rule)| rule).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Transforms that turn a field's value into the attribute's type
+    fn rule_transforms(&self, field: &SampleField, attr: &Attribute) -> Vec<Transform> {
+        let data_type = attr.data_type.as_deref().unwrap_or("string_t");
+        let base = self.schema.base_type(data_type);
+        let text = field.value.is_string();
+
+        if let Some(values) = &attr.enum_values
+            && field.shape == ValueShape::Text
+        {
+            let table: Map<String, Value> = values
+                .iter()
+                .filter_map(|(id, v)| Some((v.caption.clone()?, json!(id.parse::<i64>().ok()?))))
+                .collect();
+            return vec![Transform::Lookup {
+                table,
+                default: values.contains_key("99").then(|| json!(99)),
+                case_insensitive: true,
+            }];
+        }
+
+        let cast = match (base, field.shape) {
+            ("integer_t" | "long_t", ValueShape::Timestamp)
+            | ("integer_t" | "long_t", ValueShape::EpochSeconds)
+                if data_type == "timestamp_t" =>
+            {
+                // A syslog timestamp has no year; suggest the current one in the mapping, where
+                // it is visible and fixed, rather than at conversion time
+                let year = (parse_timestamp_ms(&field.value, None, None, None).is_none())
+                    .then(|| chrono::Utc::now().year());
+                return vec![Transform::ParseTimestamp {
+                    format: None,
+                    unit: None,
+                    year,
+                }];
+            }
+            ("integer_t" | "long_t", _) if text => Some(CastType::Integer),
+            ("float_t", _) if text => Some(CastType::Float),
+            ("boolean_t", _) if !field.value.is_boolean() => Some(CastType::Boolean),
+            ("string_t", _) if !text => Some(CastType::String),
+            _ => None,
+        };
+        cast.map(Transform::Cast).into_iter().collect()
+    }
+}
+
+/// The attribute at a dotted path of a class, following object definitions
+fn attribute_at<'a>(
+    schema: &'a OcsfSchema,
+    event_class: &'a EventClass,
+    path: &str,
+) -> Option<&'a Attribute> {
+    let (parents, leaf) = match path.rsplit_once('.') {
+        Some((parents, leaf)) => (Some(parents), leaf),
+        None => (None, path),
+    };
+    let mut attributes = &event_class.attributes;
+    for segment in parents.into_iter().flat_map(|p| p.split('.')) {
+        let object_type = attributes.get(segment)?.object_type.as_ref()?;
+        attributes = &schema.objects.get(object_type)?.attributes;
+    }
+    attributes.get(leaf)
+}
+
+/// Enum id and caption whose caption matches a text value (case-insensitive)
+fn enum_caption_id(
+    values: &std::collections::HashMap<String, super::schema::EnumValue>,
+    value: &Value,
+) -> Option<(String, String)> {
+    let text = value.as_str()?.trim();
+    values.iter().find_map(|(id, v)| {
+        v.caption
+            .as_deref()
+            .filter(|c| c.eq_ignore_ascii_case(text))
+            .map(|c| (id.clone(), c.to_string()))
+    })
+}
+
+/// Sample words hinting at an event class, from the hint table and the class name itself
+fn class_hints(event_class: &EventClass, words: &HashSet<String>) -> Vec<String> {
+    let mut hints: Vec<String> = CLASS_HINTS
+        .iter()
+        .filter(|(class, _)| *class == event_class.name)
+        .flat_map(|(_, hints)| hints.iter())
+        .filter(|hint| words.contains(**hint))
+        .map(|hint| hint.to_string())
+        .collect();
+    for token in event_class.name.split('_') {
+        if token != "activity" && words.contains(token) && !hints.iter().any(|h| h == token) {
+            hints.push(token.to_string());
+        }
+    }
+    hints.sort();
+    hints
+}
+
+/// Lowercase name words, split on separators and camelCase
+fn raw_tokens(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for ch in name.chars() {
+        if !ch.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if ch.is_ascii_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = ch.is_ascii_lowercase() || ch.is_ascii_digit();
+        current.push(ch.to_ascii_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Name words normalized to OCSF vocabulary, with plurals folded (`answers` → `answer`)
+fn name_tokens(name: &str) -> Vec<String> {
+    raw_tokens(name)
+        .into_iter()
+        .flat_map(
+            |word| match TOKEN_SYNONYMS.iter().find(|(from, _)| *from == word) {
+                Some((_, to)) => to.iter().map(|t| singular(t)).collect(),
+                None => vec![singular(&word)],
+            },
+        )
+        .collect()
+}
+
+fn singular(word: &str) -> String {
+    match word.strip_suffix('s') {
+        Some(stem) if stem.len() > 2 && !stem.ends_with('s') => stem.to_string(),
+        _ => word.to_string(),
+    }
+}
+
+/// Tokens of a source field: a known alias of the whole (last) name, or its normalized words
+fn source_tokens(name: &str) -> Vec<String> {
+    let last = name.rsplit('.').next().unwrap_or(name);
+    for candidate in [name, last] {
+        let compact: String = candidate
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if let Some((_, tokens)) = FIELD_ALIASES
+            .iter()
+            .find(|(aliases, _)| aliases.contains(&compact.as_str()))
+        {
+            return tokens.iter().map(|t| singular(t)).collect();
+        }
+    }
+    name_tokens(name)
+}
+
+fn round(score: f64) -> f64 {
+    (score * 100.0).round() / 100.0
+}
@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+use super::schema::{Attribute, OcsfSchema};
+use super::validation::{field_pointer, ErrorType, ValidationReport};
+
+const DRAFT_2020_12: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Options controlling which optional parts of the schema are exported
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JsonSchemaOptions {
+    /// Profiles whose attributes are included (e.g. `cloud`, `host`)
+    #[serde(default)]
+    pub profiles: Vec<String>,
+    /// Extensions whose attributes are included (e.g. `win`)
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// Reject attributes that are not defined by the class or object
+    #[serde(default)]
+    pub strict: bool,
+}
+
+impl JsonSchemaOptions {
+    fn includes(&self, attr: &Attribute) -> bool {
+        let profile_ok = attr
+            .profile
+            .as_ref()
+            .is_none_or(|p| self.profiles.contains(p));
+        let extension_ok = attr
+            .extension
+            .as_ref()
+            .is_none_or(|e| self.extensions.contains(e));
+        profile_ok && extension_ok
+    }
+}
+
+/// Export a resolved OCSF event class as a standalone JSON Schema (draft 2020-12) document
+pub fn export_class(
+    schema: &OcsfSchema,
+    class_name: &str,
+    options: &JsonSchemaOptions,
+) -> anyhow::Result<Value> {
+    let ec = schema
+        .get_event_class(class_name)
+        .ok_or_else(|| anyhow::anyhow!("Event class '{}' not found", class_name))?;
+
+    // The class's own extension is always in scope
+    let mut options = options.clone();
+    if let Some(ext) = &ec.extension
+        && !options.extensions.contains(ext)
+    {
+        options.extensions.push(ext.clone());
+    }
+
+    let mut exporter = Exporter {
+        schema,
+        options: &options,
+        defs: BTreeMap::new(),
+    };
+    let (properties, required) = exporter.properties(&ec.attributes);
+
+    let mut document = Map::new();
+    document.insert("$schema".to_string(), json!(DRAFT_2020_12));
+    document.insert(
+        "$id".to_string(),
+        json!(format!(
+            "https://schema.ocsf.io/schema/{}/classes/{}",
+            schema.version, ec.name
+        )),
+    );
+    document.insert(
+        "title".to_string(),
+        json!(ec.caption.clone().unwrap_or_else(|| ec.name.clone())),
+    );
+    if let Some(description) = &ec.description {
+        document.insert("description".to_string(), json!(description));
+    }
+    document.insert("type".to_string(), json!("object"));
+    document.insert("properties".to_string(), Value::Object(properties));
+    document.insert("required".to_string(), json!(required));
+    if options.strict {
+        document.insert("additionalProperties".to_string(), json!(false));
+    }
+    document.insert(
+        "$defs".to_string(),
+        Value::Object(exporter.defs.into_iter().collect()),
+    );
+
+    Ok(Value::Object(document))
+}
+
+/// Validate an event by running it through the `jsonschema` crate against the exported class
+pub fn validate_with_json_schema(
+    event_json: &str,
+    schema: &OcsfSchema,
+    options: &JsonSchemaOptions,
+) -> anyhow::Result<ValidationReport> {
+    let event: Value = serde_json::from_str(event_json)?;
+    let ec = schema
+        .class_for_event(&event)
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the event class from class_uid"))?;
+
+    let document = export_class(schema, &ec.name, options)?;
+    let validator = jsonschema::draft202012::new(&document)
+        .map_err(|e| anyhow::anyhow!("Invalid exported JSON Schema: {}", e))?;
+
+    let mut report = ValidationReport::new(true, Some(ec.name.clone()));
+    for error in validator.iter_errors(&event) {
+        let error_type = match &error.kind {
+            jsonschema::error::ValidationErrorKind::Required { .. } => ErrorType::MissingRequired,
+            jsonschema::error::ValidationErrorKind::Type { .. } => ErrorType::InvalidType,
+            jsonschema::error::ValidationErrorKind::AdditionalProperties { .. } => {
+                ErrorType::UnknownField
+            }
+            _ => ErrorType::InvalidValue,
+        };
+        // A missing property is located where it belongs, not at the object that lacks it
+        let mut pointer = error.instance_path.to_string();
+        if let jsonschema::error::ValidationErrorKind::Required { property } = &error.kind
+            && let Some(name) = property.as_str()
+        {
+            pointer.push_str(&field_pointer(name));
+        }
+        report.add_error_at(pointer, error.to_string(), error_type);
+    }
+
+    report.summary = if report.is_valid {
+        format!("Valid against JSON Schema for class '{}'", ec.name)
+    } else {
+        format!(
+            "JSON Schema validation failed with {} error(s)",
+            report.errors.len()
+        )
+    };
+    report.locate(event_json);
+
+    Ok(report)
+}
+
+struct Exporter<'a> {
+    schema: &'a OcsfSchema,
+    options: &'a JsonSchemaOptions,
+    defs: BTreeMap<String, Value>,
+}
+
+impl Exporter<'_> {
+    fn properties(
+        &mut self,
+        attributes: &HashMap<String, Attribute>,
+    ) -> (Map<String, Value>, Vec<String>) {
+        let mut names: Vec<&String> = attributes.keys().collect();
+        names.sort();
+
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for name in names {
+            let attr = &attributes[name];
+            if !self.options.includes(attr) {
+                continue;
+            }
+            properties.insert(name.clone(), self.attribute(attr));
+            if attr.requirement.as_deref() == Some("required") {
+                required.push(name.clone());
+            }
+        }
+
+        (properties, required)
+    }
+
+    fn attribute(&mut self, attr: &Attribute) -> Value {
+        let mut item = match attr.object_type.as_deref() {
+            Some(object_type) => {
+                self.define_object(object_type);
+                // `/` in extension object names must be escaped in JSON pointers
+                json!({ "$ref": format!("#/$defs/{}", object_type.replace('~', "~0").replace('/', "~1")) })
+            }
+            None => self.scalar(attr),
+        };
+
+        if let (Some(caption), Some(obj)) = (&attr.caption, item.as_object_mut())
+            && !obj.contains_key("$ref")
+        {
+            obj.insert("title".to_string(), json!(caption));
+        }
+
+        if attr.is_array {
+            item = json!({ "type": "array", "items": item });
+        }
+
+        if let Some(description) = &attr.description
+            && let Some(obj) = item.as_object_mut()
+        {
+            obj.insert("description".to_string(), json!(description));
+        }
+
+        item
+    }
+
+    fn scalar(&self, attr: &Attribute) -> Value {
+        let data_type = attr.data_type.as_deref().unwrap_or("string_t");
+        let mut out = Map::new();
+
+        match self.schema.base_type(data_type) {
+            "string_t" => {
+                out.insert("type".to_string(), json!("string"));
+            }
+            "integer_t" | "long_t" => {
+                out.insert("type".to_string(), json!("integer"));
+            }
+            "float_t" => {
+                out.insert("type".to_string(), json!("number"));
+            }
+            "boolean_t" => {
+                out.insert("type".to_string(), json!("boolean"));
+            }
+            // json_t and unknown types accept any JSON value
+            _ => {}
+        }
+
+        if let Some(type_def) = self.schema.types.get(data_type) {
+            if let Some(regex) = &type_def.regex {
+                out.insert("pattern".to_string(), json!(regex));
+            }
+            if let Some(max_len) = type_def.max_len {
+                out.insert("maxLength".to_string(), json!(max_len));
+            }
+            if let Some([min, max]) = type_def.range.as_deref() {
+                out.insert("minimum".to_string(), json!(min));
+                out.insert("maximum".to_string(), json!(max));
+            }
+        }
+
+        if let Some(enum_values) = &attr.enum_values {
+            let numeric = matches!(out.get("type"), Some(Value::String(t)) if t == "integer");
+            let mut values: Vec<Value> = enum_values
+                .keys()
+                .map(|k| match k.parse::<i64>() {
+                    Ok(n) if numeric => json!(n),
+                    _ => json!(k),
+                })
+                .collect();
+            values.sort_by_key(|v| (v.as_i64(), v.to_string()));
+            out.insert("enum".to_string(), Value::Array(values));
+        }
+
+        Value::Object(out)
+    }
+
+    fn define_object(&mut self, object_type: &str) {
+        if self.defs.contains_key(object_type) {
+            return;
+        }
+        let Some(object) = self.schema.objects.get(object_type) else {
+            self.defs
+                .insert(object_type.to_string(), json!({ "type": "object" }));
+            return;
+        };
+
+        // Reserve the slot first so recursive references (e.g. process.parent_process) terminate
+        self.defs.insert(object_type.to_string(), json!({}));
+        let (properties, required) = self.properties(&object.attributes);
+
+        let mut def = Map::new();
+        def.insert(
+            "title".to_string(),
+            json!(
+                object
+                    .caption
+                    .clone()
+                    .unwrap_or_else(|| object.name.clone())
+            ),
+        );
+        def.insert("type".to_string(), json!("object"));
+        def.insert("properties".to_string(), Value::Object(properties));
+        if !required.is_empty() {
+            def.insert("required".to_string(), json!(required));
+        }
+        // Generic objects (e.g. `unmapped`) have no attributes and accept anything
+        if self.options.strict && !object.attributes.is_empty() {
+            def.insert("additionalProperties".to_string(), json!(false));
+        }
+
+        self.defs
+            .insert(object_type.to_string(), Value::Object(def));
+    }
+}
@@ -0,0 +1,814 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+use super::builder::OcsfEventBuilder;
+use super::event::OcsfEvent;
+use super::parsers::LogFormat;
+use super::schema::OcsfSchema;
+use super::validation::ValidationReport;
+
+const BUILTIN_MAPPINGS: &[&str] = &[
+    include_str!("../../data/mappings/sshd.yaml"),
+    include_str!("../../data/mappings/sudo.yaml"),
+    include_str!("../../data/mappings/auditd.yaml"),
+    include_str!("../../data/mappings/http_access.yaml"),
+    include_str!("../../data/mappings/zeek.yaml"),
+    include_str!("../../data/mappings/suricata.yaml"),
+    include_str!("../../data/mappings/cloudtrail.yaml"),
+    include_str!("../../data/mappings/azure_activity.yaml"),
+    include_str!("../../data/mappings/gcp_audit.yaml"),
+    include_str!("../../data/mappings/k8s_audit.yaml"),
+    include_str!("../../data/mappings/windows_security.yaml"),
+];
+
+/// A declarative source → OCSF mapping, written as YAML or JSON
+///
+/// ```yaml
+/// name: sshd
+/// version: 1.4.0
+/// class: authentication
+/// class_rules:
+///   - when: { field: action, equals: exec }
+///     class: process_activity
+/// fields:
+///   - { source: ts, target: time, transforms: [parse_timestamp] }
+///   - { source: user, target: user.name }
+///   - source: result
+///     target: status_id
+///     transforms: [{ lookup: { table: { accepted: 1, failed: 2 }, default: 99 } }]
+///   - { sources: [host, domain], target: device.hostname, transforms: [{ concat: { separator: "." } }] }
+///   - { target: severity_id, value: 1 }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MappingDefinition {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// OCSF schema version the mapping targets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// How raw input is turned into records: `json` (the default), `syslog`, `cef`, `leef`,
+    /// `key_value`, `csv`, `auditd`, `zeek`, `windows_xml`, or `text` (the whole line becomes the
+    /// `message` field)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<LogFormat>,
+    /// For JSON input, the dotted path of a record array wrapping the records, e.g. `Records` in
+    /// CloudTrail exports; documents without it are records themselves
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub records_path: Option<String>,
+    /// Event class used when no class rule matches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    /// Conditional class selection; the first matching rule wins
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub class_rules: Vec<ClassRule>,
+    /// Without a default `class`, skip records no class rule matches instead of failing them
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_unmatched: bool,
+    /// What becomes of the source fields no rule maps; all of them go to `unmapped` by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unmapped: Option<UnmappedOptions>,
+    pub fields: Vec<FieldRule>,
+}
+
+/// Where the source fields a record's rules leave unread go
+///
+/// ```yaml
+/// unmapped:
+///   keep: allowlist
+///   allow: [requestParameters, EventData.LogonGuid]
+///   raw_data: true
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct UnmappedOptions {
+    #[serde(default)]
+    pub keep: UnmappedPolicy,
+    /// Source fields kept by `allowlist`, as dotted paths; a path keeps the fields below it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// Also put the text each record was read from in `raw_data`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub raw_data: bool,
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum UnmappedPolicy {
+    /// Every leftover field goes to `unmapped`
+    #[default]
+    All,
+    /// Leftover fields are dropped
+    None,
+    /// Only the leftover fields under `allow` go to `unmapped`
+    Allowlist,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ClassRule {
+    pub when: Condition,
+    pub class: String,
+}
+
+/// How one OCSF attribute is produced from a record
+///
+/// The input is `value` (a constant), `source` (a field, dotted paths allowed) or `sources`
+/// (an array of fields, e.g. for `concat`). Transforms run in order; if the input is missing or
+/// a transform yields nothing, `default` is used, and without a default the rule is skipped.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FieldRule {
+    /// Dotted OCSF attribute path, e.g. `src_endpoint.ip`; a numeric segment indexes an array,
+    /// e.g. `evidences.0.src_endpoint.ip`
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<Transform>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    /// Apply the rule only when the condition holds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<Condition>,
+    /// Apply the rule only to records mapped to one of these classes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Transform {
+    /// Convert to `integer`, `float`, `string` or `boolean`; integers may be written in `0x` hex
+    /// (e.g. Windows process ids)
+    Cast(CastType),
+    /// Replace the value through a table (e.g. source outcome → `status_id`)
+    Lookup {
+        table: Map<String, Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<Value>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        case_insensitive: bool,
+    },
+    /// Parse a timestamp to epoch milliseconds
+    ParseTimestamp {
+        /// chrono format string; RFC 3339, RFC 2822, common layouts and epoch numbers otherwise
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        format: Option<String>,
+        /// Unit of numeric timestamps; guessed from the magnitude otherwise
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        unit: Option<TimeUnit>,
+        /// Year of syslog (RFC 3164) timestamps like `Jan 15 10:30:00`, which carry none;
+        /// such timestamps fail to parse without it
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        year: Option<i32>,
+    },
+    /// Extract a capture group (the first group by default, the whole match without groups)
+    Regex {
+        pattern: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        group: Option<usize>,
+    },
+    /// Join the `sources` values (skipping missing ones)
+    Concat {
+        #[serde(default)]
+        separator: String,
+    },
+    /// Put the value in an object under `key`; arrays become arrays of such objects
+    /// (e.g. DNS answers to `[{ rdata: ... }]`)
+    Wrap {
+        key: String,
+    },
+    /// Build an object from fields of the value (or of each element of an array), as
+    /// `{ new_key: source_key }`, e.g. `{ select: { type: rrtype, rdata: rdata } }`; dotted new
+    /// keys nest, e.g. `{ image.name: image }`
+    Select(BTreeMap<String, String>),
+    Lowercase,
+    Uppercase,
+    Trim,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CastType {
+    Integer,
+    Float,
+    String,
+    Boolean,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeUnit {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+}
+
+/// A predicate on a record field; every given check must hold (only `field`: it must exist)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Condition {
+    pub field: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equals: Option<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub one_of: Vec<Value>,
+    /// Regular expression the value must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matches: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exists: Option<bool>,
+}
+
+impl MappingDefinition {
+    /// Parse a mapping from YAML or JSON text
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        // Go through `Value` so transforms read as `{ cast: integer }` rather than YAML tags
+        let value: Value = if text.trim_start().starts_with('{') {
+            serde_json::from_str(text)
+                .map_err(|e| anyhow::anyhow!("Invalid mapping JSON: {}", e))?
+        } else {
+            serde_yaml::from_str(text)
+                .map_err(|e| anyhow::anyhow!("Invalid mapping YAML: {}", e))?
+        };
+        let mut value = value;
+        // Transforms whose options are all optional may be written bare, e.g. `[parse_timestamp]`
+        if let Some(fields) = value.get_mut("fields").and_then(Value::as_array_mut) {
+            let transforms = fields
+                .iter_mut()
+                .filter_map(|rule| rule.get_mut("transforms").and_then(Value::as_array_mut))
+                .flatten();
+            for transform in transforms {
+                if let Some(name @ ("parse_timestamp" | "concat")) = transform.as_str() {
+                    *transform = json!({ name: {} });
+                }
+            }
+        }
+        serde_json::from_value(value).map_err(|e| anyhow::anyhow!("Invalid mapping: {}", e))
+    }
+
+    /// Mapping packs bundled with the server
+    pub fn builtins() -> Vec<MappingDefinition> {
+        BUILTIN_MAPPINGS
+            .iter()
+            .map(|yaml| Self::parse(yaml).expect("built-in mappings are valid"))
+            .collect()
+    }
+
+    pub fn builtin(name: &str) -> Option<MappingDefinition> {
+        Self::builtins()
+            .into_iter()
+            .find(|m| m.name.as_deref() == Some(name))
+    }
+
+    pub fn to_yaml(&self) -> anyhow::Result<String> {
+        // Through `Value` again, the inverse of `parse`
+        let value = serde_json::to_value(self)?;
+        serde_yaml::to_string(&value).map_err(|e| anyhow::anyhow!(e.to_string()))
+    }
+}
+
+/// The result of mapping one record
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MappedEvent {
+    pub event: OcsfEvent,
+    pub validation: ValidationReport,
+    /// Source fields no rule mapped, whether or not the policy kept them under `unmapped`
+    pub unmapped_count: usize,
+}
+
+/// Applies a `MappingDefinition` to raw records
+pub struct MappingEngine<'a> {
+    schema: &'a OcsfSchema,
+    definition: &'a MappingDefinition,
+    regexes: HashMap<String, Regex>,
+}
+
+impl<'a> MappingEngine<'a> {
+    /// Prepare a mapping, checking its classes exist and compiling its regular expressions
+    pub fn new(schema: &'a OcsfSchema, definition: &'a MappingDefinition) -> anyhow::Result<Self> {
+        if definition.class.is_none() && definition.class_rules.is_empty() {
+            anyhow::bail!("Mapping must set 'class' or 'class_rules'");
+        }
+        let classes = definition
+            .class
+            .iter()
+            .chain(definition.class_rules.iter().map(|r| &r.class));
+        let classes: Vec<&String> = classes.collect();
+        for class in &classes {
+            if schema.get_event_class(class).is_none() {
+                anyhow::bail!(
+                    "Event class '{}' not found in schema version {}",
+                    class,
+                    schema.version
+                );
+            }
+        }
+        for rule in &definition.fields {
+            if let Some(class) = rule.classes.iter().find(|c| !classes.contains(c)) {
+                anyhow::bail!(
+                    "{}: class '{}' is not one of the mapping's classes",
+                    rule.target,
+                    class
+                );
+            }
+        }
+
+        let conditions = definition
+            .class_rules
+            .iter()
+            .map(|r| &r.when)
+            .chain(definition.fields.iter().filter_map(|f| f.when.as_ref()));
+        let mut patterns: Vec<&str> = conditions.filter_map(|c| c.matches.as_deref()).collect();
+        for rule in &definition.fields {
+            for transform in &rule.transforms {
+                if let Transform::Regex { pattern, .. } = transform {
+                    patterns.push(pattern);
+                }
+            }
+        }
+        let mut regexes = HashMap::new();
+        for pattern in patterns {
+            let regex = Regex::new(pattern)
+                .map_err(|e| anyhow::anyhow!("Invalid regex '{}': {}", pattern, e))?;
+            regexes.insert(pattern.to_string(), regex);
+        }
+
+        Ok(Self {
+            schema,
+            definition,
+            regexes,
+        })
+    }
+
+    /// Event class for a record: the first matching class rule, else the default class
+    pub fn select_class(&self, record: &Value) -> anyhow::Result<&'a str> {
+        self.definition
+            .class_rules
+            .iter()
+            .find(|rule| self.condition_holds(&rule.when, record))
+            .map(|rule| rule.class.as_str())
+            .or(self.definition.class.as_deref())
+            .ok_or_else(|| anyhow::anyhow!("No class rule matches the record"))
+    }
+
+    /// Whether a record is left out: `skip_unmatched` is set and no class applies to it
+    pub fn skips(&self, record: &Value) -> bool {
+        self.definition.skip_unmatched && self.select_class(record).is_err()
+    }
+
+    /// Map a record to an OCSF event and validate it
+    pub fn apply(&self, record: &Value) -> anyhow::Result<MappedEvent> {
+        self.apply_source(record, None)
+    }
+
+    /// Map a record read from `raw` text; `raw_data`, when the mapping asks for it, holds that
+    /// text rather than the record as JSON
+    pub fn apply_source(&self, record: &Value, raw: Option<&str>) -> anyhow::Result<MappedEvent> {
+        let class = self.select_class(record)?;
+        let (mut fields, read) = self.map_record(record)?;
+
+        let mut leftover = record.clone();
+        for path in read {
+            remove_path(&mut leftover, path);
+        }
+        prune_empty(&mut leftover);
+        let unmapped_count = count_fields(&leftover);
+        let options = self.definition.unmapped.clone().unwrap_or_default();
+        if let Value::Object(mut leftover) = leftover {
+            match options.keep {
+                UnmappedPolicy::All => {}
+                UnmappedPolicy::None => leftover.clear(),
+                UnmappedPolicy::Allowlist => retain_allowed("", &mut leftover, &options.allow),
+            }
+            // Rules may target `unmapped` themselves; what they set wins
+            if !leftover.is_empty() {
+                let slot = fields
+                    .entry("unmapped")
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if let Value::Object(unmapped) = slot {
+                    for (key, value) in leftover {
+                        unmapped.entry(key).or_insert(value);
+                    }
+                }
+            }
+        }
+        if options.raw_data && !fields.contains_key("raw_data") {
+            let raw = raw.map_or_else(|| record.to_string(), str::to_string);
+            fields.insert("raw_data".to_string(), json!(raw));
+        }
+
+        let (event, validation) = OcsfEventBuilder::new(self.schema, class)?
+            .set_fields(fields)?
+            .build()?;
+        Ok(MappedEvent {
+            event,
+            validation,
+            unmapped_count,
+        })
+    }
+
+    /// Produce the nested OCSF attributes of a record, before schema checks
+    #[allow(dead_code)]
+    pub fn map_fields(&self, record: &Value) -> anyhow::Result<Map<String, Value>> {
+        self.map_record(record).map(|(fields, _)| fields)
+    }
+
+    /// The nested OCSF attributes of a record and the source paths of the rules that applied
+    fn map_record(&self, record: &Value) -> anyhow::Result<(Map<String, Value>, Vec<&'a str>)> {
+        let mut out = Map::new();
+        let mut read = Vec::new();
+        let class = self.select_class(record).ok();
+        for rule in &self.definition.fields {
+            if !rule.classes.is_empty()
+                && !class.is_some_and(|class| rule.classes.iter().any(|c| c == class))
+            {
+                continue;
+            }
+            if let Some(when) = &rule.when
+                && !self.condition_holds(when, record)
+            {
+                continue;
+            }
+            read.extend(rule.source.as_deref());
+            read.extend(rule.sources.iter().map(String::as_str));
+            let value = self
+                .apply_rule(rule, record)
+                .map_err(|e| anyhow::anyhow!("{}: {}", rule.target, e))?;
+            if let Some(value) = value.or_else(|| rule.default.clone()) {
+                insert_path(&mut out, &rule.target, value)?;
+            }
+        }
+        Ok((out, read))
+    }
+
+    fn apply_rule(&self, rule: &FieldRule, record: &Value) -> anyhow::Result<Option<Value>> {
+        let input = if let Some(value) = &rule.value {
+            Some(value.clone())
+        } else if !rule.sources.is_empty() {
+            let values: Vec<Value> = rule
+                .sources
+                .iter()
+                .filter_map(|s| lookup(record, s).cloned())
+                .filter(|v| !v.is_null())
+                .collect();
+            (!values.is_empty()).then_some(Value::Array(values))
+        } else if let Some(source) = &rule.source {
+            lookup(record, source).filter(|v| !v.is_null()).cloned()
+        } else {
+            anyhow::bail!("rule needs 'source', 'sources' or 'value'");
+        };
+
+        rule.transforms
+            .iter()
+            .try_fold(input, |value, transform| match value {
+                Some(value) => self.transform(transform, value),
+                None => Ok(None),
+            })
+    }
+
+    fn transform(&self, transform: &Transform, value: Value) -> anyhow::Result<Option<Value>> {
+        Ok(match transform {
+            Transform::Cast(cast) => Some(cast_value(*cast, &value)?),
+            Transform::Lookup {
+                table,
+                default,
+                case_insensitive,
+            } => {
+                let key = value_to_string(&value);
+                let found = if *case_insensitive {
+                    table
+                        .iter()
+                        .find(|(k, _)| k.eq_ignore_ascii_case(&key))
+                        .map(|(_, v)| v)
+                } else {
+                    table.get(&key)
+                };
+                found.or(default.as_ref()).cloned()
+            }
+            Transform::ParseTimestamp { format, unit, year } => Some(json!(
+                parse_timestamp_ms(&value, format.as_deref(), *unit, *year)
+                    .ok_or_else(|| anyhow::anyhow!("cannot parse {} as a timestamp", value))?
+            )),
+            Transform::Regex { pattern, group } => {
+                let regex = &self.regexes[pattern];
+                let text = value_to_string(&value);
+                regex.captures(&text).and_then(|captures| {
+                    let group = group.unwrap_or(if captures.len() > 1 { 1 } else { 0 });
+                    captures.get(group).map(|m| json!(m.as_str()))
+                })
+            }
+            Transform::Concat { separator } => Some(match value {
+                Value::Array(items) => json!(
+                    items
+                        .iter()
+                        .map(value_to_string)
+                        .collect::<Vec<_>>()
+                        .join(separator)
+                ),
+                other => json!(value_to_string(&other)),
+            }),
+            Transform::Wrap { key } => Some(match value {
+                Value::Array(items) => items.into_iter().map(|item| json!({ key: item })).collect(),
+                other => json!({ key: other }),
+            }),
+            Transform::Select(keys) => {
+                let select = |item: &Value| -> anyhow::Result<Value> {
+                    let mut out = Map::new();
+                    for (to, from) in keys {
+                        if let Some(v) = lookup(item, from).filter(|v| !v.is_null()) {
+                            insert_path(&mut out, to, v.clone())?;
+                        }
+                    }
+                    Ok(out.into())
+                };
+                Some(match &value {
+                    Value::Array(items) => items.iter().map(select).collect::<Result<_, _>>()?,
+                    other => select(other)?,
+                })
+            }
+            Transform::Lowercase => Some(json!(value_to_string(&value).to_lowercase())),
+            Transform::Uppercase => Some(json!(value_to_string(&value).to_uppercase())),
+            Transform::Trim => Some(json!(value_to_string(&value).trim())),
+        })
+    }
+
+    fn condition_holds(&self, condition: &Condition, record: &Value) -> bool {
+        let value = lookup(record, &condition.field).filter(|v| !v.is_null());
+        if let Some(exists) = condition.exists
+            && exists != value.is_some()
+        {
+            return false;
+        }
+        let Some(value) = value else {
+            return condition.exists == Some(false);
+        };
+
+        let text = value_to_string(value);
+        let same = |expected: &Value| value == expected || text == value_to_string(expected);
+        condition.equals.as_ref().is_none_or(same)
+            && (condition.one_of.is_empty() || condition.one_of.iter().any(same))
+            && condition
+                .matches
+                .as_ref()
+                .is_none_or(|pattern| self.regexes[pattern].is_match(&text))
+    }
+}
+
+/// Find a record field by exact key first, then by dotted path (array indexes allowed); keys
+/// containing dots themselves (e.g. claim URIs) are matched at any depth
+pub fn lookup<'v>(record: &'v Value, path: &str) -> Option<&'v Value> {
+    if let Some(value) = child(record, path) {
+        return Some(value);
+    }
+    path.match_indices('.').find_map(|(at, _)| {
+        let value = child(record, &path[..at])?;
+        lookup(value, &path[at + 1..])
+    })
+}
+
+/// One step into an object by key or into an array by index
+fn child<'v>(value: &'v Value, key: &str) -> Option<&'v Value> {
+    match value {
+        Value::Object(map) => map.get(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    }
+}
+
+fn child_mut<'v>(value: &'v mut Value, key: &str) -> Option<&'v mut Value> {
+    match value {
+        Value::Object(map) => map.get_mut(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+        _ => None,
+    }
+}
+
+/// Remove the field `lookup` finds at `path`; array elements become null to keep the indexes
+/// of the others
+fn remove_path(record: &mut Value, path: &str) -> bool {
+    if let Some(value) = child_mut(record, path) {
+        *value = Value::Null;
+        return true;
+    }
+    path.match_indices('.').any(|(at, _)| {
+        child_mut(record, &path[..at]).is_some_and(|value| remove_path(value, &path[at + 1..]))
+    })
+}
+
+/// Drop nulls and the objects and arrays left empty; true when nothing is left
+fn prune_empty(value: &mut Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Object(map) => {
+            map.retain(|_, value| !prune_empty(value));
+            map.is_empty()
+        }
+        Value::Array(items) => {
+            let mut empty = true;
+            for item in items.iter_mut() {
+                if prune_empty(item) {
+                    *item = Value::Null;
+                } else {
+                    empty = false;
+                }
+            }
+            empty
+        }
+        _ => false,
+    }
+}
+
+/// Fields of a record: object members are counted through, anything else is one field
+fn count_fields(value: &Value) -> usize {
+    match value {
+        Value::Object(map) => map.values().map(count_fields).sum(),
+        Value::Null => 0,
+        _ => 1,
+    }
+}
+
+/// Keep the fields at or below one of the `allow` paths, whether nested or dotted keys
+fn retain_allowed(prefix: &str, object: &mut Map<String, Value>, allow: &[String]) {
+    object.retain(|key, value| {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        if allow
+            .iter()
+            .any(|a| path == *a || path.starts_with(&format!("{a}.")))
+        {
+            return true;
+        }
+        match value {
+            Value::Object(inner) if allow.iter().any(|a| a.starts_with(&format!("{path}."))) => {
+                retain_allowed(&path, inner, allow);
+                !inner.is_empty()
+            }
+            _ => false,
+        }
+    });
+}
+
+/// Set a dotted path in a nested map, creating intermediate objects and arrays
+fn insert_path(out: &mut Map<String, Value>, path: &str, value: Value) -> anyhow::Result<()> {
+    let segments: Vec<&str> = path.split('.').collect();
+    let (first, rest) = segments.split_first().unwrap_or((&"", &[]));
+    let slot = out.entry(first.to_string()).or_insert(Value::Null);
+    insert_at(slot, first, rest, path, value)
+}
+
+/// Highest array index a target path may use; larger ones would pad the array with nulls
+pub const MAX_TARGET_INDEX: usize = 255;
+
+/// Set `segments` below `slot` (named `name`); numeric segments index arrays, padding with nulls
+fn insert_at(
+    slot: &mut Value,
+    name: &str,
+    segments: &[&str],
+    path: &str,
+    value: Value,
+) -> anyhow::Result<()> {
+    let Some((segment, rest)) = segments.split_first() else {
+        *slot = value;
+        return Ok(());
+    };
+    if let Ok(index) = segment.parse::<usize>() {
+        if index > MAX_TARGET_INDEX {
+            anyhow::bail!(
+                "index {} in '{}' is above the limit of {}",
+                index,
+                path,
+                MAX_TARGET_INDEX
+            );
+        }
+        if slot.is_null() {
+            *slot = Value::Array(Vec::new());
+        }
+        let Value::Array(items) = slot else {
+            anyhow::bail!("'{}' in '{}' is not an array", name, path);
+        };
+        if items.len() <= index {
+            items.resize(index + 1, Value::Null);
+        }
+        return insert_at(&mut items[index], segment, rest, path, value);
+    }
+    if slot.is_null() {
+        *slot = Value::Object(Map::new());
+    }
+    let Value::Object(map) = slot else {
+        anyhow::bail!("'{}' in '{}' is not an object", name, path);
+    };
+    let slot = map.entry(segment.to_string()).or_insert(Value::Null);
+    insert_at(slot, segment, rest, path, value)
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn cast_value(cast: CastType, value: &Value) -> anyhow::Result<Value> {
+    let text = value_to_string(value);
+    let text = text.trim();
+    let cast_error = || anyhow::anyhow!("cannot cast {} to {:?}", value, cast);
+    Ok(match cast {
+        CastType::String => json!(text),
+        CastType::Integer => match value {
+            Value::Bool(b) => json!(i64::from(*b)),
+            Value::Number(n) if n.is_f64() => json!(n.as_f64().unwrap_or_default().trunc() as i64),
+            _ => match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+                Some(hex) => json!(i64::from_str_radix(hex, 16).map_err(|_| cast_error())?),
+                None => json!(text.parse::<i64>().map_err(|_| cast_error())?),
+            },
+        },
+        CastType::Float => json!(text.parse::<f64>().map_err(|_| cast_error())?),
+        CastType::Boolean => match text.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" | "on" => json!(true),
+            "false" | "no" | "0" | "off" => json!(false),
+            _ => return Err(cast_error()),
+        },
+    })
+}
+
+/// Parse a timestamp (string or number) to epoch milliseconds; year-less syslog timestamps
+/// only parse with a `year`
+pub fn parse_timestamp_ms(
+    value: &Value,
+    format: Option<&str>,
+    unit: Option<TimeUnit>,
+    year: Option<i32>,
+) -> Option<i64> {
+    use chrono::{DateTime, NaiveDateTime};
+
+    let from_number = |n: f64| {
+        let unit = unit.unwrap_or(match n.abs() {
+            n if n < 1e11 => TimeUnit::Seconds,
+            n if n < 1e14 => TimeUnit::Milliseconds,
+            n if n < 1e17 => TimeUnit::Microseconds,
+            _ => TimeUnit::Nanoseconds,
+        });
+        let ms = match unit {
+            TimeUnit::Seconds => n * 1e3,
+            TimeUnit::Milliseconds => n,
+            TimeUnit::Microseconds => n / 1e3,
+            TimeUnit::Nanoseconds => n / 1e6,
+        };
+        Some(ms.round() as i64)
+    };
+
+    let text = match value {
+        Value::Number(n) => return n.as_f64().and_then(from_number),
+        Value::String(s) => s.trim(),
+        _ => return None,
+    };
+
+    if let Some(format) = format {
+        return DateTime::parse_from_str(text, format)
+            .map(|dt| dt.timestamp_millis())
+            .or_else(|_| {
+                NaiveDateTime::parse_from_str(text, format)
+                    .map(|dt| dt.and_utc().timestamp_millis())
+            })
+            .ok();
+    }
+    if let Ok(n) = text.parse::<f64>() {
+        return from_number(n);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(text) {
+        return Some(dt.timestamp_millis());
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(text) {
+        return Some(dt.timestamp_millis());
+    }
+    for layout in [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f%z",
+        "%Y/%m/%d %H:%M:%S%.f",
+        "%d/%b/%Y:%H:%M:%S %z",
+    ] {
+        if let Ok(dt) = DateTime::parse_from_str(text, layout) {
+            return Some(dt.timestamp_millis());
+        }
+        if let Ok(dt) = NaiveDateTime::parse_from_str(text, layout) {
+            return Some(dt.and_utc().timestamp_millis());
+        }
+    }
+    // Syslog timestamps carry no year, so the mapping has to give it
+    let with_year = format!("{} {}", year?, text);
+    NaiveDateTime::parse_from_str(&with_year, "%Y %b %e %H:%M:%S")
+        .ok()
+        .map(|dt| dt.and_utc().timestamp_millis())
+}
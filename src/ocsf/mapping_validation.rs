@@ -0,0 +1,628 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::inference::parse_sample;
+use super::mapping::{
+    CastType, FieldRule, MappingDefinition, MappingEngine, Transform, UnmappedPolicy,
+    MAX_TARGET_INDEX,
+};
+use super::schema::{Attribute, EventClass, OcsfSchema};
+
+/// Required attributes the event builder always supplies
+const BUILDER_SUPPLIED: &[&str] = &["class_uid", "category_uid", "type_uid", "metadata"];
+
+/// A problem found in a mapping, tied to a field rule when there is one
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MappingIssue {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub message: String,
+}
+
+/// Required-attribute coverage of one event class the mapping can produce
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ClassCoverage {
+    pub class: String,
+    pub required: Vec<String>,
+    pub missing: Vec<String>,
+    pub coverage_percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MappingValidationReport {
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<String>,
+    pub is_valid: bool,
+    pub errors: Vec<MappingIssue>,
+    pub warnings: Vec<MappingIssue>,
+    pub classes: Vec<ClassCoverage>,
+    /// Lowest required-attribute coverage across the mapping's classes
+    pub coverage_percent: f64,
+    /// Sample fields no rule or condition reads
+    pub unmapped_source_fields: Vec<String>,
+    /// Share of sample fields read by the mapping, when samples were given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_coverage_percent: Option<f64>,
+}
+
+/// What a rule produces, as far as it can be known without running it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Output {
+    Integer,
+    Float,
+    String,
+    Boolean,
+    Timestamp,
+    Array,
+    Object,
+    Unknown,
+}
+
+impl Output {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Number(n) if n.is_f64() => Self::Float,
+            Value::Number(_) => Self::Integer,
+            Value::String(_) => Self::String,
+            Value::Bool(_) => Self::Boolean,
+            Value::Array(_) => Self::Array,
+            Value::Object(_) => Self::Object,
+            Value::Null => Self::Unknown,
+        }
+    }
+}
+
+/// Where a target path ends up in a class
+enum Resolved<'a> {
+    Attribute(&'a Attribute),
+    /// Inside a free-form object (e.g. `unmapped`) or a class without attribute definitions
+    Open,
+}
+
+/// Check a mapping against a schema without needing real traffic
+///
+/// Target paths must resolve in every class the mapping can produce, rule outputs must fit the
+/// attribute types, and required attributes should be covered. Sample records, when given, are
+/// run through the mapping and their fields compared with what the rules read.
+pub fn validate_mapping(
+    schema: &OcsfSchema,
+    definition: &MappingDefinition,
+    samples: &[Value],
+) -> MappingValidationReport {
+    let mut report = MappingValidationReport {
+        version: schema.version.clone(),
+        mapping: definition.name.clone(),
+        is_valid: true,
+        errors: Vec::new(),
+        warnings: Vec::new(),
+        classes: Vec::new(),
+        coverage_percent: 0.0,
+        unmapped_source_fields: Vec::new(),
+        source_coverage_percent: None,
+    };
+    let issue = |rule: Option<usize>, target: Option<&str>, message: String| MappingIssue {
+        rule,
+        target: target.map(str::to_string),
+        message,
+    };
+
+    let mut class_names: Vec<&str> = definition.class.iter().map(String::as_str).collect();
+    for rule in &definition.class_rules {
+        if !class_names.contains(&rule.class.as_str()) {
+            class_names.push(&rule.class);
+        }
+    }
+    if class_names.is_empty() {
+        report.errors.push(issue(
+            None,
+            None,
+            "Mapping must set 'class' or 'class_rules'".to_string(),
+        ));
+    }
+    let mut classes: Vec<&EventClass> = Vec::new();
+    for name in class_names {
+        match schema.get_event_class(name) {
+            Some(ec) => classes.push(ec),
+            None => report.errors.push(issue(
+                None,
+                None,
+                format!(
+                    "Event class '{}' not found in schema version {}",
+                    name, schema.version
+                ),
+            )),
+        }
+    }
+
+    for (index, rule) in definition.fields.iter().enumerate() {
+        let target = Some(rule.target.as_str());
+        for message in rule_shape_errors(rule) {
+            report.errors.push(issue(Some(index), target, message));
+        }
+
+        for class in &rule.classes {
+            if !classes.iter().any(|ec| &ec.name == class) {
+                report.errors.push(issue(
+                    Some(index),
+                    target,
+                    format!("Class '{}' is not one of the mapping's classes", class),
+                ));
+            }
+        }
+        let rule_classes: Vec<&&EventClass> = classes
+            .iter()
+            .filter(|ec| rule.classes.is_empty() || rule.classes.contains(&ec.name))
+            .collect();
+
+        let mut resolved_anywhere = false;
+        let mut unresolved = Vec::new();
+        for ec in &rule_classes {
+            match resolve_target(schema, ec, &rule.target) {
+                Ok(Resolved::Open) => resolved_anywhere = true,
+                Ok(Resolved::Attribute(attr)) => {
+                    resolved_anywhere = true;
+                    if let Some(deprecated) = &attr.deprecated {
+                        report.warnings.push(issue(
+                            Some(index),
+                            target,
+                            format!(
+                                "'{}' is deprecated{}",
+                                rule.target,
+                                deprecated
+                                    .message
+                                    .as_deref()
+                                    .map(|m| format!(": {m}"))
+                                    .unwrap_or_default()
+                            ),
+                        ));
+                    }
+                    for (is_error, message) in type_issues(schema, rule, attr) {
+                        let found = issue(Some(index), target, format!("{}: {}", ec.name, message));
+                        if is_error {
+                            report.errors.push(found);
+                        } else {
+                            report.warnings.push(found);
+                        }
+                    }
+                }
+                Err(message) => unresolved.push(format!("{}: {}", ec.name, message)),
+            }
+        }
+        if !rule_classes.is_empty() {
+            let unresolved = unresolved
+                .into_iter()
+                .map(|m| issue(Some(index), target, m));
+            if resolved_anywhere {
+                // Records of these classes fail when the rule applies to them
+                report.warnings.extend(unresolved);
+            } else {
+                report.errors.extend(unresolved);
+            }
+        }
+    }
+
+    report.classes = classes
+        .iter()
+        .map(|ec| class_coverage(ec, definition))
+        .collect();
+    report.coverage_percent = report
+        .classes
+        .iter()
+        .map(|c| c.coverage_percent)
+        .fold(None, |min: Option<f64>, c| {
+            Some(min.map_or(c, |m| m.min(c)))
+        })
+        .unwrap_or(0.0);
+    for coverage in &report.classes {
+        for missing in &coverage.missing {
+            let note = if matches!(missing.as_str(), "activity_id" | "severity_id") {
+                " (defaults to 0, Unknown)"
+            } else {
+                ""
+            };
+            report.warnings.push(issue(
+                None,
+                None,
+                format!(
+                    "Required attribute '{}' of '{}' is not mapped{}",
+                    missing, coverage.class, note
+                ),
+            ));
+        }
+    }
+
+    if let Some(unmapped) = &definition.unmapped {
+        if unmapped.keep == UnmappedPolicy::Allowlist && unmapped.allow.is_empty() {
+            report.warnings.push(issue(
+                None,
+                None,
+                "'unmapped' keeps an empty allowlist, so no leftover field is kept".to_string(),
+            ));
+        }
+        if unmapped.keep != UnmappedPolicy::Allowlist && !unmapped.allow.is_empty() {
+            report.warnings.push(issue(
+                None,
+                None,
+                "'unmapped.allow' is only used with 'keep: allowlist'".to_string(),
+            ));
+        }
+    }
+
+    if !samples.is_empty() {
+        check_samples(schema, definition, samples, &mut report);
+    }
+
+    report.is_valid = report.errors.is_empty();
+    report
+}
+
+/// Structural problems of a rule that do not depend on the schema
+fn rule_shape_errors(rule: &FieldRule) -> Vec<String> {
+    let mut errors = Vec::new();
+    let inputs = usize::from(rule.source.is_some())
+        + usize::from(!rule.sources.is_empty())
+        + usize::from(rule.value.is_some());
+    if inputs == 0 {
+        errors.push("rule needs 'source', 'sources' or 'value'".to_string());
+    } else if inputs > 1 {
+        errors.push("rule must use only one of 'source', 'sources' and 'value'".to_string());
+    }
+    if rule.target.is_empty() || rule.target.split('.').any(str::is_empty) {
+        errors.push(format!("invalid target path '{}'", rule.target));
+    }
+    let indices = rule
+        .target
+        .split('.')
+        .filter_map(|s| s.parse::<usize>().ok());
+    if let Some(index) = indices.filter(|i| *i > MAX_TARGET_INDEX).max() {
+        errors.push(format!(
+            "index {} in target '{}' is above the limit of {}",
+            index, rule.target, MAX_TARGET_INDEX
+        ));
+    }
+
+    let patterns = rule
+        .transforms
+        .iter()
+        .filter_map(|t| match t {
+            Transform::Regex { pattern, .. } => Some(pattern),
+            _ => None,
+        })
+        .chain(rule.when.iter().filter_map(|c| c.matches.as_ref()));
+    for pattern in patterns {
+        if let Err(e) = Regex::new(pattern) {
+            errors.push(format!("invalid regex '{}': {}", pattern, e));
+        }
+    }
+    errors
+}
+
+/// Resolve a dotted target path through the class and object definitions
+fn resolve_target<'a>(
+    schema: &'a OcsfSchema,
+    event_class: &'a EventClass,
+    path: &str,
+) -> Result<Resolved<'a>, String> {
+    if event_class.attributes.is_empty() {
+        return Ok(Resolved::Open);
+    }
+
+    let mut attributes = &event_class.attributes;
+    let mut owner = event_class.name.as_str();
+    let segments: Vec<&str> = path.split('.').collect();
+    let mut next = 0;
+    loop {
+        let name = segments[next];
+        let attr = attributes
+            .get(name)
+            .ok_or_else(|| format!("'{}' is not defined in '{}'", name, owner))?;
+        next += 1;
+        let prefix = &segments[..next];
+        // An index into an array attribute, e.g. `evidences.0`
+        if attr.is_array
+            && segments
+                .get(next)
+                .is_some_and(|s| s.parse::<usize>().is_ok())
+        {
+            next += 1;
+        }
+        if next == segments.len() {
+            return Ok(Resolved::Attribute(attr));
+        }
+
+        let Some(object_type) = &attr.object_type else {
+            if attr.data_type.as_deref() == Some("json_t") {
+                return Ok(Resolved::Open);
+            }
+            return Err(format!("'{}' is not an object", prefix.join(".")));
+        };
+        match schema.objects.get(object_type) {
+            Some(object) if !object.attributes.is_empty() => {
+                attributes = &object.attributes;
+                owner = object_type;
+            }
+            _ => return Ok(Resolved::Open),
+        }
+    }
+}
+
+/// Values a rule can emit when they are known statically (constants, lookup tables, defaults)
+fn known_values(rule: &FieldRule) -> Vec<&Value> {
+    let mut values: Vec<&Value> = rule.value.iter().collect();
+    if let Some(Transform::Lookup { table, default, .. }) = rule.transforms.last() {
+        values.extend(table.values());
+        values.extend(default.iter());
+    }
+    values.extend(rule.default.iter());
+    values
+}
+
+/// Output type of a rule after its transforms
+fn rule_output(rule: &FieldRule) -> Output {
+    let start = match (&rule.value, rule.sources.is_empty()) {
+        (Some(value), _) => Output::of(value),
+        (None, false) => Output::Array,
+        (None, true) => Output::Unknown,
+    };
+    rule.transforms
+        .iter()
+        .fold(start, |output, transform| match transform {
+            Transform::Cast(CastType::Integer) => Output::Integer,
+            Transform::Cast(CastType::Float) => Output::Float,
+            Transform::Cast(CastType::String) => Output::String,
+            Transform::Cast(CastType::Boolean) => Output::Boolean,
+            Transform::ParseTimestamp { .. } => Output::Timestamp,
+            Transform::Lookup { table, default, .. } => {
+                let mut outputs = table.values().chain(default.iter()).map(Output::of);
+                match outputs.next() {
+                    Some(first) if outputs.all(|o| o == first) => first,
+                    _ => Output::Unknown,
+                }
+            }
+            Transform::Concat { .. } => Output::String,
+            Transform::Wrap { .. } | Transform::Select(_) => match output {
+                Output::Array | Output::Unknown => output,
+                _ => Output::Object,
+            },
+            Transform::Regex { .. }
+            | Transform::Lowercase
+            | Transform::Uppercase
+            | Transform::Trim => match output {
+                Output::Array => Output::Array,
+                _ => Output::String,
+            },
+        })
+}
+
+/// Type problems of a rule for an attribute, as `(is_error, message)`
+fn type_issues(schema: &OcsfSchema, rule: &FieldRule, attr: &Attribute) -> Vec<(bool, String)> {
+    let mut issues = Vec::new();
+    let data_type = attr.data_type.as_deref().unwrap_or("string_t");
+    let base = schema.base_type(data_type);
+
+    if let Some(values) = &attr.enum_values {
+        for value in known_values(rule) {
+            let valid = match value {
+                Value::Number(n) => n
+                    .as_i64()
+                    .is_some_and(|i| values.contains_key(&i.to_string())),
+                Value::String(s) => {
+                    values.contains_key(s.trim())
+                        || values.values().any(|v| {
+                            v.caption
+                                .as_deref()
+                                .is_some_and(|c| c.eq_ignore_ascii_case(s.trim()))
+                        })
+                }
+                _ => false,
+            };
+            if !valid {
+                issues.push((
+                    true,
+                    format!("{} is not a valid value of '{}'", value, rule.target),
+                ));
+            }
+        }
+    }
+
+    let output = rule_output(rule);
+    let expected = if attr.object_type.is_some() {
+        format!(
+            "a {} object",
+            attr.object_type.as_deref().unwrap_or_default()
+        )
+    } else {
+        data_type.to_string()
+    };
+    let mismatch = |is_error: bool, why: &str| {
+        (
+            is_error,
+            format!(
+                "'{}' expects {} but the rule produces {}{}",
+                rule.target,
+                expected,
+                match output {
+                    Output::Integer => "an integer",
+                    Output::Float => "a float",
+                    Output::String => "a string",
+                    Output::Boolean => "a boolean",
+                    Output::Timestamp => "epoch milliseconds",
+                    Output::Array => "an array",
+                    Output::Object => "an object",
+                    Output::Unknown => "an unknown type",
+                },
+                why
+            ),
+        )
+    };
+
+    if output == Output::Array && !attr.is_array {
+        issues.push(mismatch(true, "; join 'sources' with concat"));
+        return issues;
+    }
+    if attr.object_type.is_some() {
+        if !matches!(output, Output::Object | Output::Unknown | Output::Array) {
+            issues.push(mismatch(true, "; map the object's attributes instead"));
+        }
+        return issues;
+    }
+
+    // Constants and lookups with a default were checked value by value above
+    let enum_checked = attr.enum_values.is_some()
+        && (rule.value.is_some()
+            || matches!(
+                rule.transforms.last(),
+                Some(Transform::Lookup {
+                    default: Some(_),
+                    ..
+                })
+            ));
+    let problem = match (base, output) {
+        _ if enum_checked => None,
+        (_, Output::Unknown) => None,
+        ("json_t", _) => None,
+        ("integer_t" | "long_t", Output::Integer) => None,
+        ("integer_t" | "long_t", Output::Timestamp) if data_type == "timestamp_t" => None,
+        ("integer_t" | "long_t", Output::Timestamp) => Some((false, "")),
+        ("integer_t" | "long_t", Output::String) if data_type == "timestamp_t" => Some((
+            false,
+            "; only RFC 3339 strings convert, add parse_timestamp",
+        )),
+        ("integer_t" | "long_t", Output::String) if attr.enum_values.is_some() => {
+            Some((false, "; only exact captions convert, add a lookup"))
+        }
+        ("integer_t" | "long_t", Output::String | Output::Float) => {
+            Some((false, "; non-integer values will fail, add cast: integer"))
+        }
+        ("float_t", Output::Integer | Output::Float) => None,
+        ("float_t", Output::String) => Some((false, "; add cast: float")),
+        ("boolean_t", Output::Boolean) => None,
+        ("boolean_t", Output::String | Output::Integer) => Some((false, "; add cast: boolean")),
+        ("string_t", Output::String) => None,
+        ("string_t", Output::Timestamp) => Some((true, "")),
+        ("string_t", Output::Integer | Output::Float | Output::Boolean) => None,
+        (_, Output::Array) => None,
+        _ => Some((true, "")),
+    };
+    if let Some((is_error, why)) = problem {
+        issues.push(mismatch(is_error, why));
+    }
+    issues
+}
+
+/// Required attributes of a class and those no rule targets
+fn class_coverage(event_class: &EventClass, definition: &MappingDefinition) -> ClassCoverage {
+    let mut required: Vec<String> = event_class
+        .attributes
+        .iter()
+        .filter(|(name, attr)| {
+            attr.requirement.as_deref() == Some("required")
+                && attr.profile.is_none()
+                && !BUILDER_SUPPLIED.contains(&name.as_str())
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    required.sort();
+
+    let missing: Vec<String> = required
+        .iter()
+        .filter(|name| {
+            !definition.fields.iter().any(|rule| {
+                rule.target.split('.').next() == Some(name.as_str())
+                    && (rule.classes.is_empty() || rule.classes.contains(&event_class.name))
+            })
+        })
+        .cloned()
+        .collect();
+    let coverage_percent = if required.is_empty() {
+        100.0
+    } else {
+        percent(required.len() - missing.len(), required.len())
+    };
+
+    ClassCoverage {
+        class: event_class.name.clone(),
+        required,
+        missing,
+        coverage_percent,
+    }
+}
+
+/// Run samples through the mapping and compare their fields with what the rules read
+fn check_samples(
+    schema: &OcsfSchema,
+    definition: &MappingDefinition,
+    samples: &[Value],
+    report: &mut MappingValidationReport,
+) {
+    let mut read: Vec<&str> = Vec::new();
+    for rule in &definition.fields {
+        read.extend(rule.source.as_deref());
+        read.extend(rule.sources.iter().map(String::as_str));
+        read.extend(rule.when.iter().map(|c| c.field.as_str()));
+    }
+    read.extend(definition.class_rules.iter().map(|r| r.when.field.as_str()));
+
+    let mut fields: Vec<String> = Vec::new();
+    for sample in samples {
+        for field in parse_sample(&sample.to_string()).fields {
+            if !fields.contains(&field.name) {
+                fields.push(field.name);
+            }
+        }
+    }
+    let is_read = |field: &str| {
+        read.iter().any(|path| {
+            field == *path
+                || field
+                    .strip_prefix(*path)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+    };
+    report.unmapped_source_fields = fields.iter().filter(|f| !is_read(f)).cloned().collect();
+    report.source_coverage_percent = Some(if fields.is_empty() {
+        100.0
+    } else {
+        percent(
+            fields.len() - report.unmapped_source_fields.len(),
+            fields.len(),
+        )
+    });
+
+    // Rules are only run when the mapping itself is sound
+    if !report.errors.is_empty() {
+        return;
+    }
+    let Ok(engine) = MappingEngine::new(schema, definition) else {
+        return;
+    };
+    for (index, sample) in samples.iter().enumerate() {
+        if engine.skips(sample) {
+            continue;
+        }
+        match engine.apply(sample) {
+            Ok(mapped) => {
+                for error in mapped.validation.errors {
+                    report.warnings.push(MappingIssue {
+                        rule: None,
+                        target: Some(error.field),
+                        message: format!("sample {}: {}", index, error.message),
+                    });
+                }
+            }
+            Err(e) => report.errors.push(MappingIssue {
+                rule: None,
+                target: None,
+                message: format!("sample {}: {}", index, e),
+            }),
+        }
+    }
+}
+
+fn percent(part: usize, total: usize) -> f64 {
+    (part as f64 * 1000.0 / total as f64).round() / 10.0
+}
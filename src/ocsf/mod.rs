@@ -1,6 +1,22 @@
+pub mod builder;
+pub mod bulk;
 pub mod categories;
+pub mod conversion;
 pub mod event;
+pub mod file_validation;
+pub mod inference;
+pub mod json_schema;
+pub mod mapping;
+pub mod mapping_validation;
+pub mod parsers;
+pub mod repair;
+pub mod report_formats;
+pub mod scenario;
 pub mod schema;
+pub mod stats;
+pub mod synthetic;
+#[cfg(feature = "typed-events")]
+pub mod typed;
 pub mod validation;
 
 pub use event::*;
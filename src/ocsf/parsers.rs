@@ -0,0 +1,1255 @@
+use indexmap::IndexMap;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::sync::LazyLock;
+
+/// Record fields in the order they appear in the source
+pub type Fields = IndexMap<String, Value>;
+
+/// Lines inspected when deciding whether an input is CSV
+const CSV_PROBE_LINES: usize = 10;
+
+static KEY_VALUE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"([A-Za-z_@][\w.@-]*)=("(?:[^"\\]|\\.)*"|'[^']*'|[^\s,;]+)"#).unwrap()
+});
+static SYSLOG_5424: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"^<(\d{1,3})>(\d{1,2}) (\S+) (\S+) (\S+) (\S+) (\S+) (-|(?:\[(?:[^\]"\\]|\\.|"(?:[^"\\]|\\.)*")*\])+)(?: (.*))?$"#,
+    )
+    .unwrap()
+});
+static SYSLOG_3164: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:<(\d{1,3})>)?([A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2}|\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}\S*)\s+(\S+)\s*(?:([^\s:\[\]]+)(?:\[([^\]]*)\])?:(?:\s|$))?(.*)$",
+    )
+    .unwrap()
+});
+static SD_ELEMENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\[([^\s\]]+)((?:\s+[^\s=\]]+="(?:[^"\\]|\\.)*")*)\s*\]"#).unwrap()
+});
+static SD_PARAM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"([^\s=\]]+)="((?:[^"\\]|\\.)*)""#).unwrap());
+static CEF_START: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"CEF:\d+\|").unwrap());
+static CEF_EXTENSION_KEY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)([\w.\[\]-]+)=").unwrap());
+static LEEF_START: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"LEEF:[12]\.0\|").unwrap());
+static AUDIT_HEADER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:node=(\S+) )?type=(\w+) msg=audit\((\d+(?:\.\d+)?):(\d+)\):\s*(.*)$").unwrap()
+});
+static AUDIT_FIELD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"([\w-]+)=("[^"]*"|'[^']*'|\S*)"#).unwrap());
+static ZEEK_ESCAPE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\\x([0-9a-fA-F]{2})").unwrap());
+static XML_DECLARATION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<\?xml[^>]*\?>").unwrap());
+
+/// auditd fields holding untrusted strings, which are hex-encoded when not quoted
+const AUDIT_ENCODED_FIELDS: &[&str] = &[
+    "proctitle",
+    "cmd",
+    "comm",
+    "exe",
+    "name",
+    "cwd",
+    "acct",
+    "key",
+    "data",
+];
+
+const SYSLOG_FACILITIES: &[&str] = &[
+    "kern",
+    "user",
+    "mail",
+    "daemon",
+    "auth",
+    "syslog",
+    "lpr",
+    "news",
+    "uucp",
+    "cron",
+    "authpriv",
+    "ftp",
+    "ntp",
+    "security",
+    "console",
+    "solaris-cron",
+    "local0",
+    "local1",
+    "local2",
+    "local3",
+    "local4",
+    "local5",
+    "local6",
+    "local7",
+];
+const SYSLOG_SEVERITIES: &[&str] = &[
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+/// A raw log format understood by the parsers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    Json,
+    /// RFC 3164 (BSD) or RFC 5424 syslog
+    Syslog,
+    /// ArcSight Common Event Format, optionally behind a syslog header
+    Cef,
+    /// IBM Log Event Extended Format 1.0 or 2.0, optionally behind a syslog header
+    Leef,
+    /// logfmt-style `key=value` pairs
+    KeyValue,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Linux audit records; the lines of one event (same serial) form a single record
+    Auditd,
+    /// Zeek logs, tab-separated under `#fields` / `#types` headers or as JSON lines
+    Zeek,
+    /// Windows events rendered as XML (`<Event>` elements, e.g. from `wevtutil qe /f:RenderedXml`),
+    /// or JSON renderings of the same structure
+    WindowsXml,
+    /// Anything else; the whole line becomes `message`
+    Text,
+}
+
+impl LogFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogFormat::Json => "JSON",
+            LogFormat::Syslog => "syslog",
+            LogFormat::Cef => "CEF",
+            LogFormat::Leef => "LEEF",
+            LogFormat::KeyValue => "key=value",
+            LogFormat::Csv => "CSV",
+            LogFormat::Auditd => "auditd",
+            LogFormat::Zeek => "Zeek",
+            LogFormat::WindowsXml => "Windows event XML",
+            LogFormat::Text => "free-text",
+        }
+    }
+}
+
+/// One parsed log record
+///
+/// `fields` is flat: nested JSON becomes dotted keys, and envelope values are prefixed with
+/// their format (`syslog.hostname`, `cef.signature_id`, `leef.vendor`). `metadata` describes
+/// how the line was read (syslog RFC, CEF version, LEEF delimiter, CSV columns).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedRecord {
+    pub format: LogFormat,
+    /// 1-based line number of the record in its input
+    pub line: usize,
+    pub fields: Fields,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub metadata: Map<String, Value>,
+    /// The text the record was read from: its line, or its lines for multi-line records
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub raw: String,
+}
+
+impl ParsedRecord {
+    /// The record as a mapping reads it
+    pub fn to_record(&self) -> Value {
+        Value::Object(self.fields.clone().into_iter().collect())
+    }
+}
+
+/// Detect the format of an input from its content
+pub fn detect_format(input: &str) -> LogFormat {
+    let trimmed = input.trim();
+    if matches!(
+        serde_json::from_str::<Value>(trimmed),
+        Ok(Value::Object(_) | Value::Array(_))
+    ) {
+        return LogFormat::Json;
+    }
+    let Some(first) = trimmed.lines().map(str::trim).find(|l| !l.is_empty()) else {
+        return LogFormat::Text;
+    };
+    if matches!(serde_json::from_str::<Value>(first), Ok(Value::Object(_))) {
+        LogFormat::Json
+    } else if first.starts_with("<?xml") || first.starts_with("<Event") {
+        LogFormat::WindowsXml
+    } else if AUDIT_HEADER.is_match(first) {
+        LogFormat::Auditd
+    } else if first.starts_with("#separator") || first.starts_with("#fields") {
+        LogFormat::Zeek
+    } else if CEF_START.is_match(first) {
+        LogFormat::Cef
+    } else if LEEF_START.is_match(first) {
+        LogFormat::Leef
+    } else if parse_syslog(first, true).is_some() {
+        LogFormat::Syslog
+    } else if looks_like_csv(trimmed) {
+        LogFormat::Csv
+    } else if key_value_pairs(first).len() >= 2 {
+        LogFormat::KeyValue
+    } else {
+        LogFormat::Text
+    }
+}
+
+/// Parse every record of an input, detecting its format unless one is given
+///
+/// JSON inputs may be an object, an array of objects or NDJSON; CSV inputs start with a header
+/// row; auditd lines are grouped by event; Zeek TSV rows are read under the latest header; Windows
+/// events may span lines; every other format is one record per non-empty line.
+pub fn parse_input(input: &str, format: Option<LogFormat>) -> anyhow::Result<Vec<ParsedRecord>> {
+    let format = format.unwrap_or_else(|| detect_format(input));
+    match format {
+        LogFormat::Json => parse_json(input),
+        LogFormat::Csv => parse_csv(input),
+        LogFormat::Auditd => parse_auditd(input),
+        LogFormat::Zeek => parse_zeek(input),
+        LogFormat::WindowsXml => parse_windows_events(input),
+        _ => input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                parse_line(format, line)
+                    .map(|record| ParsedRecord {
+                        line: i + 1,
+                        ..record
+                    })
+                    .map_err(|e| anyhow::anyhow!("Line {}: {}", i + 1, e))
+            })
+            .collect(),
+    }
+}
+
+/// Parse a single line in the given format
+///
+/// A CSV line read on its own has no header, so its columns are named `column_1`, `column_2`, ...
+pub fn parse_line(format: LogFormat, line: &str) -> anyhow::Result<ParsedRecord> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let mut fields = Fields::new();
+    let mut metadata = Map::new();
+    match format {
+        LogFormat::Json => match serde_json::from_str(line)? {
+            Value::Object(object) => flatten_into("", &object, &mut fields),
+            other => anyhow::bail!("Expected a JSON object, got {}", other),
+        },
+        LogFormat::Syslog => {
+            let syslog = parse_syslog(line, false)
+                .ok_or_else(|| anyhow::anyhow!("Not an RFC 3164 or RFC 5424 syslog line"))?;
+            fields = syslog.fields;
+            metadata = syslog.metadata;
+            let pairs = key_value_pairs(&syslog.message);
+            if pairs.len() >= 2 {
+                for (key, value) in pairs {
+                    fields.entry(key).or_insert(value);
+                }
+            }
+            fields.insert("message".to_string(), json!(syslog.message));
+        }
+        LogFormat::Cef => parse_cef(line, &mut fields, &mut metadata)?,
+        LogFormat::Leef => parse_leef(line, &mut fields, &mut metadata)?,
+        LogFormat::KeyValue => fields.extend(key_value_pairs(line)),
+        LogFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .has_headers(false)
+                .from_reader(line.as_bytes());
+            if let Some(row) = reader.records().next() {
+                for (i, value) in row?.iter().enumerate() {
+                    if !value.is_empty() {
+                        fields.insert(format!("column_{}", i + 1), json!(value));
+                    }
+                }
+            }
+        }
+        LogFormat::Auditd => {
+            let parsed = audit_line(line).ok_or_else(|| anyhow::anyhow!("Not an auditd record"))?;
+            return Ok(audit_record(1, raw_line(line), parsed));
+        }
+        LogFormat::Zeek => {
+            if !line.trim_start().starts_with('{') {
+                anyhow::bail!("A Zeek TSV line needs the #fields header of its log");
+            }
+            let record = parse_line(LogFormat::Json, line)?;
+            return Ok(ParsedRecord {
+                format: LogFormat::Zeek,
+                ..record
+            });
+        }
+        LogFormat::WindowsXml => {
+            if !line.trim_start().starts_with('{') {
+                return windows_xml_events(line)?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("No <Event> element"));
+            }
+            match serde_json::from_str(line)? {
+                Value::Object(object) => windows_json_event(&object, &mut fields),
+                other => anyhow::bail!("Expected a JSON object, got {}", other),
+            }
+            metadata.insert("rendering".to_string(), json!("json"));
+        }
+        LogFormat::Text => {
+            fields.insert("message".to_string(), json!(line));
+        }
+    }
+    Ok(ParsedRecord {
+        format,
+        line: 1,
+        fields,
+        metadata,
+        raw: line.to_string(),
+    })
+}
+
+/// A line without its line ending
+fn raw_line(line: &str) -> &str {
+    line.trim_end_matches(['\r', '\n'])
+}
+
+/// logfmt-style `key=value` pairs, with quoted values unquoted
+pub fn key_value_pairs(text: &str) -> Vec<(String, Value)> {
+    KEY_VALUE
+        .captures_iter(text)
+        .map(|c| {
+            let raw = &c[2];
+            let value = if raw.len() >= 2 && raw.starts_with('"') && raw.ends_with('"') {
+                raw[1..raw.len() - 1]
+                    .replace("\\\"", "\"")
+                    .replace("\\\\", "\\")
+            } else {
+                raw.trim_matches('\'').to_string()
+            };
+            (c[1].to_string(), Value::String(value))
+        })
+        .collect()
+}
+
+/// Flatten nested JSON objects into dotted keys; arrays and scalars are kept as leaves
+pub fn flatten_into(prefix: &str, object: &Map<String, Value>, out: &mut Fields) {
+    for (key, value) in object {
+        let name = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            Value::Object(nested) => flatten_into(&name, nested, out),
+            Value::Null => {}
+            _ => {
+                out.insert(name, value.clone());
+            }
+        }
+    }
+}
+
+fn parse_json(input: &str) -> anyhow::Result<Vec<ParsedRecord>> {
+    let record = |line: usize, object: &Map<String, Value>, raw: String| {
+        let mut fields = Fields::new();
+        flatten_into("", object, &mut fields);
+        ParsedRecord {
+            format: LogFormat::Json,
+            line,
+            fields,
+            metadata: Map::new(),
+            raw,
+        }
+    };
+    let line_at = |offset: usize| input[..offset].matches('\n').count() + 1;
+    let start = input.len() - input.trim_start().len();
+    match serde_json::from_str::<Value>(input) {
+        Ok(Value::Object(object)) => Ok(vec![record(
+            line_at(start),
+            &object,
+            input.trim().to_string(),
+        )]),
+        Ok(Value::Array(items)) => items
+            .iter()
+            .zip(array_item_offsets(input, start))
+            .enumerate()
+            .map(|(i, (item, offset))| match item {
+                Value::Object(object) => Ok(record(line_at(offset), object, item.to_string())),
+                other => anyhow::bail!("Item {} is not a JSON object: {}", i, other),
+            })
+            .collect(),
+        _ => input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| match serde_json::from_str(line) {
+                Ok(Value::Object(object)) => Ok(record(i + 1, &object, raw_line(line).to_string())),
+                Ok(other) => anyhow::bail!("Line {} is not a JSON object: {}", i + 1, other),
+                Err(e) => anyhow::bail!("Invalid JSON on line {}: {}", i + 1, e),
+            })
+            .collect(),
+    }
+}
+
+/// Byte offsets where the items of the JSON array at `start` of `input` begin
+fn array_item_offsets(input: &str, start: usize) -> Vec<usize> {
+    let skip_whitespace = |offset: usize| input.len() - input[offset..].trim_start().len();
+    let mut offsets = Vec::new();
+    let mut offset = skip_whitespace(start + 1);
+    while !input[offset..].starts_with(']') {
+        offsets.push(offset);
+        let mut item = serde_json::Deserializer::from_str(&input[offset..])
+            .into_iter::<serde::de::IgnoredAny>();
+        if !matches!(item.next(), Some(Ok(_))) {
+            break;
+        }
+        offset = skip_whitespace(offset + item.byte_offset());
+        match input[offset..].strip_prefix(',') {
+            Some(_) => offset = skip_whitespace(offset + 1),
+            None => break,
+        }
+    }
+    offsets
+}
+
+fn parse_csv(input: &str) -> anyhow::Result<Vec<ParsedRecord>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(input.trim_start().as_bytes());
+    let columns: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+    let offset = input.len() - input.trim_start().len();
+
+    let mut records = Vec::new();
+    for row in reader.records() {
+        let row = row?;
+        // The reader's position can sit on blank lines it skipped; count from the row itself
+        let position = offset + row.position().map_or(0, |p| p.byte() as usize);
+        let rest = &input[position..];
+        let start = position + rest.len() - rest.trim_start_matches(['\r', '\n']).len();
+        let line = input[..start].matches('\n').count() + 1;
+        let fields: Fields = columns
+            .iter()
+            .zip(row.iter())
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(column, value)| (column.clone(), json!(value)))
+            .collect();
+        if fields.is_empty() {
+            continue;
+        }
+        records.push(ParsedRecord {
+            format: LogFormat::Csv,
+            line,
+            fields,
+            metadata: Map::from_iter([("columns".to_string(), json!(columns))]),
+            raw: raw_line(input[start..].lines().next().unwrap_or_default()).to_string(),
+        });
+    }
+    Ok(records)
+}
+
+/// A header row of distinct names followed by rows with the same number of columns
+fn looks_like_csv(input: &str) -> bool {
+    let lines: Vec<&str> = input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .take(CSV_PROBE_LINES)
+        .collect();
+    if lines.len() < 2 || !lines[0].contains(',') {
+        return false;
+    }
+    let probe = lines.join("\n");
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(probe.as_bytes());
+    let rows: Vec<csv::StringRecord> = reader.records().filter_map(Result::ok).collect();
+    let Some(header) = rows.first() else {
+        return false;
+    };
+    let names: Vec<&str> = header.iter().collect();
+    let distinct: std::collections::HashSet<&&str> = names.iter().collect();
+    names.len() >= 2
+        && distinct.len() == names.len()
+        && names
+            .iter()
+            .all(|name| !name.is_empty() && name.len() <= 64 && name.parse::<f64>().is_err())
+        && rows.len() == lines.len()
+        && rows.iter().all(|row| row.len() == names.len())
+}
+
+/// One line of an auditd event
+struct AuditLine {
+    node: Option<String>,
+    kind: String,
+    timestamp: String,
+    serial: String,
+    fields: Vec<(String, String)>,
+}
+
+fn audit_line(line: &str) -> Option<AuditLine> {
+    let c = AUDIT_HEADER.captures(line.trim())?;
+    let kind = c[2].to_string();
+    // Enriched logs append translated fields (`UID="root"`) after a 0x1d separator
+    let body = c[5].replace('\u{1d}', " ");
+    let mut fields = Vec::new();
+    for field in AUDIT_FIELD.captures_iter(&body) {
+        // USER_* records carry their own fields inside msg='...'
+        if let Some(inner) = field[2]
+            .strip_prefix('\'')
+            .and_then(|v| v.strip_suffix('\''))
+        {
+            for nested in AUDIT_FIELD.captures_iter(inner) {
+                fields.push((
+                    nested[1].to_string(),
+                    audit_value(&nested[1], &nested[2], &kind),
+                ));
+            }
+            continue;
+        }
+        fields.push((
+            field[1].to_string(),
+            audit_value(&field[1], &field[2], &kind),
+        ));
+    }
+    Some(AuditLine {
+        node: c.get(1).map(|m| m.as_str().to_string()),
+        kind,
+        timestamp: c[3].to_string(),
+        serial: c[4].to_string(),
+        fields,
+    })
+}
+
+/// The event an auditd line belongs to (`<timestamp>:<serial>`); lines of one event share it
+pub fn audit_event_id(line: &str) -> Option<String> {
+    let c = AUDIT_HEADER.captures(line.trim())?;
+    Some(format!("{}:{}", &c[3], &c[4]))
+}
+
+/// Unquote an auditd value, decoding hex-encoded strings
+fn audit_value(key: &str, raw: &str, kind: &str) -> String {
+    if let Some(quoted) = raw.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        return quoted.to_string();
+    }
+    let encoded = AUDIT_ENCODED_FIELDS.contains(&key)
+        || (kind == "EXECVE"
+            && key.len() > 1
+            && key.starts_with('a')
+            && key[1..].parse::<u32>().is_ok());
+    if encoded
+        && raw.len() >= 2
+        && raw.len().is_multiple_of(2)
+        && raw.bytes().all(|b| b.is_ascii_hexdigit())
+    {
+        let bytes: Vec<u8> = (0..raw.len())
+            .step_by(2)
+            .filter_map(|i| u8::from_str_radix(&raw[i..i + 2], 16).ok())
+            .map(|b| if b == 0 { b' ' } else { b })
+            .collect();
+        return String::from_utf8_lossy(&bytes).trim_end().to_string();
+    }
+    raw.to_string()
+}
+
+/// Group auditd lines into one record per event
+fn parse_auditd(input: &str) -> anyhow::Result<Vec<ParsedRecord>> {
+    let mut records: Vec<ParsedRecord> = Vec::new();
+    let mut current_event: Option<String> = None;
+    for (i, raw) in input.lines().enumerate() {
+        if raw.trim().is_empty() {
+            continue;
+        }
+        let line = audit_line(raw)
+            .ok_or_else(|| anyhow::anyhow!("Line {}: not an auditd record", i + 1))?;
+        let event = format!("{}:{}", line.timestamp, line.serial);
+        match records.last_mut() {
+            Some(record) if current_event.as_deref() == Some(event.as_str()) => {
+                add_audit_line(record, raw_line(raw), line)
+            }
+            _ => {
+                current_event = Some(event);
+                records.push(audit_record(i + 1, raw_line(raw), line));
+            }
+        }
+    }
+    Ok(records)
+}
+
+/// A record from the first line of an event; its fields are kept unprefixed
+fn audit_record(line_number: usize, raw: &str, line: AuditLine) -> ParsedRecord {
+    let mut fields = Fields::new();
+    fields.insert("type".to_string(), json!(line.kind));
+    if let Some(node) = &line.node {
+        fields.insert("node".to_string(), json!(node));
+    }
+    fields.insert("audit.timestamp".to_string(), json!(line.timestamp));
+    fields.insert("audit.serial".to_string(), json!(line.serial));
+    fields.insert("audit.records".to_string(), json!([line.kind]));
+    for (key, value) in line.fields {
+        fields.entry(key).or_insert(json!(value));
+    }
+    ParsedRecord {
+        format: LogFormat::Auditd,
+        line: line_number,
+        fields,
+        metadata: Map::from_iter([("serial".to_string(), json!(line.serial))]),
+        raw: raw.to_string(),
+    }
+}
+
+/// Add a later line of the same event under its lowercase type (`execve.a0`, `cwd.cwd`)
+///
+/// PATH lines go under `path.<item>.*`; the last one that is not a parent directory is also
+/// `path.*`. EXECVE arguments are joined into `execve.cmd_line`.
+fn add_audit_line(record: &mut ParsedRecord, raw: &str, line: AuditLine) {
+    record.raw.push('\n');
+    record.raw.push_str(raw);
+    if let Some(Value::Array(kinds)) = record.fields.get_mut("audit.records") {
+        kinds.push(json!(line.kind));
+    }
+    let value_of = |key: &str| {
+        line.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+    };
+    match line.kind.as_str() {
+        "EOE" => {}
+        "PATH" => {
+            let item = value_of("item").unwrap_or_default();
+            let is_parent = value_of("nametype").as_deref() == Some("PARENT");
+            for (key, value) in &line.fields {
+                if key == "item" {
+                    continue;
+                }
+                if !is_parent {
+                    record.fields.insert(format!("path.{key}"), json!(value));
+                }
+                record
+                    .fields
+                    .insert(format!("path.{item}.{key}"), json!(value));
+            }
+        }
+        kind => {
+            let prefix = kind.to_ascii_lowercase();
+            if kind == "EXECVE" {
+                let argc: usize = value_of("argc").and_then(|n| n.parse().ok()).unwrap_or(0);
+                let args: Vec<String> = (0..argc)
+                    .filter_map(|i| value_of(&format!("a{i}")))
+                    .collect();
+                record
+                    .fields
+                    .insert("execve.cmd_line".to_string(), json!(args.join(" ")));
+            }
+            for (key, value) in &line.fields {
+                record
+                    .fields
+                    .entry(format!("{prefix}.{key}"))
+                    .or_insert(json!(value));
+            }
+        }
+    }
+}
+
+/// The `#` directives of a Zeek TSV log
+struct ZeekHeader {
+    separator: String,
+    set_separator: String,
+    empty_field: String,
+    unset_field: String,
+    path: Option<String>,
+    fields: Vec<String>,
+    types: Vec<String>,
+}
+
+impl Default for ZeekHeader {
+    fn default() -> Self {
+        Self {
+            separator: "\t".to_string(),
+            set_separator: ",".to_string(),
+            empty_field: "(empty)".to_string(),
+            unset_field: "-".to_string(),
+            path: None,
+            fields: Vec::new(),
+            types: Vec::new(),
+        }
+    }
+}
+
+impl ZeekHeader {
+    fn apply(&mut self, directive: &str) {
+        // `#separator` starts a new log, and is the only directive separated by a space
+        if let Some(separator) = directive.strip_prefix("separator ") {
+            *self = Self {
+                separator: zeek_unescape(separator),
+                ..Self::default()
+            };
+            return;
+        }
+        let mut parts = directive.split(self.separator.as_str());
+        let name = parts.next().unwrap_or_default();
+        let values: Vec<String> = parts.map(zeek_unescape).collect();
+        let first = values.first().cloned().unwrap_or_default();
+        match name {
+            "set_separator" => self.set_separator = first,
+            "empty_field" => self.empty_field = first,
+            "unset_field" => self.unset_field = first,
+            "path" => self.path = Some(first),
+            "fields" => self.fields = values,
+            "types" => self.types = values,
+            _ => {}
+        }
+    }
+
+    fn record(&self, line_number: usize, line: &str) -> ParsedRecord {
+        let mut fields = Fields::new();
+        let mut metadata = Map::new();
+        if let Some(path) = &self.path {
+            fields.insert("_path".to_string(), json!(path));
+            metadata.insert("path".to_string(), json!(path));
+        }
+        for (i, raw) in line.split(self.separator.as_str()).enumerate() {
+            let Some(name) = self.fields.get(i) else {
+                break;
+            };
+            if raw != self.unset_field {
+                let kind = self.types.get(i).map_or("string", String::as_str);
+                fields.insert(name.clone(), self.value(kind, raw));
+            }
+        }
+        ParsedRecord {
+            format: LogFormat::Zeek,
+            line: line_number,
+            fields,
+            metadata,
+            raw: line.to_string(),
+        }
+    }
+
+    /// Convert a column by its Zeek type; sets and vectors become arrays
+    fn value(&self, kind: &str, raw: &str) -> Value {
+        let element = kind
+            .strip_prefix("set[")
+            .or_else(|| kind.strip_prefix("vector["))
+            .and_then(|k| k.strip_suffix(']'));
+        match element {
+            Some(_) if raw == self.empty_field => json!([]),
+            Some(element) => raw
+                .split(self.set_separator.as_str())
+                .map(|item| zeek_value(element, item))
+                .collect(),
+            None if raw == self.empty_field => json!(""),
+            None => zeek_value(kind, raw),
+        }
+    }
+}
+
+fn zeek_value(kind: &str, raw: &str) -> Value {
+    let number = match kind {
+        "count" | "int" | "port" => raw.parse::<i64>().ok().map(Value::from),
+        "double" | "time" | "interval" => raw.parse::<f64>().ok().map(Value::from),
+        "bool" => return json!(raw == "T"),
+        _ => None,
+    };
+    number.unwrap_or_else(|| json!(zeek_unescape(raw)))
+}
+
+/// Decode Zeek's `\xHH` escapes
+fn zeek_unescape(raw: &str) -> String {
+    ZEEK_ESCAPE
+        .replace_all(raw, |c: &regex::Captures| {
+            u8::from_str_radix(&c[1], 16).map_or(c[0].to_string(), |b| char::from(b).to_string())
+        })
+        .into_owned()
+}
+
+/// Zeek logs; TSV headers may repeat when logs are concatenated, and JSON lines are read as is
+fn parse_zeek(input: &str) -> anyhow::Result<Vec<ParsedRecord>> {
+    let mut header = ZeekHeader::default();
+    let mut records = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(directive) = line.strip_prefix('#') {
+            header.apply(directive);
+        } else if line.trim_start().starts_with('{') {
+            let record = parse_line(LogFormat::Zeek, line)
+                .map_err(|e| anyhow::anyhow!("Line {}: {}", i + 1, e))?;
+            records.push(ParsedRecord {
+                line: i + 1,
+                ..record
+            });
+        } else if header.fields.is_empty() {
+            anyhow::bail!("Line {}: Zeek data before a #fields header", i + 1);
+        } else {
+            records.push(header.record(i + 1, line));
+        }
+    }
+    Ok(records)
+}
+
+/// Windows events: XML documents one after another or under an `<Events>` root, a JSON array of
+/// events, or one JSON event per line
+fn parse_windows_events(input: &str) -> anyhow::Result<Vec<ParsedRecord>> {
+    let trimmed = input.trim_start();
+    if trimmed.starts_with('<') {
+        return windows_xml_events(input);
+    }
+    let record = |object: &Map<String, Value>, raw: String| {
+        let mut fields = Fields::new();
+        windows_json_event(object, &mut fields);
+        ParsedRecord {
+            format: LogFormat::WindowsXml,
+            line: 1,
+            fields,
+            metadata: Map::from_iter([("rendering".to_string(), json!("json"))]),
+            raw,
+        }
+    };
+    match serde_json::from_str::<Value>(trimmed) {
+        Ok(Value::Object(object)) => Ok(vec![record(&object, trimmed.trim_end().to_string())]),
+        Ok(Value::Array(items)) => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| match item {
+                Value::Object(object) => Ok(record(object, item.to_string())),
+                other => anyhow::bail!("Item {} is not a JSON object: {}", i, other),
+            })
+            .collect(),
+        _ => input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                parse_line(LogFormat::WindowsXml, line)
+                    .map(|record| ParsedRecord {
+                        line: i + 1,
+                        ..record
+                    })
+                    .map_err(|e| anyhow::anyhow!("Line {}: {}", i + 1, e))
+            })
+            .collect(),
+    }
+}
+
+/// Every `<Event>` element of an input
+///
+/// Exports concatenate event documents without a common root, so the input is read under one;
+/// XML declarations are blanked rather than removed so positions keep their lines.
+fn windows_xml_events(input: &str) -> anyhow::Result<Vec<ParsedRecord>> {
+    let body = XML_DECLARATION.replace_all(input, |c: &regex::Captures| " ".repeat(c[0].len()));
+    const WRAPPER: &str = "<Events>";
+    let wrapped = format!("{WRAPPER}{body}</Events>");
+    let document = roxmltree::Document::parse(&wrapped)
+        .map_err(|e| anyhow::anyhow!("Invalid Windows event XML: {}", e))?;
+    let records = document
+        .descendants()
+        .filter(|node| {
+            node.has_tag_name("Event") && !node.ancestors().skip(1).any(|a| a.has_tag_name("Event"))
+        })
+        .map(|event| {
+            let range = event.range();
+            let mut fields = Fields::new();
+            for section in event.children().filter(|n| n.is_element()) {
+                windows_xml_element(section.tag_name().name(), section, &mut fields);
+            }
+            ParsedRecord {
+                format: LogFormat::WindowsXml,
+                line: document.text_pos_at(range.start).row as usize,
+                fields,
+                metadata: Map::from_iter([("rendering".to_string(), json!("xml"))]),
+                raw: input[range.start - WRAPPER.len()..range.end - WRAPPER.len()].to_string(),
+            }
+        })
+        .collect();
+    Ok(records)
+}
+
+/// Flatten an event XML element: attributes become `<path>.<name>` and text `<path>`, while
+/// `<Data Name="X">` becomes `<path>.X`; repeated elements (unnamed `Data`, `Keyword`) collect
+/// into an array
+fn windows_xml_element(path: &str, node: roxmltree::Node, fields: &mut Fields) {
+    for attribute in node.attributes().filter(|a| !a.value().is_empty()) {
+        push_field(
+            fields,
+            format!("{path}.{}", attribute.name()),
+            json!(attribute.value()),
+        );
+    }
+    let children: Vec<_> = node.children().filter(|n| n.is_element()).collect();
+    if children.is_empty() {
+        if let Some(text) = node.text().map(str::trim).filter(|t| !t.is_empty()) {
+            push_field(fields, path.to_string(), json!(text));
+        }
+        return;
+    }
+    for child in children {
+        let tag = child.tag_name().name();
+        match child.attribute("Name") {
+            Some(name) if tag == "Data" => {
+                if let Some(text) = child.text().map(str::trim).filter(|t| !t.is_empty()) {
+                    push_field(fields, format!("{path}.{name}"), json!(text));
+                }
+            }
+            _ => windows_xml_element(&format!("{path}.{tag}"), child, fields),
+        }
+    }
+}
+
+/// Flatten a JSON rendering of an event to the fields its XML gives
+///
+/// Attributes are read from `#attributes` objects (evtx_dump) or `@`-prefixed keys (xmltodict),
+/// element text from `#text`, and an `Event` wrapper is optional. Scalars are kept as text, as
+/// in the XML.
+fn windows_json_event(object: &Map<String, Value>, fields: &mut Fields) {
+    match object.get("Event") {
+        Some(Value::Object(event)) if object.len() == 1 => windows_json_value("", event, fields),
+        _ => windows_json_value("", object, fields),
+    }
+}
+
+fn windows_json_value(path: &str, object: &Map<String, Value>, fields: &mut Fields) {
+    for (key, value) in object {
+        let name = key.strip_prefix('@').unwrap_or(key);
+        match name {
+            "xmlns" => {}
+            "#attributes" | "#text" => windows_json_leaf(path, value, fields),
+            "Data" if windows_json_named_data(path, value, fields) => {}
+            _ if path.is_empty() => windows_json_leaf(name, value, fields),
+            _ => windows_json_leaf(&format!("{path}.{name}"), value, fields),
+        }
+    }
+}
+
+fn windows_json_leaf(path: &str, value: &Value, fields: &mut Fields) {
+    match value {
+        Value::Object(object) => windows_json_value(path, object, fields),
+        Value::Array(items) => {
+            for item in items {
+                windows_json_leaf(path, item, fields);
+            }
+        }
+        Value::Null => {}
+        Value::String(text) if text.is_empty() => {}
+        Value::String(text) => push_field(fields, path.to_string(), json!(text)),
+        other => push_field(fields, path.to_string(), json!(other.to_string())),
+    }
+}
+
+/// `Data` elements rendered with their `Name` attribute (`[{"@Name": "X", "#text": "..."}]`);
+/// false when they are unnamed
+fn windows_json_named_data(path: &str, value: &Value, fields: &mut Fields) -> bool {
+    let items = match value {
+        Value::Array(items) => items.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+    let named: Option<Vec<_>> = items
+        .iter()
+        .map(|item| {
+            let name = item
+                .get("@Name")
+                .or_else(|| item.pointer("/#attributes/Name"))?
+                .as_str()?;
+            Some((name, item.get("#text")))
+        })
+        .collect();
+    let Some(named) = named else {
+        return false;
+    };
+    for (name, text) in named {
+        if let Some(text) = text {
+            windows_json_leaf(&format!("{path}.{name}"), text, fields);
+        }
+    }
+    true
+}
+
+/// Insert a field, collecting repeated ones into an array
+fn push_field(fields: &mut Fields, key: String, value: Value) {
+    match fields.get_mut(&key) {
+        Some(Value::Array(items)) => items.push(value),
+        Some(existing) => *existing = json!([existing.take(), value]),
+        None => {
+            fields.insert(key, value);
+        }
+    }
+}
+
+struct Syslog {
+    fields: Fields,
+    metadata: Map<String, Value>,
+    message: String,
+}
+
+/// Parse an RFC 5424 or RFC 3164 line
+///
+/// BSD lines without a `<PRI>` are only accepted with a program tag (`sshd[42]:`) unless
+/// `require_tag` is false, so free text that merely starts with a date is not taken for syslog.
+fn parse_syslog(line: &str, require_tag: bool) -> Option<Syslog> {
+    let mut fields = Fields::new();
+    let mut metadata = Map::new();
+    let mut put = |name: &str, value: &str| {
+        if !value.is_empty() && value != "-" {
+            fields.insert(format!("syslog.{name}"), json!(value));
+        }
+    };
+
+    if let Some(c) = SYSLOG_5424.captures(line) {
+        put("timestamp", &c[3]);
+        put("hostname", &c[4]);
+        put("app_name", &c[5]);
+        put("procid", &c[6]);
+        put("msgid", &c[7]);
+        for element in SD_ELEMENT.captures_iter(&c[8]) {
+            for param in SD_PARAM.captures_iter(&element[2]) {
+                let value = param[2]
+                    .replace("\\\"", "\"")
+                    .replace("\\]", "]")
+                    .replace("\\\\", "\\");
+                fields.insert(
+                    format!("syslog.sd.{}.{}", &element[1], &param[1]),
+                    json!(value),
+                );
+            }
+        }
+        metadata.insert("rfc".to_string(), json!("5424"));
+        metadata.insert("version".to_string(), json!(c[2].parse::<u8>().ok()?));
+        add_priority(&c[1], &mut fields, &mut metadata)?;
+        let message = c.get(9).map_or("", |m| m.as_str());
+        return Some(Syslog {
+            fields,
+            metadata,
+            message: message.trim_start_matches('\u{feff}').to_string(),
+        });
+    }
+
+    let c = SYSLOG_3164.captures(line)?;
+    if c.get(1).is_none() && c.get(4).is_none() && require_tag {
+        return None;
+    }
+    put("timestamp", &c[2]);
+    put("hostname", &c[3]);
+    if let Some(tag) = c.get(4) {
+        put("app_name", tag.as_str());
+    }
+    if let Some(pid) = c.get(5) {
+        put("procid", pid.as_str());
+    }
+    metadata.insert("rfc".to_string(), json!("3164"));
+    if let Some(pri) = c.get(1) {
+        add_priority(pri.as_str(), &mut fields, &mut metadata)?;
+    }
+    Some(Syslog {
+        fields,
+        metadata,
+        message: c[6].to_string(),
+    })
+}
+
+/// Split `<PRI>` into facility and severity names; the number itself goes in the metadata
+fn add_priority(pri: &str, fields: &mut Fields, metadata: &mut Map<String, Value>) -> Option<()> {
+    let pri: usize = pri.parse().ok().filter(|p| *p <= 191)?;
+    metadata.insert("priority".to_string(), json!(pri));
+    fields.insert(
+        "syslog.facility".to_string(),
+        json!(SYSLOG_FACILITIES[pri / 8]),
+    );
+    fields.insert(
+        "syslog.severity".to_string(),
+        json!(SYSLOG_SEVERITIES[pri % 8]),
+    );
+    Some(())
+}
+
+/// Syslog fields from whatever precedes a CEF or LEEF payload
+fn envelope_header(prefix: &str, fields: &mut Fields, metadata: &mut Map<String, Value>) {
+    let prefix = prefix.trim();
+    if prefix.is_empty() {
+        return;
+    }
+    if let Some(syslog) = parse_syslog(prefix, false) {
+        fields.extend(syslog.fields);
+        metadata.insert("syslog".to_string(), Value::Object(syslog.metadata));
+    }
+}
+
+/// `CEF:Version|Device Vendor|Device Product|Device Version|Signature ID|Name|Severity|Extension`
+fn parse_cef(
+    line: &str,
+    fields: &mut Fields,
+    metadata: &mut Map<String, Value>,
+) -> anyhow::Result<()> {
+    let start = CEF_START
+        .find(line)
+        .ok_or_else(|| anyhow::anyhow!("No 'CEF:' header found"))?
+        .start();
+    envelope_header(&line[..start], fields, metadata);
+
+    let mut header = split_unescaped_pipes(&line[start + 4..], 7);
+    if header.len() < 7 {
+        anyhow::bail!(
+            "CEF header needs 7 '|'-separated fields, found {}",
+            header.len()
+        );
+    }
+    let extension = if header.len() == 8 {
+        header.pop().unwrap_or_default()
+    } else {
+        String::new()
+    };
+    metadata.insert("cef_version".to_string(), json!(header[0]));
+    let names = [
+        "device_vendor",
+        "device_product",
+        "device_version",
+        "signature_id",
+        "name",
+        "severity",
+    ];
+    for (name, value) in names.iter().zip(&header[1..]) {
+        if !value.is_empty() {
+            fields.insert(format!("cef.{name}"), json!(value));
+        }
+    }
+
+    let keys: Vec<(usize, usize, String)> = CEF_EXTENSION_KEY
+        .captures_iter(&extension)
+        .map(|c| {
+            let whole = c.get(0).unwrap();
+            (whole.start(), whole.end(), c[1].to_string())
+        })
+        .collect();
+    let mut extensions = Fields::new();
+    for (i, (_, value_start, key)) in keys.iter().enumerate() {
+        let value_end = keys.get(i + 1).map_or(extension.len(), |next| next.0);
+        let value = unescape_cef_value(extension[*value_start..value_end].trim());
+        extensions.insert(key.clone(), json!(value));
+    }
+    // Custom fields come with their meaning, e.g. `cs1Label=Rule cs1=block-all`
+    let labelled: Vec<(String, Value)> = extensions
+        .iter()
+        .filter_map(|(key, label)| {
+            let base = key.strip_suffix("Label")?;
+            let value = extensions.get(base)?;
+            Some((label.as_str()?.to_string(), value.clone()))
+        })
+        .collect();
+    fields.extend(extensions);
+    for (label, value) in labelled {
+        fields.entry(label).or_insert(value);
+    }
+    Ok(())
+}
+
+/// Split on `|` not escaped as `\|`, unescaping `\|` and `\\`; the rest goes in a final part
+fn split_unescaped_pipes(text: &str, max_splits: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.char_indices();
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some((_, next @ ('|' | '\\'))) => current.push(next),
+                Some((_, next)) => {
+                    current.push('\\');
+                    current.push(next);
+                }
+                None => current.push('\\'),
+            },
+            '|' => {
+                parts.push(std::mem::take(&mut current));
+                if parts.len() == max_splits {
+                    parts.push(text[i + 1..].to_string());
+                    return parts;
+                }
+            }
+            _ => current.push(ch),
+        }
+    }
+    parts.push(current);
+    parts
+}
+
+fn unescape_cef_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(next) => out.push(next),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// `LEEF:1.0|Vendor|Product|Version|EventID|attrs` (tab-separated) or
+/// `LEEF:2.0|Vendor|Product|Version|EventID|Delimiter|attrs`
+fn parse_leef(
+    line: &str,
+    fields: &mut Fields,
+    metadata: &mut Map<String, Value>,
+) -> anyhow::Result<()> {
+    let start = LEEF_START
+        .find(line)
+        .ok_or_else(|| anyhow::anyhow!("No 'LEEF:' header found"))?
+        .start();
+    envelope_header(&line[..start], fields, metadata);
+
+    let body = &line[start + 5..];
+    let version = &body[..3];
+    let splits = if version == "2.0" { 6 } else { 5 };
+    let mut header: Vec<&str> = body.splitn(splits + 1, '|').collect();
+    if header.len() < 5 {
+        anyhow::bail!(
+            "LEEF header needs 5 '|'-separated fields, found {}",
+            header.len()
+        );
+    }
+    let attributes = if header.len() > 5 {
+        header.pop().unwrap_or_default()
+    } else {
+        ""
+    };
+    let delimiter = match header.get(5) {
+        Some(spec) => leef_delimiter(spec)?,
+        None => '\t',
+    };
+    metadata.insert("leef_version".to_string(), json!(version));
+    metadata.insert("delimiter".to_string(), json!(delimiter.to_string()));
+    let names = ["vendor", "product", "product_version", "event_id"];
+    for (name, value) in names.iter().zip(&header[1..5]) {
+        if !value.is_empty() {
+            fields.insert(format!("leef.{name}"), json!(value));
+        }
+    }
+
+    let pairs: Vec<(&str, &str)> = attributes
+        .split(delimiter)
+        .filter_map(|attr| attr.split_once('='))
+        .collect();
+    // Tabs are often turned into spaces in transit
+    if pairs.len() == 1 && delimiter == '\t' && attributes.matches('=').count() > 1 {
+        fields.extend(key_value_pairs(attributes));
+    } else {
+        for (key, value) in pairs {
+            fields.insert(key.trim().to_string(), json!(value));
+        }
+    }
+    Ok(())
+}
+
+/// A LEEF 2.0 delimiter: one character, or its code as `x09` / `0x09`; empty means tab
+fn leef_delimiter(spec: &str) -> anyhow::Result<char> {
+    let hex = spec
+        .strip_prefix("0x")
+        .or_else(|| spec.strip_prefix('x'))
+        .filter(|h| !h.is_empty() && h.len() <= 4);
+    match (hex, spec.chars().count()) {
+        (Some(hex), _) => u32::from_str_radix(hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| anyhow::anyhow!("Invalid LEEF delimiter '{}'", spec)),
+        (None, 0) => Ok('\t'),
+        (None, 1) => Ok(spec.chars().next().unwrap_or('\t')),
+        _ => anyhow::bail!("Invalid LEEF delimiter '{}'", spec),
+    }
+}
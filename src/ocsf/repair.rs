@@ -0,0 +1,415 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+use super::builder::enum_id;
+use super::schema::{Attribute, EventClass, OcsfSchema};
+use super::validation::{
+    field_pointer, validate_value_with_schema, ValidationReport, CLASSIFICATION_ATTRIBUTES,
+};
+
+/// An event after its mechanical validation failures were repaired
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventRepair {
+    pub event: Value,
+    pub fixes: Vec<AppliedFix>,
+    /// Validation of the repaired event; what is left here needs a human decision
+    pub validation: ValidationReport,
+    pub summary: String,
+}
+
+/// One repair, with the value it replaced
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppliedFix {
+    pub rule: String,
+    /// RFC 6901 JSON pointer of the repaired attribute
+    pub pointer: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+    pub after: Value,
+}
+
+/// Apply safe, schema-derived repairs to an event and validate the result
+///
+/// Repairs only derive a value the schema determines: a missing category of the class, `type_uid`
+/// from `class_uid` and `activity_id` (or `activity_id` from `type_uid`), an enum id from its
+/// caption and a caption from its id, epoch milliseconds from a timestamp string, integers from
+/// integer strings, the schema version of `metadata` and the move of legacy `metadata`
+/// classification attributes to the top level. Conflicting values, such as a `type_uid` that
+/// disagrees with a present `activity_id`, are left for the validation report.
+pub fn repair_event(event: Value, schema: &OcsfSchema) -> anyhow::Result<EventRepair> {
+    let Value::Object(mut fields) = event else {
+        anyhow::bail!("An OCSF event must be a JSON object");
+    };
+    let mut repair = Repair {
+        schema,
+        fixes: Vec::new(),
+    };
+
+    repair.legacy_metadata(&mut fields);
+    // Integer strings (`"3002"`) are repaired first, so the class can be resolved
+    for name in CLASSIFICATION_ATTRIBUTES {
+        if let Some(Value::String(text)) = fields.get(*name)
+            && let Ok(n) = text.trim().parse::<i64>()
+        {
+            let message = format!("Converted '{text}' to an integer");
+            repair.set(&mut fields, "", name, json!(n), "integer", || message);
+        }
+    }
+
+    let event_class = schema
+        .class_for_event(&Value::Object(fields.clone()))
+        .cloned();
+    if let Some(ec) = &event_class {
+        repair.object(&ec.attributes, &mut fields, "");
+        repair.classification(ec, &mut fields);
+    }
+    repair.metadata_version(&mut fields);
+
+    let event = Value::Object(fields);
+    let validation = validate_value_with_schema(&event, schema);
+    let summary = format!(
+        "Applied {} fix(es); {} error(s) remain",
+        repair.fixes.len(),
+        validation.errors.len()
+    );
+    Ok(EventRepair {
+        event,
+        fixes: repair.fixes,
+        validation,
+        summary,
+    })
+}
+
+struct Repair<'a> {
+    schema: &'a OcsfSchema,
+    fixes: Vec<AppliedFix>,
+}
+
+impl Repair<'_> {
+    /// Replace (or add) an attribute and record the fix
+    fn set(
+        &mut self,
+        fields: &mut Map<String, Value>,
+        pointer: &str,
+        name: &str,
+        value: Value,
+        rule: &str,
+        message: impl FnOnce() -> String,
+    ) {
+        let before = fields.insert(name.to_string(), value.clone());
+        self.fixes.push(AppliedFix {
+            rule: rule.to_string(),
+            pointer: format!("{pointer}{}", field_pointer(name)),
+            message: message(),
+            before,
+            after: value,
+        });
+    }
+
+    /// Move `metadata.class_uid` and `metadata.category_uid` (written by older releases) to
+    /// the top level, and replace `metadata.event_class` with the `class_uid` it names
+    fn legacy_metadata(&mut self, fields: &mut Map<String, Value>) {
+        let legacy = |fields: &Map<String, Value>, name: &str| {
+            fields.get("metadata").and_then(|m| m.get(name)).cloned()
+        };
+
+        for name in ["class_uid", "category_uid"] {
+            let Some(value) = legacy(fields, name) else {
+                continue;
+            };
+            match fields.get(name) {
+                // Conflicting values: which one is right is not for us to decide
+                Some(top) if *top != value => continue,
+                Some(_) => self.remove_metadata(fields, name, || {
+                    format!("Removed 'metadata.{name}', a duplicate of '{name}'")
+                }),
+                None => {
+                    take_metadata(fields, name);
+                    self.set(fields, "", name, value, "legacy_metadata", || {
+                        format!("Moved 'metadata.{name}' to the top level")
+                    });
+                }
+            }
+        }
+
+        let Some(event_class) = legacy(fields, "event_class") else {
+            return;
+        };
+        let class = event_class
+            .as_str()
+            .and_then(|name| self.schema.get_event_class(name));
+        match (class, fields.get("class_uid")) {
+            (Some(ec), None) => {
+                let uid = json!(ec.uid);
+                take_metadata(fields, "event_class");
+                self.set(fields, "", "class_uid", uid, "legacy_metadata", || {
+                    format!(
+                        "'class_uid' of the class named by 'metadata.event_class' ({})",
+                        ec.name
+                    )
+                });
+            }
+            (Some(ec), Some(uid)) if uid.as_u64() == Some(u64::from(ec.uid)) => {
+                self.remove_metadata(fields, "event_class", || {
+                    "Removed 'metadata.event_class', a duplicate of 'class_uid'".to_string()
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn remove_metadata(
+        &mut self,
+        fields: &mut Map<String, Value>,
+        name: &str,
+        message: impl FnOnce() -> String,
+    ) {
+        if let Some(before) = take_metadata(fields, name) {
+            self.fixes.push(AppliedFix {
+                rule: "legacy_metadata".to_string(),
+                pointer: format!("/metadata{}", field_pointer(name)),
+                message: message(),
+                before: Some(before),
+                after: Value::Null,
+            });
+        }
+    }
+
+    /// Repair the attributes of an object (or of the event) and of the objects within it
+    fn object(
+        &mut self,
+        attributes: &HashMap<String, Attribute>,
+        fields: &mut Map<String, Value>,
+        pointer: &str,
+    ) {
+        let mut names: Vec<&String> = attributes.keys().collect();
+        names.sort();
+        for name in names {
+            let attr = &attributes[name];
+            let path = format!("{pointer}{}", field_pointer(name));
+            match fields.get_mut(name.as_str()) {
+                Some(Value::Array(items)) if attr.is_array => {
+                    for (i, item) in items.iter_mut().enumerate() {
+                        self.value(attr, item, &format!("{path}/{i}"));
+                    }
+                }
+                Some(value) => self.value(attr, value, &path),
+                None => {}
+            }
+            self.enum_pair(attr, name, fields, pointer);
+        }
+    }
+
+    /// Coerce one attribute value to its OCSF type when the text alone determines the result:
+    /// timestamps only with an explicit offset, enum captions, integer strings
+    fn value(&mut self, attr: &Attribute, value: &mut Value, pointer: &str) {
+        if let Some(object_type) = &attr.object_type {
+            // Generic objects (e.g. `unmapped`) have no attribute definitions
+            if let (Value::Object(fields), Some(object)) =
+                (&mut *value, self.schema.objects.get(object_type))
+            {
+                self.object(&object.attributes, fields, pointer);
+            }
+            return;
+        }
+
+        let data_type = attr.data_type.as_deref().unwrap_or("string_t");
+        if !matches!(self.schema.base_type(data_type), "integer_t" | "long_t") {
+            return;
+        }
+        let Value::String(text) = &*value else {
+            return;
+        };
+        let (repaired, rule, message) = if data_type == "timestamp_t" {
+            // Other layouts need a guessed time zone or year, so they stay errors
+            let parsed = chrono::DateTime::parse_from_rfc3339(text.trim())
+                .or_else(|_| chrono::DateTime::parse_from_rfc2822(text.trim()));
+            let Ok(ms) = parsed.map(|dt| dt.timestamp_millis()) else {
+                return;
+            };
+            (
+                json!(ms),
+                "timestamp",
+                format!("Converted '{text}' to epoch milliseconds"),
+            )
+        } else if let Some(values) = &attr.enum_values {
+            let Some(id) = enum_id(values, value) else {
+                return;
+            };
+            (
+                json!(id),
+                "enum_id",
+                format!("Resolved '{text}' to its enum id"),
+            )
+        } else {
+            let Ok(n) = text.trim().parse::<i64>() else {
+                return;
+            };
+            (
+                json!(n),
+                "integer",
+                format!("Converted '{text}' to an integer"),
+            )
+        };
+
+        self.fixes.push(AppliedFix {
+            rule: rule.to_string(),
+            pointer: pointer.to_string(),
+            message,
+            before: Some(std::mem::replace(value, repaired.clone())),
+            after: repaired,
+        });
+    }
+
+    /// Make an enum `_id` and its caption sibling agree
+    ///
+    /// A missing id is resolved from a caption the enum defines; a missing or different caption
+    /// is replaced with the id's, except for `Other` (99), whose caption is the source's own.
+    fn enum_pair(
+        &mut self,
+        attr: &Attribute,
+        name: &str,
+        fields: &mut Map<String, Value>,
+        pointer: &str,
+    ) {
+        let (Some(values), Some(sibling)) = (&attr.enum_values, &attr.sibling) else {
+            return;
+        };
+        // Either side of a conflicting type_uid may be wrong, so both captions stay as evidence
+        if pointer.is_empty()
+            && matches!(name, "activity_id" | "type_uid")
+            && type_uid_conflicts(fields)
+        {
+            return;
+        }
+        match (fields.get(name), fields.get(sibling)) {
+            (None, Some(caption @ Value::String(text))) => {
+                let Some(id) = enum_id(values, caption) else {
+                    return;
+                };
+                let message = format!("'{name}' of the '{sibling}' caption '{text}'");
+                self.set(fields, pointer, name, id, "enum_id", || message);
+                // The caption may differ from the enum's in case only
+                self.enum_pair(attr, name, fields, pointer);
+            }
+            (Some(id), caption) => {
+                let Some(expected) = id
+                    .as_i64()
+                    .and_then(|id| values.get(&id.to_string()))
+                    .and_then(|v| v.caption.clone())
+                else {
+                    return;
+                };
+                if caption.and_then(Value::as_str) == Some(expected.as_str())
+                    || (id.as_i64() == Some(99) && caption.is_some())
+                {
+                    return;
+                }
+                let message = format!("'{sibling}' is the caption of '{name}' {id}");
+                self.set(
+                    fields,
+                    pointer,
+                    sibling,
+                    json!(expected),
+                    "enum_caption",
+                    || message,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Derive `category_uid`, `activity_id` and `type_uid` from what the event already states
+    fn classification(&mut self, ec: &EventClass, fields: &mut Map<String, Value>) {
+        // A category_uid that disagrees with the class stays an error
+        let category_uid = json!(ec.resolved_category_uid());
+        if !fields.contains_key("category_uid") {
+            self.set(
+                fields,
+                "",
+                "category_uid",
+                category_uid,
+                "category_uid",
+                || {
+                    format!(
+                        "Class '{}' belongs to category {}",
+                        ec.name,
+                        ec.resolved_category_uid()
+                    )
+                },
+            );
+        }
+
+        let int =
+            |fields: &Map<String, Value>, name: &str| fields.get(name).and_then(Value::as_i64);
+        let class_uid = i64::from(ec.uid);
+        if int(fields, "activity_id").is_none()
+            && let Some(type_uid) = int(fields, "type_uid")
+            && type_uid / 100 == class_uid
+        {
+            self.set(
+                fields,
+                "",
+                "activity_id",
+                json!(type_uid % 100),
+                "activity_id",
+                || format!("'activity_id' of type_uid {type_uid}"),
+            );
+        }
+
+        // A type_uid that disagrees with a present activity_id is ambiguous and stays an error
+        if let Some(activity_id) = int(fields, "activity_id")
+            && fields.get("type_uid").is_none()
+        {
+            let (type_uid, _) = self.schema.type_uid_for(ec, activity_id);
+            self.set(fields, "", "type_uid", json!(type_uid), "type_uid", || {
+                format!("class_uid * 100 + activity_id ({class_uid} * 100 + {activity_id})")
+            });
+        }
+
+        for name in ["category_uid", "activity_id", "type_uid"] {
+            if let Some(attr) = ec.attributes.get(name) {
+                self.enum_pair(attr, name, fields, "");
+            }
+        }
+    }
+
+    /// Stamp the schema version on `metadata` when it has none
+    fn metadata_version(&mut self, fields: &mut Map<String, Value>) {
+        let Some(Value::Object(metadata)) = fields.get_mut("metadata") else {
+            return;
+        };
+        if metadata.contains_key("version") {
+            return;
+        }
+        let version = json!(self.schema.version);
+        self.set(
+            metadata,
+            "/metadata",
+            "version",
+            version,
+            "metadata_version",
+            || "'metadata.version' of the schema the event was checked against".to_string(),
+        );
+    }
+}
+
+/// Remove an attribute of `metadata`
+fn take_metadata(fields: &mut Map<String, Value>, name: &str) -> Option<Value> {
+    match fields.get_mut("metadata") {
+        Some(Value::Object(metadata)) => metadata.remove(name),
+        _ => None,
+    }
+}
+
+/// Whether `type_uid` is present and disagrees with `class_uid * 100 + activity_id`
+fn type_uid_conflicts(fields: &Map<String, Value>) -> bool {
+    let int = |name: &str| fields.get(name).and_then(Value::as_i64);
+    matches!(
+        (int("class_uid"), int("activity_id"), int("type_uid")),
+        (Some(class_uid), Some(activity_id), Some(type_uid))
+            if type_uid != class_uid * 100 + activity_id
+    )
+}
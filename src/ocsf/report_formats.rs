@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::file_validation::{EventResult, INVALID_JSON_RULE};
+
+/// The rule of validator warnings
+pub const WARNING_RULE: &str = "Warning";
+
+/// Rules reported by the validator, with their descriptions
+const RULES: &[(&str, &str)] = &[
+    ("MissingRequired", "A required attribute is missing"),
+    ("InvalidType", "An attribute has the wrong type"),
+    ("InvalidValue", "An attribute value is not allowed"),
+    ("UnknownField", "An attribute is not defined by the schema"),
+    (INVALID_JSON_RULE, "The event is not valid JSON"),
+    (
+        WARNING_RULE,
+        "A schema-aware check that does not make the event invalid",
+    ),
+];
+
+/// How a validation report is written out
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    /// The report as pretty-printed JSON
+    #[default]
+    Json,
+    /// SARIF 2.1.0, one result per error or warning, located by JSON pointer
+    Sarif,
+    /// JUnit XML, one test case per event
+    Junit,
+    /// One JSON object per event
+    JsonLines,
+}
+
+/// Write per-event validation results as SARIF, JUnit XML or JSON Lines
+///
+/// `artifact` names the validated file, when there is one; SARIF locations and JUnit test
+/// suites refer to it. `Json` is each tool's own report and is not written here; it falls back
+/// to JSON Lines.
+pub fn render(
+    format: ReportFormat,
+    artifact: Option<&str>,
+    results: &[EventResult],
+) -> anyhow::Result<String> {
+    match format {
+        ReportFormat::Sarif => Ok(serde_json::to_string_pretty(&sarif(artifact, results))?),
+        ReportFormat::Junit => Ok(junit(artifact, results)),
+        ReportFormat::Json | ReportFormat::JsonLines => json_lines(results),
+    }
+}
+
+/// One line per event: its position, validity, class, errors and warnings
+fn json_lines(results: &[EventResult]) -> anyhow::Result<String> {
+    let mut output = String::new();
+    for event in results {
+        let mut line = json!({ "index": event.index });
+        if let Some(number) = event.line {
+            line["line"] = json!(number);
+        }
+        match &event.result {
+            Ok(report) => {
+                line["is_valid"] = json!(report.is_valid);
+                line["event_class"] = json!(report.event_class);
+                line["errors"] = serde_json::to_value(&report.errors)?;
+                line["warnings"] = serde_json::to_value(&report.warnings)?;
+            }
+            Err(message) => {
+                line["is_valid"] = json!(false);
+                line["error"] = json!(message);
+            }
+        }
+        output.push_str(&line.to_string());
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+fn sarif(artifact: Option<&str>, results: &[EventResult]) -> Value {
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|(id, description)| json!({ "id": id, "shortDescription": { "text": description } }))
+        .collect();
+    // Error positions are relative to the event's text, which starts at the event's line of
+    // the artifact; without an artifact they stay relative to the event itself
+    let location =
+        |event: &EventResult, pointer: &str, position: (Option<usize>, Option<usize>)| {
+            let mut location = json!({
+                "logicalLocations": [{
+                    "fullyQualifiedName": pointer,
+                    "kind": "member",
+                }],
+                "properties": { "eventIndex": event.index, "jsonPointer": pointer },
+            });
+            let mut physical = Map::new();
+            if let Some(uri) = artifact {
+                physical.insert("artifactLocation".to_string(), json!({ "uri": uri }));
+            }
+            let start_line = match (artifact, event.line) {
+                (Some(_), Some(line)) => Some(line + position.0.unwrap_or(1) - 1),
+                (Some(_), None) => None,
+                (None, _) => position.0,
+            };
+            if let Some(line) = start_line {
+                let mut region = json!({ "startLine": line });
+                if let Some(column) = position.1 {
+                    region["startColumn"] = json!(column);
+                }
+                physical.insert("region".to_string(), region);
+            }
+            if !physical.is_empty() {
+                location["physicalLocation"] = Value::Object(physical);
+            }
+            location
+        };
+
+    let mut findings = Vec::new();
+    for event in results {
+        let report = match &event.result {
+            Ok(report) => report,
+            Err(message) => {
+                findings.push(json!({
+                    "ruleId": INVALID_JSON_RULE,
+                    "level": "error",
+                    "message": { "text": message },
+                    "locations": [location(event, "", (None, None))],
+                }));
+                continue;
+            }
+        };
+        for error in &report.errors {
+            findings.push(json!({
+                "ruleId": error.error_type.name(),
+                "level": "error",
+                "message": { "text": error.message },
+                "locations": [location(event, &error.pointer, (error.line, error.column))],
+            }));
+        }
+        for warning in &report.warnings {
+            findings.push(json!({
+                "ruleId": WARNING_RULE,
+                "level": "warning",
+                "message": { "text": warning.message },
+                "locations": [location(event, &warning.pointer, (warning.line, warning.column))],
+            }));
+        }
+    }
+
+    let mut run = json!({
+        "tool": {
+            "driver": {
+                "name": "ocsf-mcp",
+                "version": env!("CARGO_PKG_VERSION"),
+                "informationUri": "https://schema.ocsf.io",
+                "rules": rules,
+            }
+        },
+        "results": findings,
+    });
+    if let Some(uri) = artifact {
+        run["artifacts"] = json!([{ "location": { "uri": uri } }]);
+    }
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [run],
+    })
+}
+
+fn junit(artifact: Option<&str>, results: &[EventResult]) -> String {
+    let failures = results
+        .iter()
+        .filter(|event| !matches!(&event.result, Ok(report) if report.is_valid))
+        .count();
+    let suite = xml_escape(artifact.unwrap_or("ocsf-events"));
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"ocsf-validation\" tests=\"{}\" failures=\"{}\">\n",
+        results.len(),
+        failures
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\">\n",
+        suite,
+        results.len(),
+        failures
+    ));
+    for event in results {
+        let name = match event.line {
+            Some(line) => format!("event {} (line {})", event.index, line),
+            None => format!("event {}", event.index),
+        };
+        let class = match &event.result {
+            Ok(report) => report.event_class.as_deref().unwrap_or("unknown"),
+            Err(_) => "unknown",
+        };
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\"",
+            name,
+            xml_escape(class)
+        ));
+        let failures: Vec<(&str, &str, String)> = match &event.result {
+            Ok(report) => report
+                .errors
+                .iter()
+                .map(|e| {
+                    let text = match (e.line, e.column) {
+                        (Some(line), Some(column)) => {
+                            format!("{} ({}:{}): {}", e.pointer, line, column, e.message)
+                        }
+                        _ => format!("{}: {}", e.pointer, e.message),
+                    };
+                    (e.error_type.name(), e.message.as_str(), text)
+                })
+                .collect(),
+            Err(message) => vec![(INVALID_JSON_RULE, message.as_str(), message.clone())],
+        };
+        if failures.is_empty() {
+            xml.push_str("/>\n");
+            continue;
+        }
+        xml.push_str(">\n");
+        for (rule, message, text) in failures {
+            xml.push_str(&format!(
+                "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+                rule,
+                xml_escape(message),
+                xml_escape(&text)
+            ));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,214 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::bulk::DEFAULT_WINDOW_START_MS;
+use super::event::OcsfEvent;
+use super::schema::OcsfSchema;
+use super::synthetic::{random_uuid, EventSynthesizer, SyntheticOptions};
+
+/// Entity references may nest (e.g. process lineage); deeper chains are treated as cycles
+const MAX_ENTITY_DEPTH: usize = 16;
+
+const BUILTIN_SCENARIOS: &[&str] = &[
+    include_str!("../../data/scenarios/ssh_brute_force_intrusion.json"),
+    include_str!("../../data/scenarios/data_exfiltration.json"),
+];
+
+/// Declarative attack storyline: shared entities plus an ordered list of event steps
+///
+/// String values of the form `$entity` or `$entity.path` inside `entities` and step
+/// `fields` are replaced by the referenced entity (or one of its attributes) when `entity` is
+/// declared; other strings are kept as they are, and a leading `$$` stands for a literal `$`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub entities: Map<String, Value>,
+    pub steps: Vec<ScenarioStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioStep {
+    /// OCSF event class name
+    pub class: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Number of events emitted by this step
+    #[serde(default = "default_repeat")]
+    pub repeat: usize,
+    /// Delay before the first event of this step, relative to the previous event
+    #[serde(default)]
+    pub delay_ms: i64,
+    /// Delay between repeated events of this step
+    #[serde(default)]
+    pub interval_ms: i64,
+    /// Attribute values set on every event of this step
+    #[serde(default)]
+    pub fields: Map<String, Value>,
+}
+
+fn default_repeat() -> usize {
+    1
+}
+
+/// Options for running a scenario
+#[derive(Debug, Clone)]
+pub struct ScenarioOptions {
+    pub seed: u64,
+    /// Time of the first event (epoch milliseconds)
+    pub start_ms: i64,
+    /// Fill attributes not set by the scenario with synthetic values
+    pub synthesize: bool,
+    pub profiles: Vec<String>,
+}
+
+impl Default for ScenarioOptions {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            start_ms: DEFAULT_WINDOW_START_MS,
+            synthesize: true,
+            profiles: Vec::new(),
+        }
+    }
+}
+
+impl Scenario {
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| anyhow::anyhow!("Invalid scenario definition: {}", e))
+    }
+
+    /// All scenarios bundled with the server
+    pub fn builtins() -> Vec<Scenario> {
+        BUILTIN_SCENARIOS
+            .iter()
+            .map(|json| Self::from_json(json).expect("built-in scenarios are valid"))
+            .collect()
+    }
+
+    pub fn builtin(name: &str) -> Option<Scenario> {
+        Self::builtins().into_iter().find(|s| s.name == name)
+    }
+
+    /// Generate the scenario's correlated event sequence
+    ///
+    /// All events share a `metadata.correlation_uid` derived from the seed, and timestamps
+    /// advance from `start_ms` by each step's `delay_ms`/`interval_ms`.
+    pub fn run(
+        &self,
+        schema: &OcsfSchema,
+        options: &ScenarioOptions,
+    ) -> anyhow::Result<Vec<OcsfEvent>> {
+        let mut rng = ChaCha8Rng::seed_from_u64(options.seed);
+        let correlation_uid = random_uuid(&mut rng);
+
+        let mut events = Vec::new();
+        let mut time_ms = options.start_ms;
+        let mut first = true;
+
+        for (index, step) in self.steps.iter().enumerate() {
+            let ec = schema.get_event_class(&step.class).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Step {} uses unknown event class '{}'",
+                    index + 1,
+                    step.class
+                )
+            })?;
+            let fields = self.resolve_fields(&step.fields, 0)?;
+
+            for repeat in 0..step.repeat {
+                if !first {
+                    time_ms += if repeat == 0 {
+                        step.delay_ms
+                    } else {
+                        step.interval_ms
+                    };
+                }
+                first = false;
+
+                let synthesizer = EventSynthesizer::new(
+                    schema,
+                    SyntheticOptions {
+                        profiles: options.profiles.clone(),
+                        time_ms,
+                        ..Default::default()
+                    },
+                );
+                let mut event = if options.synthesize {
+                    synthesizer.generate(&step.class, &mut rng)?
+                } else {
+                    OcsfEvent::new(ec, &schema.version)
+                };
+
+                for (key, value) in &fields {
+                    event.set_field(key.clone(), value.clone())?;
+                }
+                event.set_field("time".to_string(), Value::from(time_ms))?;
+                synthesizer.sync_siblings(ec, &mut event);
+                synthesizer.sync_type_uid(ec, &mut event);
+
+                event.metadata.uid = Some(random_uuid(&mut rng));
+                event.metadata.correlation_uid = Some(correlation_uid.clone());
+                events.push(event);
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Replace `$entity[.path]` references with entity values, recursively
+    fn resolve(&self, value: &Value, depth: usize) -> anyhow::Result<Value> {
+        if depth > MAX_ENTITY_DEPTH {
+            anyhow::bail!(
+                "Entity references in scenario '{}' are too deep or cyclic",
+                self.name
+            );
+        }
+
+        match value {
+            Value::String(s) => self.resolve_str(s, depth),
+            Value::Object(map) => self.resolve_fields(map, depth).map(Value::Object),
+            Value::Array(items) => Ok(Value::Array(
+                items
+                    .iter()
+                    .map(|v| self.resolve(v, depth))
+                    .collect::<anyhow::Result<_>>()?,
+            )),
+            other => Ok(other.clone()),
+        }
+    }
+
+    /// Resolve a string: `$$` escapes a literal `$`, and `$name[.path]` refers to the entity
+    /// `name` only when it is declared
+    fn resolve_str(&self, s: &str, depth: usize) -> anyhow::Result<Value> {
+        let mut path = match s.strip_prefix('$') {
+            Some(escaped) if escaped.starts_with('$') => return Ok(Value::String(escaped.into())),
+            Some(reference) => reference.split('.'),
+            None => return Ok(Value::String(s.to_string())),
+        };
+        let Some(entity) = path.next().and_then(|name| self.entities.get(name)) else {
+            return Ok(Value::String(s.to_string()));
+        };
+        let target = path
+            .try_fold(entity, |v, segment| v.get(segment))
+            .ok_or_else(|| anyhow::anyhow!("Entity path '{}' does not exist", s))?;
+        self.resolve(target, depth + 1)
+    }
+
+    /// Resolve the entity references of every field of an object
+    fn resolve_fields(
+        &self,
+        fields: &Map<String, Value>,
+        depth: usize,
+    ) -> anyhow::Result<Map<String, Value>> {
+        fields
+            .iter()
+            .map(|(key, v)| Ok((key.clone(), self.resolve(v, depth)?)))
+            .collect()
+    }
+}
@@ -28,10 +28,30 @@ pub struct EventClass {
     pub description: Option<String>,
     #[serde(default)]
     pub category: String,
+    /// Explicit in resolved exports; extension classes do not follow the `uid / 1000` rule
+    #[serde(default)]
+    pub category_uid: Option<u32>,
     #[serde(default)]
     pub attributes: HashMap<String, Attribute>,
     #[serde(default)]
     pub extends: Option<String>,
+    /// Attribute paths that describe the same entity (e.g. `user` ↔ `dst_endpoint`)
+    #[serde(default)]
+    pub associations: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub profiles: Vec<String>,
+    #[serde(default)]
+    pub extension: Option<String>,
+    #[serde(rename = "@deprecated")]
+    #[serde(default)]
+    pub deprecated: Option<Deprecation>,
+}
+
+impl EventClass {
+    /// Category UID, falling back to the `uid / 1000` convention of core classes
+    pub fn resolved_category_uid(&self) -> u32 {
+        self.category_uid.unwrap_or(self.uid / 1000)
+    }
 }
 
 /// OCSF Object definition
@@ -46,6 +66,9 @@ pub struct Object {
     pub attributes: HashMap<String, Attribute>,
     #[serde(default)]
     pub extends: Option<String>,
+    #[serde(rename = "@deprecated")]
+    #[serde(default)]
+    pub deprecated: Option<Deprecation>,
 }
 
 /// OCSF Attribute definition
@@ -62,6 +85,41 @@ pub struct Attribute {
     pub requirement: Option<String>,
     #[serde(default)]
     pub type_name: Option<String>,
+    #[serde(default)]
+    pub object_type: Option<String>,
+    #[serde(default)]
+    pub is_array: bool,
+    #[serde(rename = "enum")]
+    #[serde(default)]
+    pub enum_values: Option<HashMap<String, EnumValue>>,
+    /// Name of the caption attribute paired with an enum `_id` attribute
+    #[serde(default)]
+    pub sibling: Option<String>,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub extension: Option<String>,
+    #[serde(rename = "@deprecated")]
+    #[serde(default)]
+    pub deprecated: Option<Deprecation>,
+}
+
+/// Deprecation notice attached to classes, objects and attributes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Deprecation {
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub since: Option<String>,
+}
+
+/// OCSF enumeration member (keyed by its value in `Attribute::enum_values`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnumValue {
+    #[serde(default)]
+    pub caption: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 /// OCSF Type definition
@@ -71,6 +129,16 @@ pub struct TypeDef {
     pub caption: Option<String>,
     #[serde(default)]
     pub description: Option<String>,
+    /// Base type for derived types (e.g. `ip_t` is a `string_t`)
+    #[serde(rename = "type")]
+    #[serde(default)]
+    pub base_type: Option<String>,
+    #[serde(default)]
+    pub regex: Option<String>,
+    #[serde(default)]
+    pub max_len: Option<u64>,
+    #[serde(default)]
+    pub range: Option<Vec<i64>>,
 }
 
 /// Schema browsing result
@@ -80,6 +148,7 @@ pub struct SchemaInfo {
     pub categories: Option<Vec<CategorySummary>>,
     pub event_classes: Option<Vec<EventClassSummary>>,
     pub attributes: Option<Vec<AttributeSummary>>,
+    pub associations: Option<HashMap<String, Vec<String>>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -126,10 +195,10 @@ impl OcsfSchema {
         for entry in std::fs::read_dir(schema_dir)? {
             let entry = entry?;
             let path = entry.path();
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                if name.ends_with(".json") {
-                    versions.push(name.trim_end_matches(".json").to_string());
-                }
+            if let Some(name) = path.file_name().and_then(|n| n.to_str())
+                && name.ends_with(".json")
+            {
+                versions.push(name.trim_end_matches(".json").to_string());
             }
         }
 
@@ -198,8 +267,13 @@ impl OcsfSchema {
                     "User authentication events (login, logout, failed attempts)".to_string(),
                 ),
                 category: "iam".to_string(),
+                category_uid: None,
                 attributes: HashMap::new(),
                 extends: None,
+                associations: HashMap::new(),
+                profiles: Vec::new(),
+                extension: None,
+                deprecated: None,
             },
         );
 
@@ -212,8 +286,13 @@ impl OcsfSchema {
                 caption: Some("Process Activity".to_string()),
                 description: Some("Process lifecycle events (start, stop, injection)".to_string()),
                 category: "system".to_string(),
+                category_uid: None,
                 attributes: HashMap::new(),
                 extends: None,
+                associations: HashMap::new(),
+                profiles: Vec::new(),
+                extension: None,
+                deprecated: None,
             },
         );
 
@@ -226,8 +305,13 @@ impl OcsfSchema {
                 caption: Some("File Activity".to_string()),
                 description: Some("File system operations".to_string()),
                 category: "system".to_string(),
+                category_uid: None,
                 attributes: HashMap::new(),
                 extends: None,
+                associations: HashMap::new(),
+                profiles: Vec::new(),
+                extension: None,
+                deprecated: None,
             },
         );
 
@@ -240,8 +324,13 @@ impl OcsfSchema {
                 caption: Some("Network Activity".to_string()),
                 description: Some("Network connections and traffic".to_string()),
                 category: "network".to_string(),
+                category_uid: None,
                 attributes: HashMap::new(),
                 extends: None,
+                associations: HashMap::new(),
+                profiles: Vec::new(),
+                extension: None,
+                deprecated: None,
             },
         );
 
@@ -258,6 +347,47 @@ impl OcsfSchema {
         self.classes.get(name)
     }
 
+    /// Resolve a (possibly derived) OCSF type to its primitive type, e.g. `ip_t` → `string_t`
+    pub fn base_type<'a>(&'a self, data_type: &'a str) -> &'a str {
+        let mut current = data_type;
+        while let Some(base) = self.types.get(current).and_then(|t| t.base_type.as_deref()) {
+            if base == current {
+                break;
+            }
+            current = base;
+        }
+        current
+    }
+
+    /// Compute `type_uid` (`class_uid * 100 + activity_id`) and its caption for an event class
+    pub fn type_uid_for(
+        &self,
+        event_class: &EventClass,
+        activity_id: i64,
+    ) -> (i64, Option<String>) {
+        let type_uid = i64::from(event_class.uid) * 100 + activity_id;
+        let caption = event_class
+            .attributes
+            .get("type_uid")
+            .and_then(|attr| attr.enum_values.as_ref())
+            .and_then(|values| values.get(&type_uid.to_string()))
+            .and_then(|value| value.caption.clone());
+        (type_uid, caption)
+    }
+
+    pub fn get_event_class_by_uid(&self, uid: u32) -> Option<&EventClass> {
+        self.classes.values().find(|ec| ec.uid == uid)
+    }
+
+    /// Resolve the event class of an event from its top-level `class_uid`
+    pub fn class_for_event(&self, event: &serde_json::Value) -> Option<&EventClass> {
+        event
+            .get("class_uid")
+            .and_then(|v| v.as_u64())
+            .and_then(|uid| u32::try_from(uid).ok())
+            .and_then(|uid| self.get_event_class_by_uid(uid))
+    }
+
     pub fn list_categories(&self) -> Vec<CategorySummary> {
         // Group classes by category
         let mut category_map: HashMap<String, Vec<String>> = HashMap::new();
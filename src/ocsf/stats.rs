@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use super::schema::{Attribute, OcsfSchema};
+
+/// Number of largest enums listed in `SchemaStats::largest_enums`
+const LARGEST_ENUM_LIMIT: usize = 10;
+
+/// Structured counts describing a loaded OCSF schema version
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SchemaStats {
+    pub version: String,
+    pub class_count: usize,
+    pub object_count: usize,
+    pub type_count: usize,
+    pub dictionary_attribute_count: usize,
+    pub classes_per_category: BTreeMap<String, usize>,
+    /// Dictionary attributes grouped by their OCSF type (e.g. `string_t`, `ip_t`)
+    pub attributes_by_type: BTreeMap<String, usize>,
+    /// Attribute definitions across all classes and objects
+    pub class_attribute_count: usize,
+    pub object_attribute_count: usize,
+    pub enum_count: usize,
+    pub enum_value_count: usize,
+    pub largest_enums: Vec<EnumSize>,
+    pub deprecated: DeprecatedStats,
+    pub profile_usage: BTreeMap<String, ProfileUsage>,
+    /// Dangling references and empty sections that hint at a truncated or broken file
+    pub integrity_warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnumSize {
+    pub owner: String,
+    pub attribute: String,
+    pub values: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DeprecatedStats {
+    pub classes: Vec<String>,
+    pub objects: Vec<String>,
+    pub attributes: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProfileUsage {
+    /// Classes that list the profile in `profiles`
+    pub classes: usize,
+    /// Class and object attributes contributed by the profile
+    pub attributes: usize,
+}
+
+impl OcsfSchema {
+    /// Compute structured statistics for this schema version
+    pub fn stats(&self) -> SchemaStats {
+        let mut stats = SchemaStats {
+            version: self.version.clone(),
+            class_count: 0,
+            object_count: self.objects.len(),
+            type_count: self.types.len(),
+            dictionary_attribute_count: self.dictionary_attributes.len(),
+            classes_per_category: BTreeMap::new(),
+            attributes_by_type: BTreeMap::new(),
+            class_attribute_count: 0,
+            object_attribute_count: 0,
+            enum_count: 0,
+            enum_value_count: 0,
+            largest_enums: Vec::new(),
+            deprecated: DeprecatedStats::default(),
+            profile_usage: BTreeMap::new(),
+            integrity_warnings: Vec::new(),
+        };
+
+        for attr in self.dictionary_attributes.values() {
+            let data_type = attr
+                .data_type
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string());
+            *stats.attributes_by_type.entry(data_type).or_default() += 1;
+        }
+
+        let mut enums = Vec::new();
+
+        for (name, class) in &self.classes {
+            if name == "base_event" {
+                continue;
+            }
+            stats.class_count += 1;
+            stats.class_attribute_count += class.attributes.len();
+            *stats
+                .classes_per_category
+                .entry(class.category.clone())
+                .or_default() += 1;
+
+            if class.deprecated.is_some() {
+                stats.deprecated.classes.push(name.clone());
+            }
+            for profile in &class.profiles {
+                stats
+                    .profile_usage
+                    .entry(profile.clone())
+                    .or_default()
+                    .classes += 1;
+            }
+            if class.attributes.is_empty() {
+                stats
+                    .integrity_warnings
+                    .push(format!("Class '{name}' has no attributes"));
+            }
+
+            self.tally_attributes(name, &class.attributes, &mut stats, &mut enums);
+        }
+
+        for (name, object) in &self.objects {
+            stats.object_attribute_count += object.attributes.len();
+            if object.deprecated.is_some() {
+                stats.deprecated.objects.push(name.clone());
+            }
+            self.tally_attributes(name, &object.attributes, &mut stats, &mut enums);
+        }
+
+        if stats.class_count == 0 {
+            stats
+                .integrity_warnings
+                .push("Schema defines no event classes".to_string());
+        }
+        if self.objects.is_empty() {
+            stats
+                .integrity_warnings
+                .push("Schema defines no objects".to_string());
+        }
+        if self.dictionary_attributes.is_empty() {
+            stats
+                .integrity_warnings
+                .push("Schema has an empty attribute dictionary".to_string());
+        }
+
+        stats.enum_count = enums.len();
+        stats.enum_value_count = enums.iter().map(|e| e.values).sum();
+        enums.sort_by(|a, b| {
+            b.values
+                .cmp(&a.values)
+                .then_with(|| a.owner.cmp(&b.owner))
+                .then_with(|| a.attribute.cmp(&b.attribute))
+        });
+        enums.truncate(LARGEST_ENUM_LIMIT);
+        stats.largest_enums = enums;
+
+        stats.deprecated.classes.sort();
+        stats.deprecated.objects.sort();
+        stats.integrity_warnings.sort();
+
+        stats
+    }
+
+    fn tally_attributes(
+        &self,
+        owner: &str,
+        attributes: &HashMap<String, Attribute>,
+        stats: &mut SchemaStats,
+        enums: &mut Vec<EnumSize>,
+    ) {
+        for (attr_name, attr) in attributes {
+            if attr.deprecated.is_some() {
+                stats.deprecated.attributes += 1;
+            }
+            if let Some(profile) = &attr.profile {
+                stats
+                    .profile_usage
+                    .entry(profile.clone())
+                    .or_default()
+                    .attributes += 1;
+            }
+            if let Some(values) = &attr.enum_values {
+                enums.push(EnumSize {
+                    owner: owner.to_string(),
+                    attribute: attr_name.clone(),
+                    values: values.len(),
+                });
+            }
+            if let Some(object_type) = &attr.object_type
+                && !self.objects.contains_key(object_type)
+            {
+                stats.integrity_warnings.push(format!(
+                    "'{owner}.{attr_name}' references missing object '{object_type}'"
+                ));
+            }
+            if let Some(data_type) = &attr.data_type
+                && data_type != "object_t"
+                && !self.types.is_empty()
+                && !self.types.contains_key(data_type)
+            {
+                stats.integrity_warnings.push(format!(
+                    "'{owner}.{attr_name}' uses undefined type '{data_type}'"
+                ));
+            }
+        }
+    }
+}
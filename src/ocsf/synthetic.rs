@@ -0,0 +1,422 @@
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+use super::event::OcsfEvent;
+use super::schema::{Attribute, EventClass, OcsfSchema};
+
+const USER_NAMES: &[&str] = &[
+    "alice",
+    "bob",
+    "carol",
+    "dave",
+    "erin",
+    "frank",
+    "svc-backup",
+];
+const PROCESS_NAMES: &[&str] = &[
+    "sshd", "bash", "python3", "nginx", "curl", "systemd", "java",
+];
+const FILE_NAMES: &[&str] = &[
+    "report.pdf",
+    "id_rsa",
+    "config.yaml",
+    "payload.sh",
+    "app.log",
+];
+const DIRECTORIES: &[&str] = &["/home/alice", "/tmp", "/var/log", "/etc", "/opt/app"];
+const PRODUCT_NAMES: &[&str] = &["Sentinel EDR", "Gatekeeper IdP", "Netwatch", "Auditd"];
+const VENDOR_NAMES: &[&str] = &["Acme Security", "Globex", "Initech", "Umbrella Corp"];
+const DOMAINS: &[&str] = &["example.com", "corp.example.com", "example.org"];
+const REGIONS: &[&str] = &["us-east-1", "eu-west-1", "ap-south-1"];
+
+/// Options for schema-aware synthetic event generation
+#[derive(Debug, Clone)]
+pub struct SyntheticOptions {
+    /// Profiles whose attributes may be populated (e.g. `host`, `cloud`)
+    pub profiles: Vec<String>,
+    /// Populate recommended attributes in addition to required ones
+    pub include_recommended: bool,
+    /// Object nesting depth after which only required attributes are generated
+    pub max_depth: usize,
+    /// Event time (epoch milliseconds); other timestamps are generated shortly before it
+    pub time_ms: i64,
+}
+
+impl Default for SyntheticOptions {
+    fn default() -> Self {
+        Self {
+            profiles: Vec::new(),
+            include_recommended: true,
+            max_depth: 3,
+            time_ms: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+/// Generates type-correct OCSF events from the resolved class definitions of a schema
+pub struct EventSynthesizer<'a> {
+    schema: &'a OcsfSchema,
+    options: SyntheticOptions,
+}
+
+impl<'a> EventSynthesizer<'a> {
+    pub fn new(schema: &'a OcsfSchema, options: SyntheticOptions) -> Self {
+        Self { schema, options }
+    }
+
+    /// Generate an event of the given class with all required (and recommended) attributes
+    pub fn generate<R: Rng + ?Sized>(
+        &self,
+        event_class: &str,
+        rng: &mut R,
+    ) -> anyhow::Result<OcsfEvent> {
+        let ec = self.event_class(event_class)?;
+        let mut event = OcsfEvent::new(ec, &self.schema.version);
+        event.metadata.profiles = self.options.profiles.clone();
+
+        let mut fields = Map::new();
+        for (name, attr) in sorted(&ec.attributes) {
+            // The event envelope owns metadata
+            if name == "metadata" || !self.wanted(attr, 0) {
+                continue;
+            }
+            self.fill(&mut fields, &ec.name, name, attr, 0, rng);
+        }
+        fields.insert("time".to_string(), json!(self.options.time_ms));
+        for (key, value) in fields {
+            event.set_field(key, value)?;
+        }
+        // Classification values come from the class, not from random enum members
+        event.class_uid = ec.uid;
+        event.category_uid = ec.resolved_category_uid();
+        self.sync_siblings(ec, &mut event);
+        self.sync_type_uid(ec, &mut event);
+
+        Ok(event)
+    }
+
+    /// Generate a value (and its enum sibling, if any) for one class attribute
+    pub fn generate_attribute<R: Rng + ?Sized>(
+        &self,
+        event: &mut OcsfEvent,
+        event_class: &str,
+        name: &str,
+        rng: &mut R,
+    ) -> anyhow::Result<()> {
+        let ec = self.event_class(event_class)?;
+        let attr = ec.attributes.get(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Attribute '{}' is not defined for event class '{}'",
+                name,
+                ec.name
+            )
+        })?;
+
+        let mut fields = Map::new();
+        self.fill(&mut fields, &ec.name, name, attr, 0, rng);
+        for (key, value) in fields {
+            event.set_field(key, value)?;
+        }
+        if name == "activity_id" {
+            self.sync_type_uid(ec, event);
+        }
+        Ok(())
+    }
+
+    /// Recompute `type_uid`/`type_name` so they agree with `activity_id`
+    pub fn sync_type_uid(&self, event_class: &EventClass, event: &mut OcsfEvent) {
+        let (type_uid, caption) = self.schema.type_uid_for(event_class, event.activity_id);
+        event.type_uid = type_uid;
+        if let Some(caption) = caption {
+            event.fields.insert("type_name".to_string(), json!(caption));
+        }
+    }
+
+    /// Set the caption sibling of every top-level enum attribute to match its value
+    pub fn sync_siblings(&self, event_class: &EventClass, event: &mut OcsfEvent) {
+        for (name, attr) in &event_class.attributes {
+            let (Some(values), Some(sibling)) = (&attr.enum_values, &attr.sibling) else {
+                continue;
+            };
+            let value = event.get_field(name);
+            let caption = match &value {
+                Some(Value::Number(n)) => values.get(&n.to_string()),
+                Some(Value::String(s)) => values.get(s),
+                _ => None,
+            }
+            .and_then(|v| v.caption.clone());
+
+            // `Other` (99) keeps the source-specific caption supplied by the caller
+            if let Some(caption) = caption
+                && !(value.and_then(|v| v.as_i64()) == Some(99)
+                    && event.fields.contains_key(sibling))
+            {
+                event.fields.insert(sibling.clone(), json!(caption));
+            }
+        }
+    }
+
+    fn event_class(&self, name: &str) -> anyhow::Result<&'a EventClass> {
+        self.schema
+            .get_event_class(name)
+            .ok_or_else(|| anyhow::anyhow!("Event class '{}' not found", name))
+    }
+
+    fn wanted(&self, attr: &Attribute, depth: usize) -> bool {
+        let required = attr.requirement.as_deref() == Some("required");
+        if attr.deprecated.is_some() && !required {
+            return false;
+        }
+        if let Some(profile) = &attr.profile
+            && !self.options.profiles.contains(profile)
+        {
+            return false;
+        }
+        required
+            || (attr.requirement.as_deref() == Some("recommended")
+                && self.options.include_recommended
+                && depth < self.options.max_depth)
+    }
+
+    fn fill<R: Rng + ?Sized>(
+        &self,
+        fields: &mut Map<String, Value>,
+        owner: &str,
+        name: &str,
+        attr: &Attribute,
+        depth: usize,
+        rng: &mut R,
+    ) {
+        // Enum attributes pick a member and set the caption sibling to match
+        if let Some(values) = &attr.enum_values
+            && !values.is_empty()
+        {
+            let (key, caption) = pick_enum(values, rng);
+            let value = match key.parse::<i64>() {
+                Ok(n) if self.is_numeric(attr) => json!(n),
+                _ => json!(key),
+            };
+            fields.insert(name.to_string(), wrap_array(attr, value));
+            if let (Some(sibling), Some(caption)) = (&attr.sibling, caption) {
+                fields.insert(sibling.clone(), wrap_array(attr, json!(caption)));
+            }
+            return;
+        }
+
+        let value = if attr.is_array {
+            let count = rng.random_range(1..=2);
+            Value::Array(
+                (0..count)
+                    .map(|_| self.value(owner, name, attr, depth, rng))
+                    .collect(),
+            )
+        } else {
+            self.value(owner, name, attr, depth, rng)
+        };
+        fields.insert(name.to_string(), value);
+    }
+
+    fn value<R: Rng + ?Sized>(
+        &self,
+        owner: &str,
+        name: &str,
+        attr: &Attribute,
+        depth: usize,
+        rng: &mut R,
+    ) -> Value {
+        if let Some(object_type) = &attr.object_type {
+            return self.object(object_type, depth + 1, rng);
+        }
+
+        let data_type = attr.data_type.as_deref().unwrap_or("string_t");
+        match data_type {
+            "timestamp_t" => json!(self.options.time_ms - rng.random_range(0..86_400_000)),
+            "datetime_t" => {
+                let ms = self.options.time_ms - rng.random_range(0..86_400_000);
+                let dt = chrono::DateTime::from_timestamp_millis(ms).unwrap_or_default();
+                json!(dt.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+            }
+            "ip_t" => json!(random_ip(rng)),
+            "subnet_t" => json!(format!(
+                "10.{}.{}.0/24",
+                rng.random_range(0..=255),
+                rng.random_range(0..=255)
+            )),
+            "mac_t" => {
+                let octets: Vec<String> = (0..6)
+                    .map(|_| format!("{:02x}", rng.random::<u8>()))
+                    .collect();
+                json!(octets.join(":"))
+            }
+            "port_t" => json!(rng.random_range(1024..=65535)),
+            "email_t" => json!(format!("{}@{}", pick(USER_NAMES, rng), pick(DOMAINS, rng))),
+            "file_hash_t" => json!(random_hex(rng, 64)),
+            "hostname_t" => json!(format!(
+                "host-{:02}.{}",
+                rng.random_range(1..100),
+                pick(DOMAINS, rng)
+            )),
+            "url_t" => json!(format!(
+                "https://{}/api/v1/{}",
+                pick(DOMAINS, rng),
+                pick(USER_NAMES, rng)
+            )),
+            "uuid_t" => json!(random_uuid(rng)),
+            "username_t" => json!(pick(USER_NAMES, rng)),
+            "process_name_t" => json!(pick(PROCESS_NAMES, rng)),
+            "file_name_t" => json!(pick(FILE_NAMES, rng)),
+            "resource_uid_t" => json!(format!(
+                "arn:aws:s3:::{}-{}",
+                pick(USER_NAMES, rng),
+                rng.random_range(100..1000)
+            )),
+            "bytestring_t" => json!("U3ludGhldGljIE9DU0YgZGF0YQ=="),
+            "json_t" => json!({}),
+            _ => match self.schema.base_type(data_type) {
+                "boolean_t" => json!(rng.random_bool(0.5)),
+                "integer_t" => json!(self.integer(data_type, name, rng)),
+                "long_t" => json!(rng.random_range(0..1_000_000i64)),
+                "float_t" => json!((rng.random_range(0.0..100.0f64) * 100.0).round() / 100.0),
+                _ => json!(string_value(owner, name, rng)),
+            },
+        }
+    }
+
+    fn object<R: Rng + ?Sized>(&self, object_type: &str, depth: usize, rng: &mut R) -> Value {
+        let Some(object) = self.schema.objects.get(object_type) else {
+            return json!({});
+        };
+
+        let mut fields = Map::new();
+        for (name, attr) in sorted(&object.attributes) {
+            if self.wanted(attr, depth) {
+                self.fill(&mut fields, object_type, name, attr, depth, rng);
+            }
+        }
+        Value::Object(fields)
+    }
+
+    fn integer<R: Rng + ?Sized>(&self, data_type: &str, name: &str, rng: &mut R) -> i64 {
+        if let Some([min, max]) = self
+            .schema
+            .types
+            .get(data_type)
+            .and_then(|t| t.range.as_deref())
+        {
+            return rng.random_range(*min..=*max);
+        }
+        match name {
+            "pid" | "ppid" => rng.random_range(2..65_535),
+            "count" => rng.random_range(1..10),
+            "timezone_offset" => 0,
+            _ => rng.random_range(0..1_000),
+        }
+    }
+
+    fn is_numeric(&self, attr: &Attribute) -> bool {
+        let data_type = attr.data_type.as_deref().unwrap_or("string_t");
+        matches!(self.schema.base_type(data_type), "integer_t" | "long_t")
+    }
+}
+
+/// Iterate attributes in name order so seeded generation is reproducible
+fn sorted(attributes: &HashMap<String, Attribute>) -> Vec<(&String, &Attribute)> {
+    let mut attrs: Vec<_> = attributes.iter().collect();
+    attrs.sort_by(|a, b| a.0.cmp(b.0));
+    attrs
+}
+
+fn wrap_array(attr: &Attribute, value: Value) -> Value {
+    if attr.is_array {
+        Value::Array(vec![value])
+    } else {
+        value
+    }
+}
+
+/// Pick an enum member, preferring concrete values over `0` (Unknown) and `99` (Other)
+fn pick_enum<R: Rng + ?Sized>(
+    values: &HashMap<String, super::schema::EnumValue>,
+    rng: &mut R,
+) -> (String, Option<String>) {
+    let mut keys: Vec<&String> = values.keys().collect();
+    keys.sort();
+    let concrete: Vec<&String> = keys
+        .iter()
+        .copied()
+        .filter(|k| k.as_str() != "0" && k.as_str() != "99")
+        .collect();
+    let candidates = if concrete.is_empty() {
+        &keys
+    } else {
+        &concrete
+    };
+
+    let key = (*candidates.choose(rng).expect("enum has members")).clone();
+    let caption = values[&key].caption.clone();
+    (key, caption)
+}
+
+fn pick<'a, R: Rng + ?Sized>(items: &[&'a str], rng: &mut R) -> &'a str {
+    items.choose(rng).copied().unwrap_or_default()
+}
+
+fn random_ip<R: Rng + ?Sized>(rng: &mut R) -> String {
+    match rng.random_range(0..3) {
+        0 => format!(
+            "10.{}.{}.{}",
+            rng.random_range(0..=255),
+            rng.random_range(0..=255),
+            rng.random_range(1..255)
+        ),
+        1 => format!(
+            "192.168.{}.{}",
+            rng.random_range(0..=255),
+            rng.random_range(1..255)
+        ),
+        // TEST-NET-3, reserved for documentation
+        _ => format!("203.0.113.{}", rng.random_range(1..255)),
+    }
+}
+
+fn random_hex<R: Rng + ?Sized>(rng: &mut R, len: usize) -> String {
+    (0..len)
+        .map(|_| format!("{:x}", rng.random_range(0..16u8)))
+        .collect()
+}
+
+pub(crate) fn random_uuid<R: Rng + ?Sized>(rng: &mut R) -> String {
+    uuid::Builder::from_random_bytes(rng.random())
+        .into_uuid()
+        .to_string()
+}
+
+fn string_value<R: Rng + ?Sized>(owner: &str, name: &str, rng: &mut R) -> String {
+    match (owner, name) {
+        ("user", "name") | (_, "account_name") => pick(USER_NAMES, rng).to_string(),
+        ("process", "name") => pick(PROCESS_NAMES, rng).to_string(),
+        ("file", "name") => pick(FILE_NAMES, rng).to_string(),
+        ("product", "name") => pick(PRODUCT_NAMES, rng).to_string(),
+        (_, "vendor_name") => pick(VENDOR_NAMES, rng).to_string(),
+        (_, "uid") | (_, "instance_uid") | (_, "tenant_uid") => random_uuid(rng),
+        (_, "path") => format!("{}/{}", pick(DIRECTORIES, rng), pick(FILE_NAMES, rng)),
+        (_, "parent_folder") => pick(DIRECTORIES, rng).to_string(),
+        (_, "ext") => "log".to_string(),
+        (_, "cmd_line") => format!("/usr/bin/{} --verbose", pick(PROCESS_NAMES, rng)),
+        (_, "version") => format!(
+            "{}.{}.{}",
+            rng.random_range(1..5),
+            rng.random_range(0..10),
+            rng.random_range(0..20)
+        ),
+        (_, "domain") => pick(DOMAINS, rng).to_string(),
+        (_, "region") => pick(REGIONS, rng).to_string(),
+        (_, "interface_name") => format!("eth{}", rng.random_range(0..4)),
+        (_, "svc_name") => pick(PROCESS_NAMES, rng).to_string(),
+        (_, "message") => format!("Synthetic {} event", owner.replace('_', " ")),
+        (_, "status_code") => rng.random_range(0..5).to_string(),
+        _ => format!("{}-{}-{}", owner, name, rng.random_range(1..1000)),
+    }
+}
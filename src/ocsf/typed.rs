@@ -0,0 +1,65 @@
+//! Strongly typed OCSF classes, objects and `_id` enums generated at build time
+//!
+//! Enabled with the `typed-events` feature. The schema version is chosen with the
+//! `OCSF_TYPED_VERSION` environment variable (defaults to 1.4.0) and must be bundled
+//! under `data/ocsf-schema/`.
+//!
+//! Required attributes are plain fields and optional or profile attributes are `Option`s,
+//! so a missing required attribute is a compile error. Structs with required attributes get
+//! a `new()` taking them in alphabetical order; for event classes `class_uid`,
+//! `category_uid` and `type_uid` are derived. `_id` enums serialize as their integer value.
+
+// The server binary compiles this module tree too but only library users need the types
+#![allow(dead_code)]
+// Constructors take every required attribute of the class
+#![allow(clippy::too_many_arguments)]
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::event::OcsfEvent;
+
+/// Identity of a generated event class struct
+pub trait OcsfClass {
+    const NAME: &'static str;
+    const CLASS_UID: i32;
+    const CATEGORY_UID: i32;
+}
+
+/// An integer that is not a member of a generated `_id` enum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownEnumValue {
+    pub enum_name: &'static str,
+    pub value: i32,
+}
+
+impl std::fmt::Display for UnknownEnumValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is not a valid {} value", self.value, self.enum_name)
+    }
+}
+
+impl std::error::Error for UnknownEnumValue {}
+
+/// Convert a typed event into the generic event model (e.g. for validation)
+pub fn to_event<T: OcsfClass + Serialize>(event: &T) -> serde_json::Result<OcsfEvent> {
+    serde_json::from_value(serde_json::to_value(event)?)
+}
+
+macro_rules! impl_id_serde {
+    ($name:ident) => {
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_i32(self.id())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = i32::deserialize(deserializer)?;
+                Self::try_from(value).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+include!(concat!(env!("OUT_DIR"), "/ocsf_typed.rs"));
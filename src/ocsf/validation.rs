@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use super::schema::{Attribute, EventClass, OcsfSchema};
 
 /// Validation result for OCSF events
 #[derive(Debug, Serialize, Deserialize)]
@@ -14,11 +17,22 @@ pub struct ValidationReport {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidationError {
     pub field: String,
+    /// RFC 6901 JSON pointer to the attribute (`/observables/2/value`); for a missing attribute,
+    /// where it belongs
+    #[serde(default)]
+    pub pointer: String,
     pub message: String,
     pub error_type: ErrorType,
+    /// 1-based line in the validated text of the attribute, or of the nearest enclosing value
+    /// when it is missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// 1-based column (in characters) of the same position
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ErrorType {
     MissingRequired,
     InvalidType,
@@ -26,10 +40,41 @@ pub enum ErrorType {
     UnknownField,
 }
 
+impl ErrorType {
+    /// The variant name, as serialized
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorType::MissingRequired => "MissingRequired",
+            ErrorType::InvalidType => "InvalidType",
+            ErrorType::InvalidValue => "InvalidValue",
+            ErrorType::UnknownField => "UnknownField",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidationWarning {
     pub field: String,
+    /// RFC 6901 JSON pointer to the attribute
+    #[serde(default)]
+    pub pointer: String,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+}
+
+impl ValidationWarning {
+    pub fn new(field: String, message: String) -> Self {
+        Self {
+            pointer: field_pointer(&field),
+            field,
+            message,
+            line: None,
+            column: None,
+        }
+    }
 }
 
 impl ValidationReport {
@@ -49,47 +94,269 @@ impl ValidationReport {
         }
     }
 
+    /// Add an error on a dotted attribute path
     pub fn add_error(&mut self, field: String, message: String, error_type: ErrorType) {
         self.errors.push(ValidationError {
+            pointer: field_pointer(&field),
             field,
             message,
             error_type,
+            line: None,
+            column: None,
         });
         self.is_valid = false;
     }
 
-    #[allow(dead_code)]
+    /// Add an error on a JSON pointer
+    pub fn add_error_at(&mut self, pointer: String, message: String, error_type: ErrorType) {
+        self.add_error(pointer_field(&pointer), message, error_type);
+        if let Some(error) = self.errors.last_mut() {
+            error.pointer = pointer;
+        }
+    }
+
     pub fn add_warning(&mut self, field: String, message: String) {
-        self.warnings.push(ValidationWarning { field, message });
+        self.warnings.push(ValidationWarning::new(field, message));
+    }
+
+    /// Set the line and column of every error and warning from the text that was validated
+    pub fn locate(&mut self, text: &str) {
+        for error in &mut self.errors {
+            (error.line, error.column) = pointer_position(text, &error.pointer).unzip();
+        }
+        for warning in &mut self.warnings {
+            (warning.line, warning.column) = pointer_position(text, &warning.pointer).unzip();
+        }
     }
 }
 
+/// The RFC 6901 JSON pointer of a dotted attribute path (`metadata.version` is
+/// `/metadata/version`)
+pub fn field_pointer(field: &str) -> String {
+    if field.is_empty() {
+        return String::new();
+    }
+    field
+        .split('.')
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+/// The dotted attribute path of a JSON pointer (`/observables/2/value` is `observables.2.value`)
+pub fn pointer_field(pointer: &str) -> String {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// The 1-based line and column in JSON `text` of the value a pointer resolves to, or of its
+/// nearest enclosing value when the pointer leads nowhere
+pub fn pointer_position(text: &str, pointer: &str) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let mut at = skip_whitespace(bytes, 0);
+    if at >= bytes.len() {
+        return None;
+    }
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        match child_start(bytes, at, &segment) {
+            Some(child) => at = child,
+            None => break,
+        }
+    }
+    // Positions sit on structural characters, so they are always char boundaries
+    let before = &text[..at];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Some((
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    ))
+}
+
+/// Where the value of a member or element of the object or array starting at `at` starts
+fn child_start(bytes: &[u8], at: usize, segment: &str) -> Option<usize> {
+    match bytes.get(at)? {
+        b'{' => {
+            let mut found = None;
+            let mut i = skip_whitespace(bytes, at + 1);
+            while bytes.get(i) == Some(&b'"') {
+                let key_end = string_end(bytes, i)?;
+                let key: String = serde_json::from_slice(&bytes[i..key_end]).ok()?;
+                i = skip_whitespace(bytes, key_end);
+                if bytes.get(i) != Some(&b':') {
+                    break;
+                }
+                i = skip_whitespace(bytes, i + 1);
+                // Like parsing, the last of duplicate keys wins
+                if key == segment {
+                    found = Some(i);
+                }
+                i = skip_whitespace(bytes, value_end(bytes, i)?);
+                if bytes.get(i) != Some(&b',') {
+                    break;
+                }
+                i = skip_whitespace(bytes, i + 1);
+            }
+            found
+        }
+        b'[' => {
+            let index: usize = segment.parse().ok()?;
+            let mut i = skip_whitespace(bytes, at + 1);
+            for _ in 0..index {
+                i = skip_whitespace(bytes, value_end(bytes, i)?);
+                if bytes.get(i) != Some(&b',') {
+                    return None;
+                }
+                i = skip_whitespace(bytes, i + 1);
+            }
+            bytes.get(i).filter(|&&b| b != b']').map(|_| i)
+        }
+        _ => None,
+    }
+}
+
+/// The end (exclusive) of the string whose opening quote is at `at`
+fn string_end(bytes: &[u8], at: usize) -> Option<usize> {
+    let mut i = at + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// The end (exclusive) of the value starting at `at`
+fn value_end(bytes: &[u8], at: usize) -> Option<usize> {
+    match bytes.get(at)? {
+        b'"' => string_end(bytes, at),
+        b'{' | b'[' => {
+            let mut depth = 0;
+            let mut i = at;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'"' => {
+                        i = string_end(bytes, i)?;
+                        continue;
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(i + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+            None
+        }
+        _ => Some(
+            bytes[at..]
+                .iter()
+                .position(|b| matches!(b, b',' | b'}' | b']') || b.is_ascii_whitespace())
+                .map_or(bytes.len(), |end| at + end),
+        ),
+    }
+}
+
+fn skip_whitespace(bytes: &[u8], at: usize) -> usize {
+    at + bytes
+        .get(at..)
+        .unwrap_or_default()
+        .iter()
+        .take_while(|b| b.is_ascii_whitespace())
+        .count()
+}
+
+/// Classification attributes every event carries at the top level (from `base_event`)
+pub(crate) const CLASSIFICATION_ATTRIBUTES: &[&str] = &[
+    "class_uid",
+    "category_uid",
+    "type_uid",
+    "activity_id",
+    "severity_id",
+];
+
+/// Non-standard `metadata` attributes written by older releases of this server
+const LEGACY_METADATA_ATTRIBUTES: &[&str] = &["event_class", "class_uid", "category_uid"];
+
 /// Validate an OCSF event against the schema
+#[allow(dead_code)]
 pub fn validate_event(event_json: &str) -> anyhow::Result<ValidationReport> {
     let event: Value = serde_json::from_str(event_json)?;
+    let mut report = check_event(&event);
+    report.locate(event_json);
+    Ok(report)
+}
 
+/// Structural checks shared by every validation; errors carry pointers but no text positions
+fn check_event(event: &Value) -> ValidationReport {
     let mut report = ValidationReport::new(true, None);
 
+    for name in CLASSIFICATION_ATTRIBUTES {
+        match event.get(name) {
+            None => report.add_error(
+                name.to_string(),
+                format!("Missing required '{name}' field"),
+                ErrorType::MissingRequired,
+            ),
+            Some(value) if !value.is_i64() => report.add_error(
+                name.to_string(),
+                format!("'{name}' must be an integer"),
+                ErrorType::InvalidType,
+            ),
+            _ => {}
+        }
+    }
+
+    let int = |name: &str| event.get(name).and_then(|v| v.as_i64());
+    if let (Some(class_uid), Some(activity_id), Some(type_uid)) =
+        (int("class_uid"), int("activity_id"), int("type_uid"))
+        && type_uid != class_uid * 100 + activity_id
+    {
+        report.add_error(
+            "type_uid".to_string(),
+            format!(
+                "type_uid {type_uid} does not match class_uid * 100 + activity_id ({})",
+                class_uid * 100 + activity_id
+            ),
+            ErrorType::InvalidValue,
+        );
+    }
+
     // Check for metadata
     if let Some(metadata) = event.get("metadata") {
-        // Validate metadata fields
-        if let Some(event_class) = metadata.get("event_class").and_then(|v| v.as_str()) {
-            report.event_class = Some(event_class.to_string());
-        } else {
+        if !metadata.get("version").is_some_and(|v| v.is_string()) {
             report.add_error(
-                "metadata.event_class".to_string(),
-                "Missing event_class in metadata".to_string(),
+                "metadata.version".to_string(),
+                "Missing version in metadata".to_string(),
                 ErrorType::MissingRequired,
             );
         }
 
-        if metadata.get("version").is_none() {
+        if !metadata.get("product").is_some_and(|v| v.is_object()) {
             report.add_error(
-                "metadata.version".to_string(),
-                "Missing version in metadata".to_string(),
+                "metadata.product".to_string(),
+                "Missing product in metadata".to_string(),
                 ErrorType::MissingRequired,
             );
         }
+
+        for name in LEGACY_METADATA_ATTRIBUTES {
+            if metadata.get(name).is_some() {
+                report.add_warning(
+                    format!("metadata.{name}"),
+                    format!("'metadata.{name}' is not an OCSF metadata attribute"),
+                );
+            }
+        }
     } else {
         report.add_error(
             "metadata".to_string(),
@@ -107,18 +374,201 @@ pub fn validate_event(event_json: &str) -> anyhow::Result<ValidationReport> {
         );
     }
 
-    // Generate summary
-    if report.is_valid {
-        report.summary = format!(
-            "Valid OCSF event of class '{}'",
-            report
-                .event_class
-                .as_ref()
-                .unwrap_or(&"unknown".to_string())
+    summarize(&mut report);
+    report
+}
+
+/// Validate an OCSF event and run schema-aware checks (class, version, associations)
+pub fn validate_event_with_schema(
+    event_json: &str,
+    schema: &OcsfSchema,
+) -> anyhow::Result<ValidationReport> {
+    let event: Value = serde_json::from_str(event_json)?;
+    let mut report = validate_value_with_schema(&event, schema);
+    report.locate(event_json);
+    Ok(report)
+}
+
+/// `validate_event_with_schema` for an event that has no source text, such as one just built
+pub fn validate_value_with_schema(event: &Value, schema: &OcsfSchema) -> ValidationReport {
+    let mut report = check_event(event);
+
+    if let Some(version) = event.pointer("/metadata/version").and_then(|v| v.as_str())
+        && version != schema.version
+    {
+        report.add_warning(
+            "metadata.version".to_string(),
+            format!(
+                "Event declares version {version} but was validated against {}",
+                schema.version
+            ),
         );
-    } else {
-        report.summary = format!("Validation failed with {} error(s)", report.errors.len());
     }
 
-    Ok(report)
+    match schema.class_for_event(event) {
+        Some(ec) => {
+            report.event_class = Some(ec.name.clone());
+
+            if let Some(category_uid) = event.get("category_uid").and_then(|v| v.as_i64())
+                && category_uid != i64::from(ec.resolved_category_uid())
+            {
+                report.add_error(
+                    "category_uid".to_string(),
+                    format!(
+                        "category_uid {category_uid} does not match class '{}' (category {})",
+                        ec.name,
+                        ec.resolved_category_uid()
+                    ),
+                    ErrorType::InvalidValue,
+                );
+            }
+
+            if let Some(fields) = event.as_object() {
+                check_attributes(schema, &ec.attributes, fields, "", &mut report);
+            }
+
+            for warning in association_warnings(ec, event) {
+                report.add_warning(warning.field, warning.message);
+            }
+        }
+        None => {
+            if let Some(class_uid) = event.get("class_uid").and_then(|v| v.as_i64()) {
+                report.add_warning(
+                    "class_uid".to_string(),
+                    format!(
+                        "class_uid {class_uid} is not defined in schema version {}",
+                        schema.version
+                    ),
+                );
+            }
+        }
+    }
+
+    summarize(&mut report);
+    report
+}
+
+/// Check the JSON type of every defined attribute of an object, descending into nested
+/// objects and array elements so errors point at the exact element
+fn check_attributes(
+    schema: &OcsfSchema,
+    attributes: &HashMap<String, Attribute>,
+    fields: &Map<String, Value>,
+    pointer: &str,
+    report: &mut ValidationReport,
+) {
+    let mut names: Vec<&String> = fields.keys().collect();
+    names.sort();
+    for name in names {
+        let Some(attr) = attributes.get(name) else {
+            continue;
+        };
+        let value = &fields[name];
+        let path = format!("{pointer}{}", field_pointer(name));
+        if value.is_null() || report.errors.iter().any(|e| e.pointer == path) {
+            continue;
+        }
+        if !attr.is_array {
+            check_value(schema, attr, value, &path, report);
+        } else if let Value::Array(items) = value {
+            for (i, item) in items.iter().enumerate() {
+                check_value(schema, attr, item, &format!("{path}/{i}"), report);
+            }
+        } else {
+            report.add_error_at(
+                path,
+                format!("'{name}' must be an array"),
+                ErrorType::InvalidType,
+            );
+        }
+    }
+}
+
+fn check_value(
+    schema: &OcsfSchema,
+    attr: &Attribute,
+    value: &Value,
+    pointer: &str,
+    report: &mut ValidationReport,
+) {
+    if let Some(object_type) = &attr.object_type {
+        match value {
+            // Generic objects (e.g. `unmapped`) have no attribute definitions
+            Value::Object(fields) => {
+                if let Some(object) = schema.objects.get(object_type) {
+                    check_attributes(schema, &object.attributes, fields, pointer, report);
+                }
+            }
+            _ => report.add_error_at(
+                pointer.to_string(),
+                format!(
+                    "'{}' must be a {object_type} object",
+                    pointer_field(pointer)
+                ),
+                ErrorType::InvalidType,
+            ),
+        }
+        return;
+    }
+
+    let data_type = attr.data_type.as_deref().unwrap_or("string_t");
+    let matches = match schema.base_type(data_type) {
+        "json_t" => true,
+        "integer_t" | "long_t" => value.is_i64() || value.is_u64(),
+        "float_t" => value.is_number(),
+        "boolean_t" => value.is_boolean(),
+        _ => value.is_string(),
+    };
+    if !matches {
+        report.add_error_at(
+            pointer.to_string(),
+            format!(
+                "'{}' must be {data_type}, got {value}",
+                pointer_field(pointer)
+            ),
+            ErrorType::InvalidType,
+        );
+    }
+}
+
+fn summarize(report: &mut ValidationReport) {
+    report.summary = match (&report.event_class, report.is_valid) {
+        (Some(event_class), true) => format!("Valid OCSF event of class '{event_class}'"),
+        (None, true) => "Valid OCSF event".to_string(),
+        (_, false) => format!("Validation failed with {} error(s)", report.errors.len()),
+    };
+}
+
+/// Warn when one side of a class association is populated but the other is missing
+pub fn association_warnings(event_class: &EventClass, event: &Value) -> Vec<ValidationWarning> {
+    let mut sources: Vec<&String> = event_class.associations.keys().collect();
+    sources.sort();
+
+    let mut warnings = Vec::new();
+    for source in sources {
+        if !is_populated(event, source) {
+            continue;
+        }
+        for target in &event_class.associations[source] {
+            if !is_populated(event, target) {
+                warnings.push(ValidationWarning::new(
+                    target.clone(),
+                    format!(
+                        "'{source}' is populated but its associated attribute '{target}' is missing \
+                         (both describe the same entity in class '{}')",
+                        event_class.name
+                    ),
+                ));
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Check whether a dotted attribute path (e.g. `actor.user`) holds a non-null value
+fn is_populated(event: &Value, path: &str) -> bool {
+    path.split('.')
+        .try_fold(event, |value, segment| value.get(segment))
+        .is_some_and(|value| !value.is_null())
 }
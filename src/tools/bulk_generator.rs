@@ -0,0 +1,155 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::BufWriter;
+
+use crate::ocsf::bulk::{BulkGenerator, BulkOptions, BulkSummary};
+use crate::ocsf::OcsfSchema;
+use crate::tools::schema_export::parse_list;
+
+/// Events returned inline when no output file is given
+const DEFAULT_SAMPLE_SIZE: usize = 10;
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct GenerateEventsBulkRequest {
+    #[schemars(description = "OCSF schema version (defaults to 1.7.0-dev)")]
+    pub version: Option<String>,
+    #[schemars(description = "Event classes: comma-separated names or JSON array")]
+    pub event_classes: String,
+    #[schemars(description = "Number of events to generate")]
+    pub count: usize,
+    #[schemars(
+        description = "Random seed; identical seeds produce identical events (defaults to 0)"
+    )]
+    pub seed: Option<u64>,
+    #[schemars(
+        description = "Window start as RFC 3339 or epoch milliseconds (defaults to 2025-01-01T00:00:00Z)"
+    )]
+    pub start_time: Option<String>,
+    #[schemars(
+        description = "Window end as RFC 3339 or epoch milliseconds (defaults to start + 24h)"
+    )]
+    pub end_time: Option<String>,
+    #[schemars(
+        description = "Relative class weights: comma-separated numbers or JSON array, same order as event_classes"
+    )]
+    pub weights: Option<String>,
+    #[schemars(
+        description = "Profiles whose attributes are populated: comma-separated names or JSON array"
+    )]
+    pub profiles: Option<String>,
+    #[schemars(description = "Write NDJSON to this local path instead of returning events inline")]
+    pub output_path: Option<String>,
+    #[schemars(description = "Number of events to return inline (defaults to 10)")]
+    pub sample_size: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkGenerationResponse {
+    #[serde(flatten)]
+    pub summary: BulkSummary,
+    pub output_path: Option<String>,
+    pub sample: Vec<Value>,
+}
+
+/// Generate a reproducible batch of synthetic OCSF events
+pub async fn generate_ocsf_events_bulk(request: GenerateEventsBulkRequest) -> Result<String> {
+    let version = request.version.as_deref().unwrap_or("1.7.0-dev");
+
+    tracing::info!(
+        "generate_ocsf_events_bulk called: version={}, classes={}, count={}, seed={:?}",
+        version,
+        request.event_classes,
+        request.count,
+        request.seed
+    );
+
+    let schema = OcsfSchema::load_version(version)
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+    let mut options = BulkOptions {
+        classes: parse_list(Some(&request.event_classes), "event_classes")?,
+        weights: parse_list(request.weights.as_deref(), "weights")?
+            .iter()
+            .map(|w| {
+                w.parse::<f64>()
+                    .map_err(|_| anyhow::anyhow!("Invalid weight '{}'", w))
+            })
+            .collect::<Result<_>>()?,
+        count: request.count,
+        seed: request.seed.unwrap_or(0),
+        profiles: parse_list(request.profiles.as_deref(), "profiles")?,
+        ..Default::default()
+    };
+    if let Some(start) = &request.start_time {
+        options.start_ms = parse_time_ms(start)?;
+        options.end_ms = options.start_ms + crate::ocsf::bulk::DEFAULT_WINDOW_MS;
+    }
+    if let Some(end) = &request.end_time {
+        options.end_ms = parse_time_ms(end)?;
+    }
+
+    let sample_size = request.sample_size.unwrap_or(DEFAULT_SAMPLE_SIZE);
+
+    let response = match request.output_path {
+        Some(path) => {
+            let output = path.clone();
+            let (summary, sample) = tokio::task::spawn_blocking(move || -> Result<_> {
+                let mut generator = BulkGenerator::new(&schema, options)?;
+                let file = std::fs::File::create(&output)
+                    .map_err(|e| anyhow::anyhow!("Cannot create '{}': {}", output, e))?;
+                let (summary, sample) =
+                    generator.write_ndjson_sampled(&mut BufWriter::new(file), sample_size)?;
+                let sample = sample
+                    .into_iter()
+                    .map(serde_json::to_value)
+                    .collect::<serde_json::Result<Vec<_>>>()?;
+                Ok((summary, sample))
+            })
+            .await??;
+
+            BulkGenerationResponse {
+                summary,
+                output_path: Some(path),
+                sample,
+            }
+        }
+        None => {
+            // Every event is generated, so the totals cover `count`; only the sample is kept
+            let (summary, sample) = tokio::task::spawn_blocking(move || -> Result<_> {
+                let mut summary = BulkSummary {
+                    seed: options.seed,
+                    ..Default::default()
+                };
+                let mut sample = Vec::new();
+                for event in BulkGenerator::new(&schema, options)? {
+                    let event = event?;
+                    summary.record(&schema, &event);
+                    if sample.len() < sample_size {
+                        sample.push(serde_json::to_value(event)?);
+                    }
+                }
+                Ok((summary, sample))
+            })
+            .await??;
+
+            BulkGenerationResponse {
+                summary,
+                output_path: None,
+                sample,
+            }
+        }
+    };
+
+    serde_json::to_string_pretty(&response).map_err(|e| anyhow::anyhow!(e.to_string()))
+}
+
+pub(crate) fn parse_time_ms(input: &str) -> Result<i64> {
+    if let Ok(ms) = input.trim().parse::<i64>() {
+        return Ok(ms);
+    }
+    chrono::DateTime::parse_from_rfc3339(input.trim())
+        .map(|dt| dt.timestamp_millis())
+        .map_err(|e| anyhow::anyhow!("Invalid time '{}': {}", input, e))
+}
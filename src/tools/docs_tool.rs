@@ -38,9 +38,11 @@ OCSF organizes events into classes grouped by categories:
 
 ### Event Structure
 Every OCSF event has:
-- `metadata`: Version, product info, timestamps
 - `class_uid`: Unique ID for the event class
 - `category_uid`: Category the event belongs to
+- `activity_id` and `type_uid`: The activity, with `type_uid = class_uid * 100 + activity_id`
+- `severity_id`: Event severity
+- `metadata`: Schema version, product info, event uid
 - Event-specific attributes
 
 ## Quick Start
@@ -110,16 +112,18 @@ Determine what kind of activity your log represents:
 
 ### 2. Map Required Fields
 Every OCSF event requires:
-- `time`: Event timestamp (ISO 8601 format)
+- `time`: Event timestamp (epoch milliseconds)
 - `class_uid`: Event class ID
 - `category_uid`: Category ID
+- `activity_id`: Activity within the class
+- `type_uid`: `class_uid * 100 + activity_id`
+- `severity_id`: Event severity (1=Info, 2=Low, 3=Medium, 4=High, 5=Critical)
 - `metadata`: Version and product info
 - Event-specific required fields
 
 ### 3. Map Optional Fields
 Add relevant optional fields:
-- `severity_id`: Event severity (1=Info, 2=Low, 3=Medium, 4=High, 5=Critical)
-- `status`: Operation status
+- `status_id` / `status`: Operation status
 - `unmapped`: Store fields that don't map to OCSF
 
 ### 4. Use Nested Objects
@@ -146,14 +150,18 @@ OCSF mapping:
 {
   "class_uid": 3002,
   "category_uid": 3,
-  "time": "2024-01-15T10:30:00Z",
+  "activity_id": 1,
+  "type_uid": 300201,
+  "severity_id": 1,
+  "time": 1705314600000,
   "metadata": {
-    "version": "1.7.0-dev",
+    "version": "1.4.0",
     "product": {
-      "name": "My Auth System"
+      "name": "My Auth System",
+      "vendor_name": "Example Corp"
     }
   },
-  "activity_id": 1,
+  "status_id": 1,
   "user": {
     "name": "john.doe"
   },
@@ -180,7 +188,7 @@ OCSF mapping:
 
 ## Event Generation
 - Always include required fields (use `browse_ocsf_schema` to check)
-- Use proper timestamp format (ISO 8601)
+- Use epoch milliseconds for `timestamp_t` attributes such as `time`
 - Set appropriate severity levels
 - Include `unmapped` object for fields that don't fit
 
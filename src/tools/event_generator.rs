@@ -1,9 +1,11 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
 
-use crate::ocsf::{OcsfEvent, OcsfSchema};
+use crate::ocsf::builder::OcsfEventBuilder;
+use crate::ocsf::synthetic::{EventSynthesizer, SyntheticOptions};
+use crate::ocsf::validation::ValidationReport;
+use crate::ocsf::{EventClass, OcsfEvent, OcsfSchema};
 
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct GenerateEventRequest {
@@ -12,6 +14,16 @@ pub struct GenerateEventRequest {
     pub event_class: String,
     pub required_fields: String,
     pub optional_fields: Option<String>,
+    #[schemars(
+        description = "Return {event, validation} with the schema validation report instead of the bare event (defaults to false)"
+    )]
+    pub include_validation: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeneratedEvent {
+    pub event: OcsfEvent,
+    pub validation: ValidationReport,
 }
 
 /// Generate a valid OCSF event JSON from parameters
@@ -34,75 +46,91 @@ pub async fn generate_ocsf_event(request: GenerateEventRequest) -> Result<String
             anyhow::anyhow!(format!("Event class '{}' not found", request.event_class))
         })?;
 
-    // Parse required_fields - can be JSON object or comma-separated field names
-    let req_fields: HashMap<String, Value> = if request.required_fields.trim().starts_with('{') {
-        // JSON object format
-        serde_json::from_str(&request.required_fields)
-            .map_err(|e| anyhow::anyhow!("Invalid JSON in required_fields: {}", e))?
+    let synthesizer = EventSynthesizer::new(&schema, SyntheticOptions::default());
+    let mut rng = rand::rng();
+
+    // Classes from the minimal embedded schema carry no attribute definitions to synthesize from
+    let mut synthesized = if ec.attributes.is_empty() {
+        None
     } else {
-        // Comma-separated field names - generate default values
-        let field_names: Vec<&str> = request.required_fields.split(',').map(|s| s.trim()).collect();
-        let mut fields = HashMap::new();
-        for field_name in field_names {
-            if !field_name.is_empty() {
-                let default_value = match field_name {
-                    "activity_id" => Value::Number(serde_json::Number::from(1)),
-                    "category_uid" => Value::Number(serde_json::Number::from(ec.uid / 1000)),
-                    "class_uid" => Value::Number(serde_json::Number::from(ec.uid)),
-                    "severity_id" => Value::Number(serde_json::Number::from(1)),
-                    "type_uid" => Value::Number(serde_json::Number::from(ec.uid * 100 + 1)),
-                    "time" => Value::String(chrono::Utc::now().to_rfc3339()),
-                    _ => Value::String(format!("default_{}", field_name)),
-                };
-                fields.insert(field_name.to_string(), default_value);
-            }
-        }
-        fields
+        Some(synthesizer.generate(&request.event_class, &mut rng)?)
     };
 
-    let opt_fields: HashMap<String, Value> = if let Some(opt) = request.optional_fields {
-        if opt.trim().starts_with('{') {
-            // JSON object format
-            serde_json::from_str(&opt)
-                .map_err(|e| anyhow::anyhow!("Invalid JSON in optional_fields: {}", e))?
+    let mut explicit = serde_json::Map::new();
+    for (param, fields) in [
+        ("required_fields", Some(request.required_fields.as_str())),
+        ("optional_fields", request.optional_fields.as_deref()),
+    ] {
+        let Some(fields) = fields else {
+            continue;
+        };
+
+        if fields.trim().starts_with('{') {
+            // JSON object format - explicit values win over synthesized ones
+            let values: serde_json::Map<String, Value> = serde_json::from_str(fields)
+                .map_err(|e| anyhow::anyhow!("Invalid JSON in {}: {}", param, e))?;
+            explicit.extend(values);
         } else {
-            // Comma-separated field names - generate default values
-            let field_names: Vec<&str> = opt.split(',').map(|s| s.trim()).collect();
-            let mut fields = HashMap::new();
-            for field_name in field_names {
-                if !field_name.is_empty() {
-                    let default_value = match field_name {
-                        "message" => Value::String("Generated OCSF event".to_string()),
-                        "user" => serde_json::json!({"name": "example_user", "uid": "1001"}),
-                        _ => Value::String(format!("default_{}", field_name)),
-                    };
-                    fields.insert(field_name.to_string(), default_value);
+            // Comma-separated field names - synthesize type-correct values
+            for field_name in fields
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+            {
+                match synthesized.as_mut() {
+                    Some(event) => synthesizer.generate_attribute(
+                        event,
+                        &request.event_class,
+                        field_name,
+                        &mut rng,
+                    )?,
+                    None => {
+                        explicit.insert(field_name.to_string(), fallback_value(field_name, ec));
+                    }
                 }
             }
-            fields
         }
-    } else {
-        HashMap::new()
-    };
-
-    // Derive category_uid from class_uid (OCSF category is first digit of class UID)
-    let category_uid = ec.uid / 1000;
-
-    let mut event = OcsfEvent::new(&request.event_class, ec.uid, category_uid);
-
-    for (key, value) in req_fields {
-        event.set_field(key, value);
-    }
-    for (key, value) in opt_fields {
-        event.set_field(key, value);
     }
 
-    if !event.fields.contains_key("time") {
-        event.set_field(
+    let has_time = synthesized
+        .as_ref()
+        .is_some_and(|e| e.fields.contains_key("time"));
+    if !has_time && !explicit.contains_key("time") {
+        explicit.insert(
             "time".to_string(),
-            Value::String(chrono::Utc::now().to_rfc3339()),
+            Value::from(chrono::Utc::now().timestamp_millis()),
         );
     }
 
+    // The builder checks and coerces explicit values and derives siblings and type_uid
+    let builder = match synthesized {
+        Some(event) => OcsfEventBuilder::from_event(&schema, event)?,
+        None => OcsfEventBuilder::new(&schema, &request.event_class)?,
+    };
+    let (event, report) = builder.set_fields(explicit)?.build()?;
+
+    if request.include_validation.unwrap_or(false) {
+        let response = GeneratedEvent {
+            event,
+            validation: report,
+        };
+        return serde_json::to_string_pretty(&response).map_err(|e| anyhow::anyhow!(e.to_string()));
+    }
+
     event.to_json().map_err(|e| anyhow::anyhow!(e.to_string()))
 }
+
+/// Placeholder values for classes without attribute definitions (minimal embedded schema)
+fn fallback_value(field_name: &str, ec: &EventClass) -> Value {
+    match field_name {
+        "activity_id" => Value::from(1),
+        "category_uid" => Value::from(ec.resolved_category_uid()),
+        "class_uid" => Value::from(ec.uid),
+        "severity_id" => Value::from(1),
+        "type_uid" => Value::from(ec.uid * 100 + 1),
+        "time" => Value::from(chrono::Utc::now().timestamp_millis()),
+        "message" => Value::String("Generated OCSF event".to_string()),
+        "user" => serde_json::json!({"name": "example_user", "uid": "1001"}),
+        _ => Value::String(format!("default_{}", field_name)),
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::ocsf::conversion::open_log;
+use crate::ocsf::file_validation::{
+    check_events, validate_file, FileValidationOptions, FileValidationReport, DEFAULT_MAX_EXAMPLES,
+};
+use crate::ocsf::report_formats::{render, ReportFormat};
+use crate::ocsf::OcsfSchema;
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ValidateFileRequest {
+    #[schemars(description = "OCSF schema version (defaults to 1.7.0-dev)")]
+    pub version: Option<String>,
+    #[schemars(
+        description = "Local file of OCSF events: NDJSON or a JSON array; gzip and zstd files are decompressed"
+    )]
+    pub input_path: String,
+    #[schemars(description = "Failing examples listed per rule (defaults to 3)")]
+    pub max_examples: Option<usize>,
+    #[schemars(
+        description = "Output format: 'json' (default, the aggregated report), or per event 'sarif' (SARIF 2.1.0 with line and JSON pointer locations), 'junit' (JUnit XML, one test case per event) or 'json_lines'"
+    )]
+    pub format: Option<ReportFormat>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidateFileResponse {
+    pub version: String,
+    pub input_path: String,
+    #[serde(flatten)]
+    pub report: FileValidationReport,
+}
+
+/// Validate every event of a local NDJSON or JSON array file, aggregating the errors
+pub async fn validate_ocsf_file(request: ValidateFileRequest) -> Result<String> {
+    let version = request.version.as_deref().unwrap_or("1.7.0-dev");
+
+    tracing::info!(
+        "validate_ocsf_file called: version={}, input={}",
+        version,
+        request.input_path
+    );
+
+    let schema = OcsfSchema::load_version(version)
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    let options = FileValidationOptions {
+        max_examples: request.max_examples.unwrap_or(DEFAULT_MAX_EXAMPLES),
+    };
+    let input = PathBuf::from(&request.input_path);
+    let format = request.format.unwrap_or_default();
+
+    if format != ReportFormat::Json {
+        return tokio::task::spawn_blocking(move || {
+            let (reader, _) = open_log(&input)?;
+            let mut results = Vec::new();
+            check_events(&schema, reader, |event| results.push(event))?;
+            render(format, Some(&request.input_path), &results)
+        })
+        .await?;
+    }
+
+    let response = tokio::task::spawn_blocking(move || -> Result<_> {
+        Ok(ValidateFileResponse {
+            version: schema.version.clone(),
+            input_path: request.input_path,
+            report: validate_file(&schema, &input, &options)?,
+        })
+    })
+    .await??;
+
+    serde_json::to_string_pretty(&response).map_err(|e| anyhow::anyhow!(e.to_string()))
+}
@@ -0,0 +1,31 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ocsf::repair::repair_event;
+use crate::ocsf::OcsfSchema;
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FixEventRequest {
+    #[schemars(description = "OCSF schema version (defaults to 1.7.0-dev)")]
+    pub version: Option<String>,
+    pub event_json: String,
+}
+
+/// Repair the mechanical validation failures of an OCSF event
+pub async fn fix_ocsf_event(request: FixEventRequest) -> Result<String> {
+    let version = request.version.as_deref().unwrap_or("1.7.0-dev");
+
+    tracing::info!("fix_ocsf_event called: version={}", version);
+
+    let schema = OcsfSchema::load_version(version)
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+    let event: Value = serde_json::from_str(&request.event_json)
+        .map_err(|e| anyhow::anyhow!("Invalid event JSON: {}", e))?;
+
+    let repair = repair_event(event, &schema)?;
+
+    serde_json::to_string_pretty(&repair).map_err(|e| anyhow::anyhow!(e.to_string()))
+}
@@ -0,0 +1,65 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ocsf::{validation, OcsfSchema};
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct LintEventRequest {
+    #[schemars(description = "OCSF schema version (defaults to 1.7.0-dev)")]
+    pub version: Option<String>,
+    pub event_json: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LintReport {
+    pub event_class: Option<String>,
+    pub findings: Vec<LintFinding>,
+    pub summary: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LintFinding {
+    pub rule: String,
+    pub field: String,
+    pub message: String,
+}
+
+/// Lint an OCSF event for consistency issues that are not hard validation errors
+pub async fn lint_ocsf_event(request: LintEventRequest) -> Result<String> {
+    let version = request.version.as_deref().unwrap_or("1.7.0-dev");
+
+    tracing::info!("lint_ocsf_event called: version={}", version);
+
+    let schema = OcsfSchema::load_version(version)
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+    let event: Value = serde_json::from_str(&request.event_json)
+        .map_err(|e| anyhow::anyhow!("Invalid event JSON: {}", e))?;
+
+    let ec = schema
+        .class_for_event(&event)
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the event class from class_uid"))?;
+
+    let findings: Vec<LintFinding> = validation::association_warnings(ec, &event)
+        .into_iter()
+        .map(|w| LintFinding {
+            rule: "association".to_string(),
+            field: w.field,
+            message: w.message,
+        })
+        .collect();
+
+    let report = LintReport {
+        event_class: Some(ec.name.clone()),
+        summary: if findings.is_empty() {
+            format!("No lint findings for class '{}'", ec.name)
+        } else {
+            format!("{} lint finding(s) for class '{}'", findings.len(), ec.name)
+        },
+        findings,
+    };
+
+    serde_json::to_string_pretty(&report).map_err(|e| anyhow::anyhow!(e.to_string()))
+}
@@ -0,0 +1,108 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::ocsf::conversion::{
+    convert_file, ConversionSummary, ConvertOptions, DEFAULT_MAX_ERRORS,
+};
+use crate::ocsf::mapping::UnmappedOptions;
+use crate::ocsf::parsers::LogFormat;
+use crate::ocsf::OcsfSchema;
+
+use super::mapping_applier::{builtin_names, resolve_mapping};
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ConvertLogFileRequest {
+    #[schemars(
+        description = "OCSF schema version (defaults to the mapping's version, then 1.7.0-dev)"
+    )]
+    pub version: Option<String>,
+    #[schemars(description = "Mapping definition as YAML or JSON")]
+    pub mapping: Option<String>,
+    #[schemars(
+        description = "Bundled mapping pack to use instead of 'mapping': 'sshd', 'sudo', 'auditd', 'http_access', 'zeek', 'suricata', 'cloudtrail', 'azure_activity', 'gcp_audit', 'k8s_audit' or 'windows_security'"
+    )]
+    pub pack: Option<String>,
+    #[schemars(
+        description = "Local log file in the mapping's format (NDJSON or JSON documents when it sets none); gzip and zstd files are decompressed"
+    )]
+    pub input_path: String,
+    #[schemars(
+        description = "Local path the OCSF events are written to as NDJSON; compressed when it ends in .gz or .zst"
+    )]
+    pub output_path: String,
+    #[schemars(
+        description = "Leave events that fail validation out of the output; they are still counted (defaults to false)"
+    )]
+    pub skip_invalid: Option<bool>,
+    #[schemars(
+        description = "What becomes of source fields no rule maps, overriding the mapping's 'unmapped': { keep: all | none | allowlist, allow: [dotted paths], raw_data: true to also keep the source text }"
+    )]
+    pub unmapped: Option<UnmappedOptions>,
+    #[schemars(description = "Record errors listed in the response (defaults to 20)")]
+    pub max_errors: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConvertLogFileResponse {
+    pub version: String,
+    pub mapping: Option<String>,
+    pub format: String,
+    pub input_path: String,
+    pub output_path: String,
+    #[serde(flatten)]
+    pub summary: ConversionSummary,
+}
+
+/// Stream a local log file through a mapping into an OCSF NDJSON file
+pub async fn convert_log_file(request: ConvertLogFileRequest) -> Result<String> {
+    let mut definition = resolve_mapping(request.mapping.as_deref(), request.pack.as_deref())?
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Set 'mapping' or 'pack'. Bundled packs: {}",
+                builtin_names().join(", ")
+            )
+        })?;
+    if let Some(unmapped) = request.unmapped {
+        definition.unmapped = Some(unmapped);
+    }
+    let version = request
+        .version
+        .as_deref()
+        .or(definition.version.as_deref())
+        .unwrap_or("1.7.0-dev");
+
+    tracing::info!(
+        "convert_log_file called: version={}, mapping={:?}, input={}, output={}",
+        version,
+        definition.name,
+        request.input_path,
+        request.output_path
+    );
+
+    let schema = OcsfSchema::load_version(version)
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    let options = ConvertOptions {
+        skip_invalid: request.skip_invalid.unwrap_or(false),
+        max_errors: request.max_errors.unwrap_or(DEFAULT_MAX_ERRORS),
+    };
+    let input = PathBuf::from(&request.input_path);
+    let output = PathBuf::from(&request.output_path);
+    let format = definition.format.unwrap_or(LogFormat::Json);
+
+    let response = tokio::task::spawn_blocking(move || -> Result<_> {
+        let summary = convert_file(&schema, &definition, &input, &output, &options)?;
+        Ok(ConvertLogFileResponse {
+            version: schema.version.clone(),
+            mapping: definition.name.clone(),
+            format: format.label().to_string(),
+            input_path: request.input_path,
+            output_path: request.output_path,
+            summary,
+        })
+    })
+    .await??;
+
+    serde_json::to_string_pretty(&response).map_err(|e| anyhow::anyhow!(e.to_string()))
+}
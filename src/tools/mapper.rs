@@ -1,25 +1,63 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+use super::mapping_applier::{mapping_records, MappedRecord};
+use crate::ocsf::inference::{sample_from_record, ClassScore, FieldInference, FieldInferrer};
+use crate::ocsf::mapping::MappingEngine;
+use crate::ocsf::parsers::{parse_input, LogFormat};
+use crate::ocsf::OcsfSchema;
+
+/// Classes listed in the ranking returned by `map_custom_to_ocsf`
+const CLASS_RANKING_SIZE: usize = 5;
+
+/// Sample records converted with the generated mapping by default
+const DEFAULT_PREVIEW_SIZE: usize = 3;
+
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct MapCustomRequest {
+    #[schemars(
+        description = "Sample log entries: JSON, syslog (RFC 3164/5424), CEF, LEEF, key=value, CSV with a header row, or free text"
+    )]
     pub sample_log: String,
+    #[schemars(
+        description = "Format of sample_log: json, syslog, cef, leef, key_value, csv, auditd, zeek, windows_xml or text (detected by default)"
+    )]
+    pub sample_format: Option<LogFormat>,
+    #[schemars(description = "Event class to map onto (defaults to the best-ranked class)")]
     pub suggested_class: Option<String>,
+    #[schemars(description = "OCSF schema version (defaults to 1.7.0-dev)")]
+    pub version: Option<String>,
+    #[schemars(
+        description = "Also return a ready-to-run mapping definition and a conversion preview (defaults to false)"
+    )]
+    pub emit_mapping: Option<bool>,
+    #[schemars(description = "Mapping output format: 'yaml' (default) or 'json'")]
+    pub mapping_format: Option<String>,
+    #[schemars(
+        description = "Number of sample records (lines, or JSON array items) converted in the preview (defaults to 3)"
+    )]
+    pub preview_count: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MappingRecommendation {
+    pub version: String,
+    pub sample_format: LogFormat,
+    /// What the parser learned about the first record, e.g. the syslog RFC or CEF version
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    pub sample_metadata: serde_json::Map<String, serde_json::Value>,
     pub suggested_event_class: String,
     pub confidence: String,
-    pub field_mappings: Vec<FieldMapping>,
+    pub class_ranking: Vec<ClassScore>,
+    pub field_mappings: Vec<FieldInference>,
+    pub unmapped_fields: Vec<String>,
     pub explanation: String,
-}
-
-#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
-pub struct FieldMapping {
-    pub source_field: String,
-    pub ocsf_field: String,
-    pub transformation: Option<String>,
+    /// Mapping definition for `apply_ocsf_mapping`, when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<String>,
+    /// The first sample records converted with `mapping`, with their validation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<Vec<MappedRecord>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
@@ -30,60 +68,111 @@ pub struct ListExamplesRequest {
 
 /// Map custom log format to OCSF event class
 pub async fn map_custom_to_ocsf(request: MapCustomRequest) -> Result<String> {
-    tracing::info!("map_custom_to_ocsf called");
+    let version = request.version.as_deref().unwrap_or("1.7.0-dev");
+    tracing::info!("map_custom_to_ocsf called: version={}", version);
+
+    let schema = OcsfSchema::load_version(version)
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+    // Fields are inferred from the first record of a multi-line sample
+    let first_record = parse_input(&request.sample_log, request.sample_format)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("sample_log contains no records"))?;
+    let sample = sample_from_record(&first_record);
+    let inferrer = FieldInferrer::new(&schema);
+    let ranking = inferrer.rank_classes(&sample);
 
-    let event_class = if let Some(suggested) = request.suggested_class {
-        suggested
+    let event_class = match &request.suggested_class {
+        Some(name) => schema
+            .get_event_class(name)
+            .ok_or_else(|| anyhow::anyhow!("Event class '{}' not found", name))?,
+        None => ranking
+            .first()
+            .and_then(|top| schema.get_event_class(&top.name))
+            .ok_or_else(|| anyhow::anyhow!("No event classes in schema version {}", version))?,
+    };
+
+    let field_mappings = inferrer.infer_fields(event_class, &sample);
+    let unmapped_fields: Vec<String> = field_mappings
+        .iter()
+        .filter(|m| m.ocsf_field.is_none())
+        .map(|m| m.source_field.clone())
+        .collect();
+
+    let class_score = ranking
+        .iter()
+        .find(|c| c.name == event_class.name)
+        .map_or(0.0, |c| c.score);
+    let confidence = if class_score >= 0.6 {
+        "high"
+    } else if class_score >= 0.4 {
+        "medium"
     } else {
-        // Heuristic-based detection
-        if request.sample_log.to_lowercase().contains("login")
-            || request.sample_log.to_lowercase().contains("auth")
-        {
-            "authentication".to_string()
-        } else if request.sample_log.to_lowercase().contains("process")
-            || request.sample_log.to_lowercase().contains("exec")
-        {
-            "process_activity".to_string()
-        } else if request.sample_log.to_lowercase().contains("network")
-            || request.sample_log.to_lowercase().contains("connection")
-        {
-            "network_activity".to_string()
-        } else if request.sample_log.to_lowercase().contains("file")
-            || request.sample_log.to_lowercase().contains("path")
-        {
-            "file_activity".to_string()
-        } else {
-            "unknown".to_string()
-        }
+        "low"
     };
 
-    let mappings = vec![
-        FieldMapping {
-            source_field: "timestamp".to_string(),
-            ocsf_field: "time".to_string(),
-            transformation: Some("Convert to ISO 8601 format".to_string()),
-        },
-        FieldMapping {
-            source_field: "username".to_string(),
-            ocsf_field: "user.name".to_string(),
-            transformation: None,
-        },
-        FieldMapping {
-            source_field: "user_id".to_string(),
-            ocsf_field: "user.uid".to_string(),
-            transformation: None,
-        },
-    ];
+    let mapped = field_mappings.len() - unmapped_fields.len();
+    let explanation = format!(
+        "Parsed {} field(s) from the {} sample; {} map onto '{}' (class score {:.2}). \
+         Unmapped fields can be kept under `unmapped`. Review low-confidence mappings and alternatives.",
+        field_mappings.len(),
+        sample.format.label(),
+        mapped,
+        event_class.name,
+        class_score
+    );
+
+    let (mapping, preview) = if request.emit_mapping.unwrap_or(false) {
+        let definition = inferrer.build_mapping(event_class, &sample, &field_mappings);
+        let mapping = match request.mapping_format.as_deref().unwrap_or("yaml") {
+            "yaml" => definition.to_yaml()?,
+            "json" => serde_json::to_string_pretty(&definition)?,
+            other => anyhow::bail!("Unknown mapping_format '{}'. Use 'yaml' or 'json'", other),
+        };
+
+        let engine = MappingEngine::new(&schema, &definition)?;
+        let preview = mapping_records(&definition, &request.sample_log)?
+            .iter()
+            .take(request.preview_count.unwrap_or(DEFAULT_PREVIEW_SIZE))
+            .enumerate()
+            .map(|(index, source)| {
+                match engine.apply_source(&source.record, source.raw.as_deref()) {
+                    Ok(mapped) => MappedRecord {
+                        index,
+                        event: Some(mapped.event),
+                        validation: Some(mapped.validation),
+                        unmapped_count: Some(mapped.unmapped_count),
+                        error: None,
+                    },
+                    Err(e) => MappedRecord {
+                        index,
+                        event: None,
+                        validation: None,
+                        unmapped_count: None,
+                        error: Some(e.to_string()),
+                    },
+                }
+            })
+            .collect();
+        (Some(mapping), Some(preview))
+    } else {
+        (None, None)
+    };
 
     let recommendation = MappingRecommendation {
-        suggested_event_class: event_class.clone(),
-        confidence: "medium".to_string(),
-        field_mappings: mappings,
-        explanation: format!(
-            "Based on log content analysis, suggested OCSF event class is '{event_class}'. \
-             Map your fields to OCSF attributes for standardization. \
-             Confidence is medium - please review and adjust as needed."
-        ),
+        version: schema.version.clone(),
+        sample_format: sample.format,
+        sample_metadata: first_record.metadata,
+        suggested_event_class: event_class.name.clone(),
+        confidence: confidence.to_string(),
+        class_ranking: ranking.into_iter().take(CLASS_RANKING_SIZE).collect(),
+        field_mappings,
+        unmapped_fields,
+        explanation,
+        mapping,
+        preview,
     };
 
     serde_json::to_string_pretty(&recommendation).map_err(|e| anyhow::anyhow!(e.to_string()))
@@ -0,0 +1,219 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ocsf::mapping::{lookup, MappingDefinition, MappingEngine, UnmappedOptions};
+use crate::ocsf::parsers::{parse_input, LogFormat};
+use crate::ocsf::validation::ValidationReport;
+use crate::ocsf::{OcsfEvent, OcsfSchema};
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ApplyMappingRequest {
+    #[schemars(
+        description = "OCSF schema version (defaults to the mapping's version, then 1.7.0-dev)"
+    )]
+    pub version: Option<String>,
+    #[schemars(description = "Mapping definition as YAML or JSON")]
+    pub mapping: Option<String>,
+    #[schemars(
+        description = "Bundled mapping pack to use instead of 'mapping': 'sshd', 'sudo', 'auditd', 'http_access', 'zeek', 'suricata', 'cloudtrail', 'azure_activity', 'gcp_audit', 'k8s_audit' or 'windows_security'"
+    )]
+    pub pack: Option<String>,
+    #[schemars(
+        description = "Records to map: a JSON object, a JSON array, or NDJSON lines (unwrapped from the mapping's records_path); or raw log lines (CSV with a header row) when the mapping sets a syslog, cef, leef, key_value, csv, auditd, zeek, windows_xml or text format"
+    )]
+    pub records: String,
+    #[schemars(description = "Include each event's validation report (defaults to false)")]
+    pub include_validation: Option<bool>,
+    #[schemars(
+        description = "What becomes of source fields no rule maps, overriding the mapping's 'unmapped': { keep: all | none | allowlist, allow: [dotted paths], raw_data: true to also keep the source text } (defaults to keeping all under 'unmapped')"
+    )]
+    pub unmapped: Option<UnmappedOptions>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MappedRecord {
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<OcsfEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation: Option<ValidationReport>,
+    /// Source fields no rule mapped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unmapped_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApplyMappingResponse {
+    pub version: String,
+    pub mapping: Option<String>,
+    pub record_count: usize,
+    pub mapped_count: usize,
+    pub invalid_count: usize,
+    pub failed_count: usize,
+    /// Records left out because no class rule matched a `skip_unmatched` mapping
+    pub skipped_count: usize,
+    /// Source fields no rule mapped, over all mapped records
+    pub unmapped_field_count: usize,
+    pub records: Vec<MappedRecord>,
+}
+
+/// Apply a declarative mapping to raw records, producing OCSF events
+pub async fn apply_ocsf_mapping(request: ApplyMappingRequest) -> Result<String> {
+    let mut definition = resolve_mapping(request.mapping.as_deref(), request.pack.as_deref())?
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Set 'mapping' or 'pack'. Bundled packs: {}",
+                builtin_names().join(", ")
+            )
+        })?;
+    if let Some(unmapped) = request.unmapped {
+        definition.unmapped = Some(unmapped);
+    }
+    let version = request
+        .version
+        .as_deref()
+        .or(definition.version.as_deref())
+        .unwrap_or("1.7.0-dev");
+
+    tracing::info!(
+        "apply_ocsf_mapping called: version={}, mapping={:?}",
+        version,
+        definition.name
+    );
+
+    let schema = OcsfSchema::load_version(version)
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    let engine = MappingEngine::new(&schema, &definition)?;
+    let records = mapping_records(&definition, &request.records)?;
+    let include_validation = request.include_validation.unwrap_or(false);
+
+    let mut response = ApplyMappingResponse {
+        version: schema.version.clone(),
+        mapping: definition.name.clone(),
+        record_count: records.len(),
+        mapped_count: 0,
+        invalid_count: 0,
+        failed_count: 0,
+        skipped_count: 0,
+        unmapped_field_count: 0,
+        records: Vec::with_capacity(records.len()),
+    };
+    for (index, source) in records.iter().enumerate() {
+        if engine.skips(&source.record) {
+            response.skipped_count += 1;
+            continue;
+        }
+        let mapped = match engine.apply_source(&source.record, source.raw.as_deref()) {
+            Ok(mapped) => {
+                response.mapped_count += 1;
+                response.unmapped_field_count += mapped.unmapped_count;
+                if !mapped.validation.is_valid {
+                    response.invalid_count += 1;
+                }
+                MappedRecord {
+                    index,
+                    event: Some(mapped.event),
+                    validation: include_validation.then_some(mapped.validation),
+                    unmapped_count: Some(mapped.unmapped_count),
+                    error: None,
+                }
+            }
+            Err(e) => {
+                response.failed_count += 1;
+                MappedRecord {
+                    index,
+                    event: None,
+                    validation: None,
+                    unmapped_count: None,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+        response.records.push(mapped);
+    }
+
+    serde_json::to_string_pretty(&response).map_err(|e| anyhow::anyhow!(e.to_string()))
+}
+
+/// The inline mapping, or the bundled pack of that name; `None` when neither is given
+pub(crate) fn resolve_mapping(
+    mapping: Option<&str>,
+    pack: Option<&str>,
+) -> Result<Option<MappingDefinition>> {
+    match (mapping, pack) {
+        (Some(mapping), _) => MappingDefinition::parse(mapping).map(Some),
+        (None, Some(name)) => MappingDefinition::builtin(name).map(Some).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown pack '{}'. Available: {}",
+                name,
+                builtin_names().join(", ")
+            )
+        }),
+        (None, None) => Ok(None),
+    }
+}
+
+pub(crate) fn builtin_names() -> Vec<String> {
+    MappingDefinition::builtins()
+        .into_iter()
+        .filter_map(|m| m.name)
+        .collect()
+}
+
+/// A record to map and, for formats other than JSON, the text it was parsed from
+pub(crate) struct SourceRecord {
+    pub record: Value,
+    pub raw: Option<String>,
+}
+
+/// Records for a mapping: JSON as-is (unwrapped from `records_path`), other formats through their parser
+pub(crate) fn mapping_records(
+    definition: &MappingDefinition,
+    input: &str,
+) -> Result<Vec<SourceRecord>> {
+    let json = |record| SourceRecord { record, raw: None };
+    match definition.format {
+        None | Some(LogFormat::Json) => {
+            let records = parse_records(input)?;
+            Ok(match definition.records_path.as_deref() {
+                Some(path) => records
+                    .into_iter()
+                    .flat_map(|document| match lookup(&document, path) {
+                        Some(Value::Array(inner)) => inner.clone(),
+                        _ => vec![document],
+                    })
+                    .map(json)
+                    .collect(),
+                None => records.into_iter().map(json).collect(),
+            })
+        }
+        Some(format) => Ok(parse_input(input, Some(format))?
+            .into_iter()
+            .map(|record| SourceRecord {
+                record: record.to_record(),
+                raw: Some(record.raw),
+            })
+            .collect()),
+    }
+}
+
+/// Parse a JSON object, a JSON array of objects, or NDJSON
+pub(crate) fn parse_records(input: &str) -> Result<Vec<Value>> {
+    match serde_json::from_str::<Value>(input) {
+        Ok(Value::Array(records)) => Ok(records),
+        Ok(record @ Value::Object(_)) => Ok(vec![record]),
+        _ => input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| anyhow::anyhow!("Invalid JSON on line {}: {}", i + 1, e))
+            })
+            .collect(),
+    }
+}
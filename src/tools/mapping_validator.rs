@@ -0,0 +1,86 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::mapping_applier::{mapping_records, resolve_mapping};
+use crate::ocsf::mapping::MappingDefinition;
+use crate::ocsf::mapping_validation::validate_mapping;
+use crate::ocsf::parsers::LogFormat;
+use crate::ocsf::OcsfSchema;
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ValidateMappingRequest {
+    #[schemars(
+        description = "OCSF schema version (defaults to the mapping's version, then 1.7.0-dev)"
+    )]
+    pub version: Option<String>,
+    #[schemars(description = "Mapping definition as YAML or JSON")]
+    pub mapping: Option<String>,
+    #[schemars(
+        description = "Bundled mapping pack to check instead of 'mapping'; omit both to list the packs"
+    )]
+    pub pack: Option<String>,
+    #[schemars(
+        description = "Optional sample records (JSON object, JSON array, NDJSON, or raw log lines for mappings with a non-JSON format) to dry-run and to find unmapped source fields"
+    )]
+    pub sample_records: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MappingPackListing {
+    pub name: String,
+    pub description: String,
+    pub format: Option<LogFormat>,
+    pub classes: Vec<String>,
+}
+
+fn pack_listing(mapping: MappingDefinition) -> MappingPackListing {
+    let mut classes: Vec<String> = mapping.class.into_iter().collect();
+    for rule in mapping.class_rules {
+        if !classes.contains(&rule.class) {
+            classes.push(rule.class);
+        }
+    }
+    MappingPackListing {
+        name: mapping.name.unwrap_or_default(),
+        description: mapping.description.unwrap_or_default(),
+        format: mapping.format,
+        classes,
+    }
+}
+
+/// Check a mapping's target paths, types and required-attribute coverage against the schema
+pub async fn validate_ocsf_mapping(request: ValidateMappingRequest) -> Result<String> {
+    let Some(definition) = resolve_mapping(request.mapping.as_deref(), request.pack.as_deref())?
+    else {
+        let listing: Vec<MappingPackListing> = MappingDefinition::builtins()
+            .into_iter()
+            .map(pack_listing)
+            .collect();
+        return serde_json::to_string_pretty(&listing).map_err(|e| anyhow::anyhow!(e.to_string()));
+    };
+    let version = request
+        .version
+        .as_deref()
+        .or(definition.version.as_deref())
+        .unwrap_or("1.7.0-dev");
+
+    tracing::info!(
+        "validate_ocsf_mapping called: version={}, mapping={:?}",
+        version,
+        definition.name
+    );
+
+    let schema = OcsfSchema::load_version(version)
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    let samples = match &request.sample_records {
+        Some(records) => mapping_records(&definition, records)?
+            .into_iter()
+            .map(|source| source.record)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let report = validate_mapping(&schema, &definition, &samples);
+    serde_json::to_string_pretty(&report).map_err(|e| anyhow::anyhow!(e.to_string()))
+}
@@ -1,23 +1,49 @@
 // MCP Tools for OCSF Server
 // Each tool is in its own module for better organization
 
+pub mod bulk_generator;
 pub mod code_generator;
 pub mod docs_tool;
 pub mod event_generator;
+pub mod file_validator;
+pub mod fixer;
+pub mod linter;
+pub mod log_converter;
 pub mod mapper;
+pub mod mapping_applier;
+pub mod mapping_validator;
+pub mod scenario_generator;
 pub mod schema_browser;
+pub mod schema_export;
+pub mod schema_stats;
 pub mod validator;
 pub mod version_tools;
 
 // Re-export for convenience
+pub use bulk_generator::{
+    generate_ocsf_events_bulk as generate_events_bulk_impl, GenerateEventsBulkRequest,
+};
 pub use code_generator::{generate_logging_code as generate_code_impl, GenerateCodeRequest};
 pub use docs_tool::{read_ocsf_docs as read_docs_impl, ReadOcsfDocsRequest};
 pub use event_generator::{generate_ocsf_event as generate_event_impl, GenerateEventRequest};
+pub use file_validator::{validate_ocsf_file as validate_file_impl, ValidateFileRequest};
+pub use fixer::{fix_ocsf_event as fix_event_impl, FixEventRequest};
+pub use linter::{lint_ocsf_event as lint_event_impl, LintEventRequest};
+pub use log_converter::{convert_log_file as convert_log_file_impl, ConvertLogFileRequest};
 pub use mapper::{
     list_event_examples as list_examples_impl, map_custom_to_ocsf as map_custom_impl,
     ListExamplesRequest, MapCustomRequest,
 };
+pub use mapping_applier::{apply_ocsf_mapping as apply_mapping_impl, ApplyMappingRequest};
+pub use mapping_validator::{
+    validate_ocsf_mapping as validate_mapping_impl, ValidateMappingRequest,
+};
+pub use scenario_generator::{
+    generate_ocsf_scenario as generate_scenario_impl, GenerateScenarioRequest,
+};
 pub use schema_browser::{browse_ocsf_schema as browse_schema_impl, BrowseSchemaRequest};
+pub use schema_export::{export_json_schema as export_json_schema_impl, ExportJsonSchemaRequest};
+pub use schema_stats::{schema_stats as schema_stats_impl, SchemaStatsRequest};
 pub use validator::{validate_ocsf_event as validate_event_impl, ValidateEventRequest};
 pub use version_tools::{
     get_newest_ocsf_version as get_newest_version_impl, list_ocsf_versions as list_versions_impl,
@@ -67,6 +93,38 @@ impl OcsfServer {
         }
     }
 
+    #[tool(
+        description = "Export an OCSF event class (with chosen profiles and extensions) as a JSON Schema draft 2020-12 document"
+    )]
+    async fn export_json_schema(
+        &self,
+        Parameters(request): Parameters<ExportJsonSchemaRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match export_json_schema_impl(request).await {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Err(McpError::internal_error(
+                "export_json_schema_error",
+                Some(serde_json::json!({"error": e.to_string()})),
+            )),
+        }
+    }
+
+    #[tool(
+        description = "Report OCSF schema statistics: classes per category, objects, attributes by type, enums, deprecations and profile usage"
+    )]
+    async fn schema_stats(
+        &self,
+        Parameters(request): Parameters<SchemaStatsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match schema_stats_impl(request).await {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Err(McpError::internal_error(
+                "schema_stats_error",
+                Some(serde_json::json!({"error": e.to_string()})),
+            )),
+        }
+    }
+
     #[tool(description = "Generate a valid OCSF event JSON from parameters")]
     async fn generate_ocsf_event(
         &self,
@@ -81,7 +139,41 @@ impl OcsfServer {
         }
     }
 
-    #[tool(description = "Validate an OCSF event JSON against the schema")]
+    #[tool(
+        description = "Generate reproducible synthetic OCSF events in bulk (seeded, weighted classes, time window) as NDJSON or an inline sample"
+    )]
+    async fn generate_ocsf_events_bulk(
+        &self,
+        Parameters(request): Parameters<GenerateEventsBulkRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match generate_events_bulk_impl(request).await {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Err(McpError::internal_error(
+                "generate_events_bulk_error",
+                Some(serde_json::json!({"error": e.to_string()})),
+            )),
+        }
+    }
+
+    #[tool(
+        description = "Generate correlated attack-scenario event sequences (shared actors, hosts, process lineage) from a built-in or inline scenario"
+    )]
+    async fn generate_ocsf_scenario(
+        &self,
+        Parameters(request): Parameters<GenerateScenarioRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match generate_scenario_impl(request).await {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Err(McpError::internal_error(
+                "generate_scenario_error",
+                Some(serde_json::json!({"error": e.to_string()})),
+            )),
+        }
+    }
+
+    #[tool(
+        description = "Validate an OCSF event JSON against the schema; the report can be JSON, SARIF, JUnit XML or JSON Lines"
+    )]
     async fn validate_ocsf_event(
         &self,
         Parameters(request): Parameters<ValidateEventRequest>,
@@ -95,6 +187,54 @@ impl OcsfServer {
         }
     }
 
+    #[tool(
+        description = "Validate every event of a local NDJSON or JSON array file (gzip or zstd compressed too) in parallel, reporting error counts per rule, class and field path, the first failing examples per rule and the pass rate, or per-event SARIF, JUnit XML or JSON Lines"
+    )]
+    async fn validate_ocsf_file(
+        &self,
+        Parameters(request): Parameters<ValidateFileRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match validate_file_impl(request).await {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Err(McpError::internal_error(
+                "validate_file_error",
+                Some(serde_json::json!({"error": e.to_string()})),
+            )),
+        }
+    }
+
+    #[tool(
+        description = "Repair the mechanical validation failures of an OCSF event (missing type_uid or category_uid, enum ids and captions that disagree or are missing, timestamp strings, integer strings, missing metadata.version, legacy metadata attributes) and return the corrected event, the applied fixes and the errors left for a human to resolve"
+    )]
+    async fn fix_ocsf_event(
+        &self,
+        Parameters(request): Parameters<FixEventRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match fix_event_impl(request).await {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Err(McpError::internal_error(
+                "fix_event_error",
+                Some(serde_json::json!({"error": e.to_string()})),
+            )),
+        }
+    }
+
+    #[tool(
+        description = "Lint an OCSF event for class association gaps (one side of an entity populated, the other missing)"
+    )]
+    async fn lint_ocsf_event(
+        &self,
+        Parameters(request): Parameters<LintEventRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match lint_event_impl(request).await {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Err(McpError::internal_error(
+                "lint_event_error",
+                Some(serde_json::json!({"error": e.to_string()})),
+            )),
+        }
+    }
+
     #[tool(description = "Generate OCSF logging code for a specific language/framework")]
     async fn generate_logging_code(
         &self,
@@ -123,6 +263,54 @@ impl OcsfServer {
         }
     }
 
+    #[tool(
+        description = "Apply a declarative YAML/JSON mapping (renames, casts, lookups, timestamp parsing, regex extraction, concat, defaults, conditional classes) or a bundled pack (sshd, sudo, auditd, http_access, zeek, suricata, cloudtrail, azure_activity, gcp_audit, k8s_audit, windows_security) to raw records; leftover source fields are kept in unmapped"
+    )]
+    async fn apply_ocsf_mapping(
+        &self,
+        Parameters(request): Parameters<ApplyMappingRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match apply_mapping_impl(request).await {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Err(McpError::internal_error(
+                "apply_mapping_error",
+                Some(serde_json::json!({"error": e.to_string()})),
+            )),
+        }
+    }
+
+    #[tool(
+        description = "Stream a local log file (NDJSON, syslog, CSV, auditd, Zeek, Windows XML; gzip or zstd compressed) through a mapping or bundled pack into an OCSF NDJSON file, reporting converted, invalid, failed and skipped totals"
+    )]
+    async fn convert_log_file(
+        &self,
+        Parameters(request): Parameters<ConvertLogFileRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match convert_log_file_impl(request).await {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Err(McpError::internal_error(
+                "convert_log_file_error",
+                Some(serde_json::json!({"error": e.to_string()})),
+            )),
+        }
+    }
+
+    #[tool(
+        description = "Validate a mapping definition or bundled pack: target paths, transform output types, required-attribute coverage and unmapped sample fields; lists the bundled packs when given neither"
+    )]
+    async fn validate_ocsf_mapping(
+        &self,
+        Parameters(request): Parameters<ValidateMappingRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match validate_mapping_impl(request).await {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result)])),
+            Err(e) => Err(McpError::internal_error(
+                "validate_mapping_error",
+                Some(serde_json::json!({"error": e.to_string()})),
+            )),
+        }
+    }
+
     #[tool(description = "List example OCSF events for learning")]
     async fn list_event_examples(
         &self,
@@ -192,9 +380,11 @@ impl ServerHandler for OcsfServer {
                 "MCP server for implementing OCSF-based logging in any application. \
                  Provides schema browsing, event generation, validation, and code generation tools. \
                  Supports multiple OCSF schema versions (1.0.0 through 1.7.0-dev). \
-                 Tools: browse_ocsf_schema, generate_ocsf_event, validate_ocsf_event, \
-                 generate_logging_code, map_custom_to_ocsf, list_event_examples, \
-                 list_ocsf_versions, get_newest_ocsf_version, read_ocsf_docs."
+                 Tools: browse_ocsf_schema, export_json_schema, schema_stats, \
+                 generate_ocsf_event, generate_ocsf_events_bulk, generate_ocsf_scenario, \
+                 validate_ocsf_event, validate_ocsf_file, fix_ocsf_event, lint_ocsf_event, generate_logging_code, map_custom_to_ocsf, \
+                 apply_ocsf_mapping, convert_log_file, validate_ocsf_mapping, list_event_examples, list_ocsf_versions, \
+                 get_newest_ocsf_version, read_ocsf_docs."
                     .to_string(),
             ),
         }
@@ -39,6 +39,7 @@ pub async fn browse_ocsf_schema(request: BrowseSchemaRequest) -> Result<String>
             categories: Some(categories),
             event_classes: None,
             attributes: None,
+            associations: None,
         };
         return serde_json::to_string_pretty(&result).map_err(|e| anyhow::anyhow!(e.to_string()));
     }
@@ -55,45 +56,53 @@ pub async fn browse_ocsf_schema(request: BrowseSchemaRequest) -> Result<String>
             categories: None,
             event_classes: Some(event_classes),
             attributes: None,
+            associations: None,
         };
         return serde_json::to_string_pretty(&result).map_err(|e| anyhow::anyhow!(e.to_string()));
     }
 
     // If event class specified, show its details
-    if let Some(ec_name) = request.event_class {
-        if let Some(ec) = schema.get_event_class(&ec_name) {
-            let req_attrs = schema.get_required_attributes(&ec_name);
+    if let Some(ec_name) = request.event_class
+        && let Some(ec) = schema.get_event_class(&ec_name)
+    {
+        let req_attrs = schema.get_required_attributes(&ec_name);
 
-            let attributes = if request.show_attributes {
-                Some(
-                    req_attrs
-                        .iter()
-                        .map(|attr| crate::ocsf::AttributeSummary {
-                            name: attr.clone(),
-                            data_type: "string".to_string(),
-                            description: format!("Required field for {}", ec.name),
-                            required: true,
-                        })
-                        .collect(),
-                )
-            } else {
-                None
-            };
+        let attributes = if request.show_attributes {
+            Some(
+                req_attrs
+                    .iter()
+                    .map(|attr| crate::ocsf::AttributeSummary {
+                        name: attr.clone(),
+                        data_type: "string".to_string(),
+                        description: format!("Required field for {}", ec.name),
+                        required: true,
+                    })
+                    .collect(),
+            )
+        } else {
+            None
+        };
+
+        let associations = if ec.associations.is_empty() {
+            None
+        } else {
+            Some(ec.associations.clone())
+        };
 
-            let result = SchemaInfo {
-                summary: format!(
-                    "Event class '{}' (UID: {}) - {} required fields",
-                    ec.name,
-                    ec.uid,
-                    req_attrs.len()
-                ),
-                categories: None,
-                event_classes: None,
-                attributes,
-            };
-            return serde_json::to_string_pretty(&result)
-                .map_err(|e| anyhow::anyhow!(e.to_string()));
-        }
+        let result = SchemaInfo {
+            summary: format!(
+                "Event class '{}' (UID: {}) - {} required fields, {} associations",
+                ec.name,
+                ec.uid,
+                req_attrs.len(),
+                ec.associations.len()
+            ),
+            categories: None,
+            event_classes: None,
+            attributes,
+            associations,
+        };
+        return serde_json::to_string_pretty(&result).map_err(|e| anyhow::anyhow!(e.to_string()));
     }
 
     Err(anyhow::anyhow!("Event class not found"))
@@ -0,0 +1,73 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ocsf::json_schema::{self, JsonSchemaOptions};
+use crate::ocsf::OcsfSchema;
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ExportJsonSchemaRequest {
+    #[schemars(description = "OCSF schema version (defaults to 1.7.0-dev)")]
+    pub version: Option<String>,
+    pub event_class: String,
+    #[schemars(
+        description = "Profiles to include: comma-separated names or JSON array (e.g. 'cloud,host')"
+    )]
+    pub profiles: Option<String>,
+    #[schemars(
+        description = "Extensions to include: comma-separated names or JSON array (e.g. 'win')"
+    )]
+    pub extensions: Option<String>,
+    #[schemars(description = "Disallow attributes not defined by the schema (defaults to false)")]
+    pub strict: Option<bool>,
+}
+
+/// Export an OCSF event class as a JSON Schema (draft 2020-12) document
+pub async fn export_json_schema(request: ExportJsonSchemaRequest) -> Result<String> {
+    let version = request.version.as_deref().unwrap_or("1.7.0-dev");
+
+    tracing::info!(
+        "export_json_schema called: version={}, event_class={}",
+        version,
+        request.event_class
+    );
+
+    let schema = OcsfSchema::load_version(version)
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+    let options = JsonSchemaOptions {
+        profiles: parse_list(request.profiles.as_deref(), "profiles")?,
+        extensions: parse_list(request.extensions.as_deref(), "extensions")?,
+        strict: request.strict.unwrap_or(false),
+    };
+
+    let document = json_schema::export_class(&schema, &request.event_class, &options)?;
+
+    serde_json::to_string_pretty(&document).map_err(|e| anyhow::anyhow!(e.to_string()))
+}
+
+/// Parse a JSON array or comma-separated list; JSON numbers (e.g. weights) become strings
+pub(crate) fn parse_list(input: Option<&str>, param: &str) -> Result<Vec<String>> {
+    let Some(input) = input else {
+        return Ok(Vec::new());
+    };
+
+    if input.trim().starts_with('[') {
+        let values: Vec<Value> = serde_json::from_str(input)
+            .map_err(|e| anyhow::anyhow!("Invalid JSON in {}: {}", param, e))?;
+        Ok(values
+            .into_iter()
+            .map(|v| match v {
+                Value::String(s) => s,
+                other => other.to_string(),
+            })
+            .collect())
+    } else {
+        Ok(input
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect())
+    }
+}
@@ -0,0 +1,47 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::ocsf::stats::SchemaStats;
+use crate::ocsf::OcsfSchema;
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SchemaStatsRequest {
+    #[schemars(description = "OCSF schema version (defaults to 1.7.0-dev)")]
+    pub version: Option<String>,
+    #[schemars(
+        description = "Report statistics for every available version to track schema growth (defaults to false)"
+    )]
+    pub all_versions: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SchemaStatsHistory {
+    pub versions: Vec<SchemaStats>,
+}
+
+/// Report class, object, attribute, enum, deprecation and profile counts for OCSF versions
+pub async fn schema_stats(request: SchemaStatsRequest) -> Result<String> {
+    tracing::info!(
+        "schema_stats called: version={:?}, all_versions={:?}",
+        request.version,
+        request.all_versions
+    );
+
+    if request.all_versions.unwrap_or(false) {
+        let mut history = SchemaStatsHistory {
+            versions: Vec::new(),
+        };
+        for version in OcsfSchema::list_versions()? {
+            let schema = OcsfSchema::load_version(&version).await?;
+            history.versions.push(schema.stats());
+        }
+        return serde_json::to_string_pretty(&history).map_err(|e| anyhow::anyhow!(e.to_string()));
+    }
+
+    let version = request.version.as_deref().unwrap_or("1.7.0-dev");
+    let schema = OcsfSchema::load_version(version)
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+    serde_json::to_string_pretty(&schema.stats()).map_err(|e| anyhow::anyhow!(e.to_string()))
+}
@@ -1,19 +1,81 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::ocsf::validation;
+use crate::ocsf::file_validation::EventResult;
+use crate::ocsf::json_schema::{self, JsonSchemaOptions};
+use crate::ocsf::report_formats::{render, ReportFormat};
+use crate::ocsf::validation::{self, ValidationReport};
+use crate::ocsf::OcsfSchema;
 
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct ValidateEventRequest {
+    #[schemars(description = "OCSF schema version (defaults to 1.7.0-dev)")]
+    pub version: Option<String>,
     pub event_json: String,
+    #[schemars(
+        description = "Also validate against the exported JSON Schema using the event's metadata.profiles (defaults to false)"
+    )]
+    pub json_schema_cross_check: Option<bool>,
+    #[schemars(
+        description = "Output format: 'json' (default), 'sarif' (SARIF 2.1.0 with JSON pointer locations), 'junit' (JUnit XML) or 'json_lines'"
+    )]
+    pub format: Option<ReportFormat>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CrossCheckReport {
+    pub validator: ValidationReport,
+    pub json_schema: ValidationReport,
 }
 
 /// Validate an OCSF event JSON against the schema
 pub async fn validate_ocsf_event(request: ValidateEventRequest) -> Result<String> {
-    tracing::info!("validate_ocsf_event called");
+    let version = request.version.as_deref().unwrap_or("1.7.0-dev");
+
+    tracing::info!("validate_ocsf_event called: version={}", version);
+
+    let schema = OcsfSchema::load_version(version)
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
 
-    let report = validation::validate_event(&request.event_json)
+    let report = validation::validate_event_with_schema(&request.event_json, &schema)
         .map_err(|e| anyhow::anyhow!(e.to_string()))?;
 
-    serde_json::to_string_pretty(&report).map_err(|e| anyhow::anyhow!(e.to_string()))
+    let format = request.format.unwrap_or_default();
+    if format != ReportFormat::Json {
+        if request.json_schema_cross_check.unwrap_or(false) {
+            anyhow::bail!("json_schema_cross_check is only available with the json format");
+        }
+        let result = EventResult {
+            index: 0,
+            line: None,
+            result: Ok(report),
+        };
+        return render(format, None, &[result]);
+    }
+
+    if !request.json_schema_cross_check.unwrap_or(false) {
+        return serde_json::to_string_pretty(&report).map_err(|e| anyhow::anyhow!(e.to_string()));
+    }
+
+    let event: Value = serde_json::from_str(&request.event_json)?;
+    let options = JsonSchemaOptions {
+        profiles: event
+            .pointer("/metadata/profiles")
+            .and_then(|p| serde_json::from_value(p.clone()).ok())
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    let cross_check = CrossCheckReport {
+        validator: report,
+        json_schema: json_schema::validate_with_json_schema(
+            &request.event_json,
+            &schema,
+            &options,
+        )?,
+    };
+
+    serde_json::to_string_pretty(&cross_check).map_err(|e| anyhow::anyhow!(e.to_string()))
 }
@@ -187,13 +187,15 @@ mod event_tests {
             event_class: "authentication".to_string(),
             required_fields: json!({"user": {"name": "testuser"}}).to_string(),
             optional_fields: None,
+            include_validation: None,
         };
 
         let result = generate_event_impl(request).await;
         assert!(result.is_ok());
 
         let event_json = result.unwrap();
-        assert!(event_json.contains("authentication"));
+        assert!(event_json.contains("\"class_uid\": 3002"));
+        assert!(event_json.contains("\"version\": \"1.7.0-dev\""));
         assert!(event_json.contains("testuser"));
     }
 
@@ -204,11 +206,124 @@ mod event_tests {
             event_class: "nonexistent_event_class_12345".to_string(),
             required_fields: "{}".to_string(),
             optional_fields: None,
+            include_validation: None,
         };
 
         let result = generate_event_impl(request).await;
         assert!(result.is_err(), "Should fail for invalid event class");
     }
+
+    #[tokio::test]
+    async fn test_generated_event_envelope() {
+        let request = GenerateEventRequest {
+            version: Some("1.4.0".to_string()),
+            event_class: "authentication".to_string(),
+            required_fields: json!({"activity_id": 2}).to_string(),
+            optional_fields: None,
+            include_validation: None,
+        };
+
+        let event: serde_json::Value =
+            serde_json::from_str(&generate_event_impl(request).await.unwrap()).unwrap();
+
+        assert_eq!(event["class_uid"], 3002);
+        assert_eq!(event["category_uid"], 3);
+        assert_eq!(event["activity_id"], 2);
+        assert_eq!(event["type_uid"], 300202);
+        assert!(event["severity_id"].is_i64());
+        assert_eq!(event["metadata"]["version"], "1.4.0");
+        assert!(event["metadata"]["product"]["name"].is_string());
+        assert!(event["metadata"].get("event_class").is_none());
+        assert!(event["metadata"].get("class_uid").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_validator_rejects_legacy_envelope() {
+        let event = json!({
+            "metadata": {
+                "version": "1.4.0",
+                "event_class": "authentication",
+                "category_uid": 3,
+                "class_uid": 3002
+            },
+            "time": 1736937000000i64
+        });
+
+        let request = ValidateEventRequest {
+            version: Some("1.4.0".to_string()),
+            event_json: event.to_string(),
+            json_schema_cross_check: None,
+            format: None,
+        };
+        let report: serde_json::Value =
+            serde_json::from_str(&validate_event_impl(request).await.unwrap()).unwrap();
+
+        assert_eq!(report["is_valid"], false);
+        let fields: Vec<&str> = report["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["field"].as_str().unwrap())
+            .collect();
+        for field in ["class_uid", "category_uid", "type_uid", "metadata.product"] {
+            assert!(fields.contains(&field), "missing error for {field}");
+        }
+        assert!(report["warnings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|w| w["field"] == "metadata.event_class"));
+    }
+
+    #[tokio::test]
+    async fn test_validator_checks_type_uid_and_category() {
+        let event = json!({
+            "metadata": {"version": "1.4.0", "product": {"name": "auditd", "vendor_name": "Linux"}},
+            "class_uid": 1007,
+            "category_uid": 3,
+            "activity_id": 1,
+            "type_uid": 100702,
+            "severity_id": 1,
+            "time": 1736937000000i64
+        });
+
+        let request = ValidateEventRequest {
+            version: Some("1.4.0".to_string()),
+            event_json: event.to_string(),
+            json_schema_cross_check: None,
+            format: None,
+        };
+        let report: serde_json::Value =
+            serde_json::from_str(&validate_event_impl(request).await.unwrap()).unwrap();
+
+        assert_eq!(report["event_class"], "process_activity");
+        let fields: Vec<&str> = report["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["field"].as_str().unwrap())
+            .collect();
+        assert_eq!(fields, ["type_uid", "category_uid"]);
+    }
+
+    #[tokio::test]
+    async fn test_event_examples_are_valid() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        for example in [
+            ocsf_mcp::ocsf::EventExample::authentication_success(),
+            ocsf_mcp::ocsf::EventExample::authentication_failure(),
+            ocsf_mcp::ocsf::EventExample::process_start(),
+        ] {
+            let report =
+                ocsf_mcp::ocsf::validation::validate_event_with_schema(&example.json, &schema)
+                    .unwrap();
+            assert!(report.is_valid, "{}: {:?}", example.scenario, report.errors);
+            assert_eq!(
+                report.event_class.as_deref(),
+                Some(example.event_class.as_str())
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -244,3 +359,3256 @@ mod tool_integration_tests {
         assert!(!schema.classes.is_empty());
     }
 }
+
+#[cfg(test)]
+mod association_tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_browse_schema_shows_associations() {
+        let request = BrowseSchemaRequest {
+            version: Some("1.4.0".to_string()),
+            category: None,
+            event_class: Some("authentication".to_string()),
+            show_attributes: false,
+        };
+
+        let response: serde_json::Value =
+            serde_json::from_str(&browse_schema_impl(request).await.unwrap()).unwrap();
+        assert_eq!(response["associations"]["user"], json!(["dst_endpoint"]));
+        assert_eq!(
+            response["associations"]["src_endpoint"],
+            json!(["actor.user"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validator_warns_on_missing_association() {
+        let event = json!({
+            "metadata": {"version": "1.4.0", "product": {"name": "sshd", "vendor_name": "OpenBSD"}},
+            "class_uid": 3002,
+            "category_uid": 3,
+            "activity_id": 1,
+            "type_uid": 300201,
+            "severity_id": 1,
+            "time": 1736937000000i64,
+            "user": {"name": "john.doe"},
+            "src_endpoint": {"ip": "10.0.0.5"},
+            "actor": {"user": {"name": "john.doe"}}
+        });
+
+        let request = ValidateEventRequest {
+            version: Some("1.4.0".to_string()),
+            event_json: event.to_string(),
+            json_schema_cross_check: None,
+            format: None,
+        };
+        let report: serde_json::Value =
+            serde_json::from_str(&validate_event_impl(request).await.unwrap()).unwrap();
+
+        let warnings = report["warnings"].as_array().unwrap();
+        assert_eq!(warnings.len(), 1, "only user -> dst_endpoint is unmatched");
+        assert_eq!(warnings[0]["field"], "dst_endpoint");
+    }
+
+    #[tokio::test]
+    async fn test_lint_event_associations() {
+        let event = json!({
+            "class_uid": 1007,
+            "device": {"hostname": "web-01"}
+        });
+
+        let request = LintEventRequest {
+            version: Some("1.4.0".to_string()),
+            event_json: event.to_string(),
+        };
+        let report: serde_json::Value =
+            serde_json::from_str(&lint_event_impl(request).await.unwrap()).unwrap();
+
+        assert_eq!(report["event_class"], "process_activity");
+        assert_eq!(report["findings"][0]["rule"], "association");
+        assert_eq!(report["findings"][0]["field"], "actor.user");
+    }
+}
+
+#[cfg(test)]
+mod json_schema_tests {
+    use super::*;
+    use ocsf_mcp::ocsf::json_schema::{export_class, validate_with_json_schema, JsonSchemaOptions};
+    use ocsf_mcp::ocsf::validation::pointer_position;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_export_class_json_schema() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let document =
+            export_class(&schema, "authentication", &JsonSchemaOptions::default()).unwrap();
+
+        assert_eq!(
+            document["$schema"],
+            "https://json-schema.org/draft/2020-12/schema"
+        );
+        assert!(document["required"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("time")));
+        assert_eq!(document["properties"]["user"]["$ref"], "#/$defs/user");
+        assert!(document["$defs"]["user"]["properties"].is_object());
+        assert!(document["properties"]["class_uid"]["enum"]
+            .as_array()
+            .unwrap()
+            .contains(&json!(3002)));
+        assert!(document["$defs"]["network_endpoint"]["properties"]["ip"]["pattern"].is_string());
+        // Profile attributes are only exported when the profile is selected
+        assert!(document["properties"].get("cloud").is_none());
+
+        let options = JsonSchemaOptions {
+            profiles: vec!["cloud".to_string()],
+            ..Default::default()
+        };
+        let document = export_class(&schema, "authentication", &options).unwrap();
+        assert!(document["properties"]["cloud"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_validate_with_json_schema() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let mut event = json!({
+            "class_uid": 3002,
+            "category_uid": 3,
+            "activity_id": 1,
+            "type_uid": 300201,
+            "severity_id": 1,
+            "time": 1736937000000i64,
+            "metadata": {"version": "1.4.0", "product": {"name": "sshd", "vendor_name": "OpenBSD"}},
+            "user": {"name": "john.doe"},
+            "dst_endpoint": {"ip": "10.0.0.1"}
+        });
+
+        let report =
+            validate_with_json_schema(&event.to_string(), &schema, &JsonSchemaOptions::default())
+                .unwrap();
+        assert!(report.is_valid, "unexpected errors: {:?}", report.errors);
+
+        event["dst_endpoint"] = json!("10.0.0.1");
+        event["activity_id"] = json!(42);
+        let report =
+            validate_with_json_schema(&event.to_string(), &schema, &JsonSchemaOptions::default())
+                .unwrap();
+        assert!(!report.is_valid);
+        assert!(report.errors.iter().any(|e| e.pointer == "/dst_endpoint"));
+        assert!(report.errors.iter().any(|e| e.pointer == "/activity_id"));
+    }
+
+    #[tokio::test]
+    async fn test_json_schema_errors_point_into_arrays() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let event = r#"{
+  "class_uid": 3002,
+  "category_uid": 3,
+  "activity_id": 1,
+  "type_uid": 300201,
+  "severity_id": 1,
+  "time": 1736937000000,
+  "metadata": {"version": "1.4.0", "product": {"name": "sshd", "vendor_name": "OpenBSD"}},
+  "observables": [
+    {"name": "user.name", "type_id": 4, "value": "john.doe"},
+    {"name": "dst_endpoint.ip", "type_id": "2", "value": "10.0.0.1"}
+  ]
+}"#;
+
+        let report =
+            validate_with_json_schema(event, &schema, &JsonSchemaOptions::default()).unwrap();
+        let error = report
+            .errors
+            .iter()
+            .find(|e| e.pointer == "/observables/1/type_id")
+            .unwrap();
+        assert_eq!(error.field, "observables.1.type_id");
+        assert_eq!((error.line, error.column), (Some(11), Some(44)));
+
+        // A pointer past the end of the array locates the array itself
+        assert_eq!(pointer_position(event, "/observables/5"), Some((9, 18)));
+        assert_eq!(
+            pointer_position(event, "/metadata/product/name"),
+            Some((8, 56))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validator_errors_point_into_arrays() {
+        let event = r#"{
+  "class_uid": 3002,
+  "category_uid": 3,
+  "activity_id": 1,
+  "type_uid": 300201,
+  "severity_id": 1,
+  "time": 1736937000000,
+  "metadata": {"version": "1.4.0", "product": {"name": "sshd", "vendor_name": "OpenBSD"}},
+  "user": {"name": "john.doe"},
+  "dst_endpoint": {"ip": "10.0.0.1", "port": "22"},
+  "observables": [
+    {"name": "user.name", "type_id": 4, "value": "john.doe"},
+    {"name": "dst_endpoint.ip", "type_id": 2, "value": "10.0.0.1"},
+    {"name": "dst_endpoint.port", "type_id": 11, "value": 22}
+  ]
+}"#;
+
+        let request = ValidateEventRequest {
+            version: Some("1.4.0".to_string()),
+            event_json: event.to_string(),
+            json_schema_cross_check: None,
+            format: None,
+        };
+        let report: serde_json::Value =
+            serde_json::from_str(&validate_event_impl(request).await.unwrap()).unwrap();
+
+        assert_eq!(report["is_valid"], false);
+        let errors = report["errors"].as_array().unwrap();
+        let value = errors
+            .iter()
+            .find(|e| e["pointer"] == "/observables/2/value")
+            .unwrap();
+        assert_eq!(value["error_type"], "InvalidType");
+        assert_eq!(value["field"], "observables.2.value");
+        assert_eq!((&value["line"], &value["column"]), (&json!(14), &json!(59)));
+        assert!(errors.iter().any(|e| e["pointer"] == "/dst_endpoint/port"));
+        assert_eq!(errors.len(), 2, "{errors:?}");
+    }
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_schema_stats_counts() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let stats = schema.stats();
+
+        assert_eq!(stats.version, "1.4.0");
+        assert_eq!(
+            stats.class_count,
+            schema.classes.len() - 1,
+            "base_event is excluded"
+        );
+        assert_eq!(
+            stats.classes_per_category.values().sum::<usize>(),
+            stats.class_count
+        );
+        assert!(stats.classes_per_category["iam"] > 0);
+        assert!(stats.attributes_by_type["string_t"] > 0);
+        assert!(stats.enum_count > 0 && stats.largest_enums.len() <= 10);
+        assert!(stats
+            .deprecated
+            .classes
+            .contains(&"security_finding".to_string()));
+        assert!(stats.profile_usage["cloud"].classes > 0);
+        assert!(stats.integrity_warnings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_schema_stats_flags_minimal_schema() {
+        // Unknown versions fall back to the minimal embedded schema, which has no attributes
+        let schema = OcsfSchema::load_version("99.99.99").await.unwrap();
+        assert!(!schema.stats().integrity_warnings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_schema_stats_tool_all_versions() {
+        let request = SchemaStatsRequest {
+            version: None,
+            all_versions: Some(true),
+        };
+        let response: serde_json::Value =
+            serde_json::from_str(&schema_stats_impl(request).await.unwrap()).unwrap();
+
+        let versions = response["versions"].as_array().unwrap();
+        assert_eq!(versions.len(), OcsfSchema::list_versions().unwrap().len());
+        let first = versions.first().unwrap()["class_count"].as_u64().unwrap();
+        let last = versions.last().unwrap()["class_count"].as_u64().unwrap();
+        assert!(last >= first, "schema should not shrink across versions");
+    }
+}
+
+#[cfg(test)]
+mod synthetic_tests {
+    use super::*;
+    use ocsf_mcp::ocsf::json_schema::{validate_with_json_schema, JsonSchemaOptions};
+    use ocsf_mcp::ocsf::synthetic::{EventSynthesizer, SyntheticOptions};
+    use ocsf_mcp::ocsf::validation::validate_event;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[tokio::test]
+    async fn test_synthetic_event_is_type_correct() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let synthesizer = EventSynthesizer::new(&schema, SyntheticOptions::default());
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let event = synthesizer.generate("authentication", &mut rng).unwrap();
+        let fields = &event.fields;
+
+        assert!(fields["src_endpoint"].is_object());
+        assert!(fields["src_endpoint"]["ip"].as_str().unwrap().contains('.'));
+        assert!(fields["time"].is_i64());
+        assert_eq!(event.type_uid, 300200 + event.activity_id);
+        assert!(fields["activity_name"].is_string());
+        assert!(fields["observables"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_synthetic_events_pass_validators_for_all_classes() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let synthesizer = EventSynthesizer::new(&schema, SyntheticOptions::default());
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for (name, class) in &schema.classes {
+            if name == "base_event" || class.extension.is_some() {
+                continue;
+            }
+            let json = synthesizer
+                .generate(name, &mut rng)
+                .unwrap()
+                .to_json()
+                .unwrap();
+
+            let report = validate_event(&json).unwrap();
+            assert!(report.is_valid, "{name}: {:?}", report.errors);
+
+            let report =
+                validate_with_json_schema(&json, &schema, &JsonSchemaOptions::default()).unwrap();
+            assert!(report.is_valid, "{name}: {:?}", report.errors);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_event_tool_synthesizes_named_fields() {
+        let request = GenerateEventRequest {
+            version: Some("1.4.0".to_string()),
+            event_class: "network_activity".to_string(),
+            required_fields: "src_endpoint, dst_endpoint".to_string(),
+            optional_fields: Some("proxy".to_string()),
+            include_validation: None,
+        };
+
+        let event: serde_json::Value =
+            serde_json::from_str(&generate_event_impl(request).await.unwrap()).unwrap();
+        assert!(event["src_endpoint"]["ip"].is_string());
+        assert!(
+            event["proxy"].is_object(),
+            "optional attributes are synthesized by type"
+        );
+
+        let request = GenerateEventRequest {
+            version: Some("1.4.0".to_string()),
+            event_class: "network_activity".to_string(),
+            required_fields: "not_an_attribute".to_string(),
+            optional_fields: None,
+            include_validation: None,
+        };
+        assert!(generate_event_impl(request).await.is_err());
+    }
+}
+
+#[cfg(test)]
+mod bulk_tests {
+    use super::*;
+    use ocsf_mcp::ocsf::bulk::{BulkGenerator, BulkOptions};
+
+    fn options(seed: u64) -> BulkOptions {
+        BulkOptions {
+            classes: vec!["authentication".to_string(), "process_activity".to_string()],
+            weights: vec![3.0, 1.0],
+            count: 50,
+            seed,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bulk_generation_is_reproducible() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+
+        let mut first = Vec::new();
+        BulkGenerator::new(&schema, options(99))
+            .unwrap()
+            .write_ndjson_sampled(&mut first, 0)
+            .unwrap();
+        let mut second = Vec::new();
+        let (summary, _) = BulkGenerator::new(&schema, options(99))
+            .unwrap()
+            .write_ndjson_sampled(&mut second, 0)
+            .unwrap();
+        let mut other = Vec::new();
+        BulkGenerator::new(&schema, options(100))
+            .unwrap()
+            .write_ndjson_sampled(&mut other, 0)
+            .unwrap();
+
+        assert_eq!(first, second, "same seed must produce identical NDJSON");
+        assert_ne!(first, other);
+        assert_eq!(summary.generated, 50);
+        assert!(summary.per_class["authentication"] > summary.per_class["process_activity"]);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_timestamps_within_window() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let opts = options(1);
+        let (start, end) = (opts.start_ms, opts.end_ms);
+
+        let times: Vec<i64> = BulkGenerator::new(&schema, opts)
+            .unwrap()
+            .map(|e| e.unwrap().fields["time"].as_i64().unwrap())
+            .collect();
+
+        assert!(times.windows(2).all(|w| w[0] <= w[1]));
+        assert!(times.iter().all(|t| (start..end).contains(t)));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_tool_writes_ndjson() {
+        let path = std::env::temp_dir().join(format!("ocsf-bulk-{}.ndjson", std::process::id()));
+        let request = GenerateEventsBulkRequest {
+            version: Some("1.4.0".to_string()),
+            event_classes: "authentication,file_activity".to_string(),
+            count: 20,
+            seed: Some(5),
+            start_time: Some("2025-06-01T00:00:00Z".to_string()),
+            end_time: None,
+            weights: None,
+            profiles: None,
+            output_path: Some(path.to_string_lossy().to_string()),
+            sample_size: Some(2),
+        };
+
+        let response: serde_json::Value =
+            serde_json::from_str(&generate_events_bulk_impl(request).await.unwrap()).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(response["generated"], 20);
+        assert_eq!(written.lines().count(), 20);
+        let first: serde_json::Value =
+            serde_json::from_str(written.lines().next().unwrap()).unwrap();
+        assert_eq!(response["sample"][0], first, "sample is the file's prefix");
+    }
+
+    #[tokio::test]
+    async fn test_bulk_tool_summarizes_count_without_output_path() {
+        let request = GenerateEventsBulkRequest {
+            version: Some("1.4.0".to_string()),
+            event_classes: r#"["authentication", "file_activity"]"#.to_string(),
+            count: 500,
+            seed: Some(9),
+            start_time: None,
+            end_time: None,
+            weights: Some("[3, 1]".to_string()),
+            profiles: None,
+            output_path: None,
+            sample_size: None,
+        };
+
+        let response: serde_json::Value =
+            serde_json::from_str(&generate_events_bulk_impl(request).await.unwrap()).unwrap();
+
+        assert_eq!(response["generated"], 500);
+        let per_class = response["per_class"].as_object().unwrap();
+        let total: u64 = per_class.values().map(|n| n.as_u64().unwrap()).sum();
+        assert_eq!(total, 500);
+        assert!(per_class["authentication"].as_u64() > per_class["file_activity"].as_u64());
+        assert_eq!(response["sample"].as_array().unwrap().len(), 10);
+    }
+}
+
+#[cfg(test)]
+mod scenario_tests {
+    use super::*;
+    use ocsf_mcp::ocsf::scenario::{Scenario, ScenarioOptions};
+
+    async fn run_builtin(name: &str) -> Vec<serde_json::Value> {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let scenario = Scenario::builtin(name).unwrap();
+        scenario
+            .run(&schema, &ScenarioOptions::default())
+            .unwrap()
+            .into_iter()
+            .map(|e| serde_json::to_value(e).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_brute_force_storyline() {
+        let events = run_builtin("ssh_brute_force_intrusion").await;
+
+        let correlation = &events[0]["metadata"]["correlation_uid"];
+        assert!(correlation.is_string());
+        assert!(events
+            .iter()
+            .all(|e| &e["metadata"]["correlation_uid"] == correlation));
+
+        let times: Vec<i64> = events.iter().map(|e| e["time"].as_i64().unwrap()).collect();
+        assert!(times.windows(2).all(|w| w[0] < w[1]));
+
+        let failures = events.iter().take_while(|e| e["status_id"] == 2).count();
+        assert_eq!(failures, 20);
+        assert_eq!(events[0]["status"], "Failure");
+        assert_eq!(events[20]["status_id"], 1);
+        assert_eq!(events[20]["src_endpoint"], events[0]["src_endpoint"]);
+    }
+
+    #[tokio::test]
+    async fn test_scenario_process_lineage() {
+        let events = run_builtin("ssh_brute_force_intrusion").await;
+        let processes: Vec<&serde_json::Value> =
+            events.iter().filter(|e| e["class_uid"] == 1007).collect();
+
+        assert_eq!(processes[0]["process"]["parent_process"]["name"], "sshd");
+        assert_eq!(
+            processes[1]["process"]["parent_process"]["parent_process"]["name"],
+            "sshd"
+        );
+        assert_eq!(processes[0]["device"]["uid"], processes[1]["device"]["uid"]);
+    }
+
+    #[tokio::test]
+    async fn test_scenario_is_seeded() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let scenario = Scenario::builtin("data_exfiltration").unwrap();
+        let run = |seed| {
+            let options = ScenarioOptions {
+                seed,
+                ..Default::default()
+            };
+            serde_json::to_string(&scenario.run(&schema, &options).unwrap()).unwrap()
+        };
+
+        assert_eq!(run(3), run(3));
+        assert_ne!(run(3), run(4));
+    }
+
+    #[tokio::test]
+    async fn test_inline_scenario_unknown_entity_path() {
+        let request = GenerateScenarioRequest {
+            version: Some("1.4.0".to_string()),
+            scenario: None,
+            scenario_json: Some(
+                r#"{"name": "broken", "entities": {"attacker": {"name": "mallory"}}, "steps": [{"class": "authentication", "fields": {"user": "$attacker.nickname"}}]}"#
+                    .to_string(),
+            ),
+            seed: None,
+            start_time: None,
+            synthesize: Some(false),
+            profiles: None,
+        };
+
+        let error = generate_scenario_impl(request).await.unwrap_err();
+        assert!(error.to_string().contains("$attacker.nickname"));
+    }
+
+    #[tokio::test]
+    async fn test_scenario_keeps_literal_dollar_values() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let scenario =
+            Scenario::from_json(include_str!("fixtures/scenarios/literal_dollars.json")).unwrap();
+        let options = ScenarioOptions {
+            synthesize: false,
+            ..Default::default()
+        };
+        let events = scenario.run(&schema, &options).unwrap();
+        let event = serde_json::to_value(&events[0]).unwrap();
+
+        assert_eq!(event["actor"]["user"]["name"], "mallory");
+        assert_eq!(event["process"]["cmd_line"], "$HOME/.ssh/id_rsa");
+        assert_eq!(event["process"]["file"]["path"], "$MFT");
+        assert_eq!(event["process"]["file"]["name"], "C$");
+        // `$$` escapes a name that is declared
+        assert_eq!(event["process"]["user"]["name"], "$attacker");
+    }
+
+    #[tokio::test]
+    async fn test_scenario_tool_synthesizes_profiles() {
+        let request = GenerateScenarioRequest {
+            version: Some("1.4.0".to_string()),
+            scenario: Some("data_exfiltration".to_string()),
+            scenario_json: None,
+            seed: None,
+            start_time: None,
+            synthesize: None,
+            profiles: Some("cloud".to_string()),
+        };
+
+        let result: serde_json::Value =
+            serde_json::from_str(&generate_scenario_impl(request).await.unwrap()).unwrap();
+        let events = result["events"].as_array().unwrap();
+        assert!(events.iter().all(|e| e["cloud"].is_object()));
+    }
+
+    #[tokio::test]
+    async fn test_scenario_tool_lists_builtins() {
+        let request = GenerateScenarioRequest {
+            version: None,
+            scenario: None,
+            scenario_json: None,
+            seed: None,
+            start_time: None,
+            synthesize: None,
+            profiles: None,
+        };
+
+        let listing: serde_json::Value =
+            serde_json::from_str(&generate_scenario_impl(request).await.unwrap()).unwrap();
+        let names: Vec<&str> = listing
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&"ssh_brute_force_intrusion"));
+        assert!(names.contains(&"data_exfiltration"));
+    }
+}
+
+#[cfg(all(test, feature = "typed-events"))]
+mod typed_tests {
+    use super::*;
+    use ocsf_mcp::ocsf::synthetic::{EventSynthesizer, SyntheticOptions};
+    use ocsf_mcp::ocsf::typed::{
+        self, Authentication, AuthenticationActivityId, Metadata, OcsfClass, Product, SeverityId,
+        User,
+    };
+    use ocsf_mcp::ocsf::validation::validate_event_with_schema;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_typed_event_validates() {
+        let schema = OcsfSchema::load_version(typed::SCHEMA_VERSION)
+            .await
+            .unwrap();
+        let product = Product {
+            name: Some("sshd".to_string()),
+            vendor_name: Some("OpenBSD".to_string()),
+            ..Default::default()
+        };
+        let user = User {
+            name: Some("john.doe".to_string()),
+            ..Default::default()
+        };
+        let event = Authentication::new(
+            AuthenticationActivityId::Logon,
+            Metadata::new(product, typed::SCHEMA_VERSION.to_string()),
+            SeverityId::Informational,
+            1736937000000,
+            user,
+        );
+
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["activity_id"], 1);
+        assert_eq!(value["severity_id"], 1);
+        assert!(value.get("auth_protocol_id").is_none());
+
+        let report = validate_event_with_schema(&value.to_string(), &schema).unwrap();
+        assert!(report.is_valid, "{:?}", report.errors);
+        assert_eq!(report.event_class.as_deref(), Some(Authentication::NAME));
+
+        let generic = typed::to_event(&event).unwrap();
+        assert_eq!(generic.class_uid, 3002);
+        assert_eq!(generic.type_uid, 300201);
+    }
+
+    #[tokio::test]
+    async fn test_typed_roundtrip_of_synthetic_event() {
+        let schema = OcsfSchema::load_version(typed::SCHEMA_VERSION)
+            .await
+            .unwrap();
+        let synthesizer = EventSynthesizer::new(&schema, SyntheticOptions::default());
+        let event = synthesizer
+            .generate("authentication", &mut StdRng::seed_from_u64(3))
+            .unwrap();
+        let value = serde_json::to_value(&event).unwrap();
+
+        let typed: Authentication = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&typed).unwrap(), value);
+    }
+
+    #[test]
+    fn test_typed_enum_serde() {
+        assert_eq!(serde_json::to_value(SeverityId::High).unwrap(), json!(4));
+        assert_eq!(
+            serde_json::from_value::<SeverityId>(json!(99)).unwrap(),
+            SeverityId::Other
+        );
+        assert_eq!(SeverityId::Critical.caption(), "Critical");
+
+        let error = serde_json::from_value::<SeverityId>(json!(42)).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("42 is not a valid SeverityId value"));
+    }
+}
+
+#[cfg(test)]
+mod builder_tests {
+    use super::*;
+    use ocsf_mcp::ocsf::builder::OcsfEventBuilder;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_builder_coerces_and_derives() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let (event, report) = OcsfEventBuilder::new(&schema, "authentication")
+            .unwrap()
+            .set("activity_id", "logon")
+            .unwrap()
+            .set("severity_id", "3")
+            .unwrap()
+            .set("time", "2025-01-15T10:30:00Z")
+            .unwrap()
+            .set("is_remote", "yes")
+            .unwrap()
+            .set(
+                "user",
+                json!({"name": "john.doe", "uid": 1001, "type_id": 1}),
+            )
+            .unwrap()
+            .set(
+                "metadata",
+                json!({"product": {"name": "sshd", "vendor_name": "OpenBSD"}}),
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["activity_id"], 1);
+        assert_eq!(value["activity_name"], "Logon");
+        assert_eq!(value["severity"], "Medium");
+        assert_eq!(value["type_uid"], 300201);
+        assert_eq!(value["type_name"], "Authentication: Logon");
+        assert_eq!(value["class_name"], "Authentication");
+        assert_eq!(value["time"], 1736937000000i64);
+        assert_eq!(value["is_remote"], true);
+        assert_eq!(value["user"]["uid"], "1001");
+        assert_eq!(value["user"]["type"], "User");
+        assert_eq!(value["metadata"]["version"], "1.4.0");
+        assert!(report.is_valid, "{:?}", report.errors);
+    }
+
+    #[tokio::test]
+    async fn test_builder_rejects_unknown_attributes() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let builder = || OcsfEventBuilder::new(&schema, "authentication").unwrap();
+
+        let error = builder().set("username", "john").err().unwrap();
+        assert!(error.to_string().contains("'username' is not defined"));
+
+        let error = builder()
+            .set("user", json!({"login": "john"}))
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("user.login"));
+
+        let error = builder().set("activity_id", 42).err().unwrap();
+        assert!(error.to_string().contains("not a valid value"));
+
+        let error = builder().set("time", "yesterday").err().unwrap();
+        assert!(error.to_string().contains("timestamp_t"));
+    }
+
+    #[tokio::test]
+    async fn test_builder_reports_missing_required_and_keeps_other_caption() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let (event, report) = OcsfEventBuilder::new(&schema, "authentication")
+            .unwrap()
+            .set("auth_protocol", "SSH password")
+            .unwrap()
+            .set("auth_protocol_id", 99)
+            .unwrap()
+            .set("time", 1736937000000i64)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(event.fields["auth_protocol"], "SSH password");
+        assert!(!report.is_valid);
+        let missing: Vec<&str> = report.errors.iter().map(|e| e.field.as_str()).collect();
+        assert!(missing.contains(&"user"), "{missing:?}");
+    }
+
+    #[tokio::test]
+    async fn test_generate_event_tool_reports_validation() {
+        let request = GenerateEventRequest {
+            version: Some("1.4.0".to_string()),
+            event_class: "authentication".to_string(),
+            required_fields: json!({"activity_id": "Logoff", "user": {"name": "alice"}})
+                .to_string(),
+            optional_fields: None,
+            include_validation: Some(true),
+        };
+
+        let response: serde_json::Value =
+            serde_json::from_str(&generate_event_impl(request).await.unwrap()).unwrap();
+        assert_eq!(response["event"]["activity_id"], 2);
+        assert_eq!(response["event"]["activity_name"], "Logoff");
+        assert_eq!(response["event"]["type_uid"], 300202);
+        assert_eq!(response["validation"]["is_valid"], true);
+
+        let request = GenerateEventRequest {
+            version: Some("1.4.0".to_string()),
+            event_class: "authentication".to_string(),
+            required_fields: json!({"username": "alice"}).to_string(),
+            optional_fields: None,
+            include_validation: None,
+        };
+        assert!(generate_event_impl(request).await.is_err());
+    }
+}
+
+#[cfg(test)]
+mod inference_tests {
+    use super::*;
+    use ocsf_mcp::ocsf::inference::{parse_sample, FieldInferrer, ValueShape};
+    use ocsf_mcp::ocsf::parsers::LogFormat;
+    use serde_json::{json, Value};
+
+    async fn map(sample: &str, suggested_class: Option<&str>) -> Value {
+        let request = MapCustomRequest {
+            sample_log: sample.to_string(),
+            sample_format: None,
+            suggested_class: suggested_class.map(str::to_string),
+            version: Some("1.4.0".to_string()),
+            emit_mapping: None,
+            mapping_format: None,
+            preview_count: None,
+        };
+        serde_json::from_str(&map_custom_impl(request).await.unwrap()).unwrap()
+    }
+
+    fn mapping<'a>(response: &'a Value, source_field: &str) -> &'a Value {
+        response["field_mappings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|m| m["source_field"] == source_field)
+            .unwrap_or_else(|| panic!("no mapping for {source_field}"))
+    }
+
+    #[test]
+    fn test_parse_sample_formats_and_shapes() {
+        let sample = parse_sample(
+            r#"{"ts": 1736937000, "client": {"ip": "10.0.0.5"}, "md5": "d41d8cd98f00b204e9800998ecf8427e"}"#,
+        );
+        assert_eq!(sample.format, LogFormat::Json);
+        let shape = |name: &str| {
+            sample
+                .fields
+                .iter()
+                .find(|f| f.name == name)
+                .map(|f| f.shape)
+                .unwrap()
+        };
+        assert_eq!(shape("ts"), ValueShape::EpochSeconds);
+        assert_eq!(shape("client.ip"), ValueShape::Ip);
+        assert_eq!(shape("md5"), ValueShape::Hash);
+
+        let sample = parse_sample("src=10.0.0.1 dst=10.0.0.2 mail=alice@example.com");
+        assert_eq!(sample.format, LogFormat::KeyValue);
+        assert_eq!(sample.fields.len(), 3);
+        assert_eq!(sample.fields[2].shape, ValueShape::Email);
+
+        let sample = parse_sample("2025-01-15 10:30:00 Accepted password for bob from 10.1.1.1");
+        assert_eq!(sample.format, LogFormat::Text);
+        let names: Vec<&str> = sample.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["timestamp", "src_ip"]);
+    }
+
+    #[tokio::test]
+    async fn test_map_json_sample_infers_fields() {
+        let response = map(
+            r#"{"timestamp": "2025-01-15T10:30:00Z", "username": "john.doe", "src_ip": "192.168.1.100",
+                "src_port": 52144, "result": "Failure", "event": "login"}"#,
+            None,
+        )
+        .await;
+
+        assert_eq!(response["suggested_event_class"], "authentication");
+        assert_eq!(response["class_ranking"][0]["name"], "authentication");
+        assert_eq!(response["class_ranking"].as_array().unwrap().len(), 5);
+
+        assert_eq!(mapping(&response, "username")["ocsf_field"], "user.name");
+        assert_eq!(
+            mapping(&response, "src_ip")["ocsf_field"],
+            "src_endpoint.ip"
+        );
+        assert_eq!(
+            mapping(&response, "src_port")["ocsf_field"],
+            "src_endpoint.port"
+        );
+        assert_eq!(mapping(&response, "timestamp")["ocsf_field"], "time");
+        assert_eq!(
+            mapping(&response, "timestamp")["transformation"],
+            "Parse timestamp to epoch milliseconds"
+        );
+        let result = mapping(&response, "result");
+        assert_eq!(result["ocsf_field"], "status_id");
+        assert_eq!(result["transformation"], "Map 'Failure' to 2 (Failure)");
+        assert!(result["confidence"].as_f64().unwrap() >= 0.75);
+    }
+
+    #[tokio::test]
+    async fn test_map_text_and_key_value_samples() {
+        let response = map(
+            "2025-01-15 10:30:00 ERROR [auth] Failed login attempt for user 'admin' from 192.168.1.100",
+            None,
+        )
+        .await;
+        assert_eq!(response["sample_format"], "text");
+        assert_eq!(response["suggested_event_class"], "authentication");
+        assert_eq!(mapping(&response, "user")["sample_value"], "admin");
+        assert_eq!(
+            mapping(&response, "src_ip")["ocsf_field"],
+            "src_endpoint.ip"
+        );
+
+        let response = map(
+            r#"ts=1736937000 pid=4242 ppid=1 exe=/usr/bin/curl cmdline="curl -s http://example.com""#,
+            None,
+        )
+        .await;
+        assert_eq!(response["suggested_event_class"], "process_activity");
+        assert_eq!(mapping(&response, "pid")["ocsf_field"], "process.pid");
+        assert_eq!(
+            mapping(&response, "ppid")["ocsf_field"],
+            "process.parent_process.pid"
+        );
+        assert_eq!(mapping(&response, "exe")["ocsf_field"], "process.file.path");
+        assert_eq!(
+            mapping(&response, "cmdline")["ocsf_field"],
+            "process.cmd_line"
+        );
+        assert_eq!(
+            mapping(&response, "ts")["transformation"],
+            "Convert epoch seconds to milliseconds"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_map_respects_suggested_class_and_reports_unmapped() {
+        let response = map(
+            r#"{"query": "example.com", "answer": "93.184.216.34", "zzz_flag": true}"#,
+            Some("dns_activity"),
+        )
+        .await;
+        assert_eq!(response["suggested_event_class"], "dns_activity");
+        assert_eq!(response["unmapped_fields"], json!(["zzz_flag"]));
+
+        let request = MapCustomRequest {
+            sample_log: "{}".to_string(),
+            sample_format: None,
+            suggested_class: Some("not_a_class".to_string()),
+            version: Some("1.4.0".to_string()),
+            emit_mapping: None,
+            mapping_format: None,
+            preview_count: None,
+        };
+        assert!(map_custom_impl(request).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rank_classes_covers_all_classes() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let ranking = FieldInferrer::new(&schema)
+            .rank_classes(&parse_sample(r#"{"query": "example.com", "qtype": "A"}"#));
+        assert_eq!(ranking.len(), schema.classes.len() - 1);
+        assert_eq!(ranking[0].name, "dns_activity");
+        assert!(ranking.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+}
+
+#[cfg(test)]
+mod mapping_tests {
+    use super::*;
+    use ocsf_mcp::ocsf::mapping::{
+        parse_timestamp_ms, MappingDefinition, MappingEngine, UnmappedOptions, UnmappedPolicy,
+    };
+    use serde_json::{json, Value};
+
+    const SSHD_MAPPING: &str = r#"
+name: sshd
+version: 1.4.0
+class: authentication
+class_rules:
+  - when: { field: action, equals: exec }
+    class: process_activity
+fields:
+  - { source: ts, target: time, transforms: [{ parse_timestamp: { format: "%Y-%m-%d %H:%M:%S" } }] }
+  - { source: user, target: user.name, transforms: [trim, lowercase] }
+  - source: result
+    target: status_id
+    transforms: [{ lookup: { table: { accepted: 1, failed: 2 }, default: 99, case_insensitive: true } }]
+  - { source: result, target: status, when: { field: result, matches: "^(?i)other" } }
+  - { source: msg, target: src_endpoint.ip, transforms: [{ regex: { pattern: 'from (\S+)' } }] }
+  - { source: port, target: src_endpoint.port, transforms: [{ cast: integer }] }
+  - { sources: [host, domain], target: device.hostname, transforms: [{ concat: { separator: "." } }] }
+  - { target: device.type_id, value: 1 }
+  - { target: severity_id, value: 1, when: { field: result, one_of: [accepted, Accepted] } }
+  - { target: severity_id, value: 3, when: { field: result, matches: "(?i)^failed$" } }
+  - { source: activity, target: activity_id, default: 1 }
+  - { source: cmd, target: process.cmd_line, when: { field: action, equals: exec } }
+  - { source: ts, target: time, when: { field: action, equals: exec }, transforms: [{ parse_timestamp: { format: "%Y-%m-%d %H:%M:%S" } }] }
+"#;
+
+    #[tokio::test]
+    async fn test_mapping_engine_applies_transforms() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let definition = MappingDefinition::parse(SSHD_MAPPING).unwrap();
+        let engine = MappingEngine::new(&schema, &definition).unwrap();
+
+        let record = json!({
+            "ts": "2025-01-15 10:30:00",
+            "user": "  Alice ",
+            "result": "Failed",
+            "msg": "Failed password from 10.0.0.7 port 52144",
+            "port": "52144",
+            "host": "web01",
+            "domain": "example.com"
+        });
+        let mapped = engine.apply(&record).unwrap();
+        let event = serde_json::to_value(&mapped.event).unwrap();
+
+        assert_eq!(event["class_uid"], 3002);
+        assert_eq!(event["time"], 1736937000000i64);
+        assert_eq!(event["user"]["name"], "alice");
+        assert_eq!(event["status_id"], 2);
+        assert_eq!(event["status"], "Failure");
+        assert_eq!(event["src_endpoint"]["ip"], "10.0.0.7");
+        assert_eq!(event["src_endpoint"]["port"], 52144);
+        assert_eq!(event["device"]["hostname"], "web01.example.com");
+        assert_eq!(event["device"]["type"], "Server");
+        assert_eq!(event["severity_id"], 3);
+        assert_eq!(event["activity_id"], 1);
+        assert_eq!(event["type_uid"], 300201);
+        assert!(mapped.validation.is_valid, "{:?}", mapped.validation.errors);
+    }
+
+    #[tokio::test]
+    async fn test_mapping_engine_selects_class_and_defaults() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let definition = MappingDefinition::parse(SSHD_MAPPING).unwrap();
+        let engine = MappingEngine::new(&schema, &definition).unwrap();
+
+        let record = json!({"action": "exec", "ts": "2025-01-15 10:30:00", "cmd": "ls -la", "result": "other: killed"});
+        assert_eq!(engine.select_class(&record).unwrap(), "process_activity");
+        let fields = engine.map_fields(&record).unwrap();
+        assert_eq!(fields["status_id"], 99);
+        assert_eq!(fields["status"], "other: killed");
+        assert_eq!(fields["process"]["cmd_line"], "ls -la");
+        assert!(!fields.contains_key("severity_id"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_mapping_tool_reports_per_record() {
+        let records = [
+            json!({"ts": "2025-01-15 10:30:00", "user": "bob", "result": "accepted"}),
+            json!({"ts": "not a time", "user": "carol"}),
+            json!({"ts": "2025-01-15 10:31:00", "result": "accepted"}),
+        ]
+        .iter()
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+        let request = ApplyMappingRequest {
+            version: None,
+            mapping: Some(SSHD_MAPPING.to_string()),
+            pack: None,
+            records,
+            include_validation: Some(true),
+            unmapped: None,
+        };
+        let response: Value =
+            serde_json::from_str(&apply_mapping_impl(request).await.unwrap()).unwrap();
+
+        assert_eq!(response["version"], "1.4.0");
+        assert_eq!(response["record_count"], 3);
+        assert_eq!(response["mapped_count"], 2);
+        assert_eq!(response["failed_count"], 1);
+        assert_eq!(response["invalid_count"], 1);
+        assert_eq!(response["records"][0]["event"]["severity_id"], 1);
+        assert!(response["records"][1]["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("time:"));
+        assert_eq!(response["records"][2]["validation"]["is_valid"], false);
+    }
+
+    #[tokio::test]
+    async fn test_unmapped_fields_are_kept_by_policy() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let mut definition = MappingDefinition::parse(
+            r#"
+class: authentication
+fields:
+  - { source: user.name, target: user.name }
+  - { source: ips.0, target: src_endpoint.ip }
+  - { source: claims.http://example.com/claims/upn, target: user.email_addr }
+  - { source: note, target: unmapped.note_text }
+  - { source: port, target: src_endpoint.port, when: { field: port, matches: '^\d+$' } }
+"#,
+        )
+        .unwrap();
+        let record = json!({
+            "user": {"name": "alice", "id": 42},
+            "ips": ["10.0.0.1", "10.0.0.2"],
+            "claims": {"http://example.com/claims/upn": "alice@example.com", "amr": ["pwd"]},
+            "port": "ssh",
+            "note": "kept by its rule",
+            "empty": {"inner": null},
+            "request.id": "r-1"
+        });
+
+        // By default every field no applied rule read is kept; the rule's own `unmapped` wins
+        let engine = MappingEngine::new(&schema, &definition).unwrap();
+        let mapped = engine.apply(&record).unwrap();
+        assert_eq!(mapped.unmapped_count, 5);
+        let event = serde_json::to_value(&mapped.event).unwrap();
+        assert_eq!(
+            event["unmapped"],
+            json!({
+                "user": {"id": 42},
+                "ips": [null, "10.0.0.2"],
+                "claims": {"amr": ["pwd"]},
+                "port": "ssh",
+                "request.id": "r-1",
+                "note_text": "kept by its rule"
+            })
+        );
+        assert!(event.get("raw_data").is_none());
+
+        definition.unmapped = Some(UnmappedOptions {
+            keep: UnmappedPolicy::Allowlist,
+            allow: vec![
+                "claims".to_string(),
+                "user.id".to_string(),
+                "request.id".to_string(),
+            ],
+            raw_data: true,
+        });
+        let engine = MappingEngine::new(&schema, &definition).unwrap();
+        let mapped = engine.apply(&record).unwrap();
+        assert_eq!(mapped.unmapped_count, 5);
+        let event = serde_json::to_value(&mapped.event).unwrap();
+        assert_eq!(
+            event["unmapped"],
+            json!({
+                "user": {"id": 42},
+                "claims": {"amr": ["pwd"]},
+                "request.id": "r-1",
+                "note_text": "kept by its rule"
+            })
+        );
+        assert_eq!(event["raw_data"], record.to_string());
+        let mapped = engine
+            .apply_source(&record, Some("alice 10.0.0.1"))
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&mapped.event).unwrap()["raw_data"],
+            "alice 10.0.0.1"
+        );
+
+        definition.unmapped = Some(UnmappedOptions {
+            keep: UnmappedPolicy::None,
+            ..Default::default()
+        });
+        let engine = MappingEngine::new(&schema, &definition).unwrap();
+        let mapped = engine.apply(&record).unwrap();
+        assert_eq!(mapped.unmapped_count, 5);
+        let event = serde_json::to_value(&mapped.event).unwrap();
+        assert_eq!(event["unmapped"], json!({"note_text": "kept by its rule"}));
+    }
+
+    #[tokio::test]
+    async fn test_apply_mapping_tool_keeps_raw_lines() {
+        let mapping = r#"
+class: authentication
+format: key_value
+fields:
+  - { source: user, target: user.name }
+"#;
+        let request = ApplyMappingRequest {
+            version: Some("1.4.0".to_string()),
+            mapping: Some(mapping.to_string()),
+            pack: None,
+            records: "user=alice src=10.0.0.1 sid=7\nuser=bob\n".to_string(),
+            include_validation: None,
+            unmapped: Some(UnmappedOptions {
+                keep: UnmappedPolicy::All,
+                allow: Vec::new(),
+                raw_data: true,
+            }),
+        };
+        let response: Value =
+            serde_json::from_str(&apply_mapping_impl(request).await.unwrap()).unwrap();
+        assert_eq!(response["unmapped_field_count"], 2);
+        let first = &response["records"][0];
+        assert_eq!(first["unmapped_count"], 2);
+        assert_eq!(first["event"]["raw_data"], "user=alice src=10.0.0.1 sid=7");
+        assert_eq!(
+            first["event"]["unmapped"],
+            json!({"src": "10.0.0.1", "sid": "7"})
+        );
+        let second = &response["records"][1];
+        assert_eq!(second["unmapped_count"], 0);
+        assert!(second["event"].get("unmapped").is_none());
+        assert_eq!(second["event"]["raw_data"], "user=bob");
+    }
+
+    #[tokio::test]
+    async fn test_mapping_errors() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+
+        let definition =
+            MappingDefinition::parse(r#"{"class": "no_such_class", "fields": []}"#).unwrap();
+        assert!(MappingEngine::new(&schema, &definition).is_err());
+
+        let definition = MappingDefinition::parse(
+            "class: authentication\nfields:\n  - { source: a, target: x, transforms: [{ regex: { pattern: '(' } }] }",
+        )
+        .unwrap();
+        assert!(MappingEngine::new(&schema, &definition).is_err());
+
+        let definition = MappingDefinition::parse(
+            "class: authentication\nfields:\n  - { source: a, target: user.nickname }",
+        )
+        .unwrap();
+        let engine = MappingEngine::new(&schema, &definition).unwrap();
+        let error = engine.apply(&json!({"a": "x"})).err().unwrap();
+        assert!(error.to_string().contains("user.nickname"));
+
+        // A huge index would pad the array with millions of nulls
+        let definition = MappingDefinition::parse(
+            "class: authentication\nfields:\n  - { source: a, target: observables.100000000.name }",
+        )
+        .unwrap();
+        let engine = MappingEngine::new(&schema, &definition).unwrap();
+        let error = engine.apply(&json!({"a": "x"})).err().unwrap();
+        assert!(
+            error.to_string().contains("above the limit of 255"),
+            "{error}"
+        );
+
+        assert!(
+            MappingDefinition::parse("fields: [{ target: time, transforms: [explode] }]").is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp_ms() {
+        let expected = Some(1736937000000i64);
+        assert_eq!(
+            parse_timestamp_ms(&json!(1736937000), None, None, None),
+            expected
+        );
+        assert_eq!(
+            parse_timestamp_ms(&json!("1736937000000"), None, None, None),
+            expected
+        );
+        assert_eq!(
+            parse_timestamp_ms(&json!("2025-01-15T10:30:00Z"), None, None, None),
+            expected
+        );
+        assert_eq!(
+            parse_timestamp_ms(&json!("2025-01-15T12:30:00+02:00"), None, None, None),
+            expected
+        );
+        assert_eq!(
+            parse_timestamp_ms(&json!("2025-01-15 10:30:00.000"), None, None, None),
+            expected
+        );
+        assert_eq!(
+            parse_timestamp_ms(&json!("15/Jan/2025:10:30:00 +0000"), None, None, None),
+            expected
+        );
+        assert_eq!(
+            parse_timestamp_ms(
+                &json!("15.01.2025 10:30"),
+                Some("%d.%m.%Y %H:%M"),
+                None,
+                None
+            ),
+            expected
+        );
+        assert_eq!(
+            parse_timestamp_ms(&json!("yesterday"), None, None, None),
+            None
+        );
+
+        // Syslog timestamps carry no year: only the mapping can supply it
+        let syslog = json!("Jan 15 10:30:00");
+        assert_eq!(
+            parse_timestamp_ms(&syslog, None, None, Some(2025)),
+            expected
+        );
+        assert_eq!(parse_timestamp_ms(&syslog, None, None, None), None);
+    }
+}
+
+#[cfg(test)]
+mod mapping_validation_tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    async fn validate(mapping: &str, sample_records: Option<Value>) -> Value {
+        let request = ValidateMappingRequest {
+            version: Some("1.4.0".to_string()),
+            mapping: Some(mapping.to_string()),
+            pack: None,
+            sample_records: sample_records.map(|v| v.to_string()),
+        };
+        serde_json::from_str(&validate_mapping_impl(request).await.unwrap()).unwrap()
+    }
+
+    fn messages(report: &Value, kind: &str) -> Vec<String> {
+        report[kind]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|i| i["message"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_valid_mapping_reports_full_coverage() {
+        let report = validate(
+            r#"
+class: authentication
+fields:
+  - { source: ts, target: time, transforms: [parse_timestamp] }
+  - { source: user, target: user.name }
+  - { source: outcome, target: status_id, transforms: [{ lookup: { table: { ok: 1, bad: 2 }, default: 99 } }] }
+  - { target: activity_id, value: Logon }
+  - { target: severity_id, value: 1 }
+"#,
+            Some(json!([
+                {"ts": "2025-01-15T10:30:00Z", "user": "alice", "outcome": "ok", "pid": 42},
+                {"ts": "2025-01-15T10:31:00Z", "user": "bob", "outcome": "bad", "pid": 43}
+            ])),
+        )
+        .await;
+
+        assert_eq!(report["is_valid"], true, "{report:#}");
+        assert_eq!(report["coverage_percent"], 100.0);
+        assert_eq!(
+            report["classes"][0]["required"],
+            json!(["activity_id", "severity_id", "time", "user"])
+        );
+        assert_eq!(report["unmapped_source_fields"], json!(["pid"]));
+        assert_eq!(report["source_coverage_percent"], 75.0);
+        assert!(messages(&report, "warnings").is_empty(), "{report:#}");
+    }
+
+    #[tokio::test]
+    async fn test_mapping_errors_are_reported() {
+        let report = validate(
+            r#"
+class: authentication
+class_rules:
+  - when: { field: kind, equals: proc }
+    class: process_activity
+fields:
+  - { source: ts, target: time, transforms: [{ cast: string }] }
+  - { source: user, target: user.nickname }
+  - { source: user, target: user.name.first }
+  - { source: outcome, target: status_id, transforms: [{ lookup: { table: { ok: 1, bad: 7 }, default: 99 } }] }
+  - { source: host, target: device.hostname }
+  - { source: ts, target: src_endpoint, transforms: [trim] }
+  - { sources: [a, b], target: message }
+  - { source: pid, target: process.pid, transforms: [{ regex: { pattern: "(" } }] }
+  - { target: user.uid }
+  - { source: ip, target: observables.4096.value }
+"#,
+            None,
+        )
+        .await;
+
+        assert_eq!(report["is_valid"], false);
+        let errors = messages(&report, "errors");
+        let has = |needle: &str| errors.iter().any(|e| e.contains(needle));
+        assert!(has("'nickname' is not defined in 'user'"), "{errors:#?}");
+        assert!(has("'user.name' is not an object"), "{errors:#?}");
+        assert!(has("7 is not a valid value of 'status_id'"), "{errors:#?}");
+        assert!(has("map the object's attributes instead"), "{errors:#?}");
+        assert!(has("join 'sources' with concat"), "{errors:#?}");
+        assert!(has("invalid regex"), "{errors:#?}");
+        assert!(has("index 4096 in target"), "{errors:#?}");
+        assert!(
+            has("rule needs 'source', 'sources' or 'value'"),
+            "{errors:#?}"
+        );
+
+        // Resolves in process_activity only, so it is a warning for authentication
+        let warnings = messages(&report, "warnings");
+        assert!(warnings.iter().any(|w| w
+            .contains("'time' expects timestamp_t but the rule produces a string; only RFC 3339")));
+        assert!(warnings
+            .iter()
+            .any(|w| w == "authentication: 'process' is not defined in 'authentication'"));
+        assert!(warnings
+            .iter()
+            .any(|w| w.contains("'activity_id' of 'authentication' is not mapped (defaults to 0")));
+        let process = report["classes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["class"] == "process_activity")
+            .unwrap();
+        assert_eq!(
+            process["missing"],
+            json!(["activity_id", "actor", "severity_id"])
+        );
+        assert_eq!(report["coverage_percent"], process["coverage_percent"]);
+    }
+
+    #[tokio::test]
+    async fn test_samples_are_dry_run() {
+        let report = validate(
+            "class: authentication\nfields:\n  - { source: port, target: src_endpoint.port, transforms: [{ cast: integer }] }",
+            Some(json!({"port": "ssh"})),
+        )
+        .await;
+        assert_eq!(report["is_valid"], false);
+        assert!(messages(&report, "errors")[0].starts_with("sample 0: src_endpoint.port"));
+    }
+
+    #[tokio::test]
+    async fn test_unmapped_options_are_checked() {
+        let report = validate(
+            "class: authentication\nunmapped: { keep: allowlist }\nfields:\n  - { source: user, target: user.name }",
+            None,
+        )
+        .await;
+        assert_eq!(report["is_valid"], true);
+        assert!(messages(&report, "warnings")
+            .iter()
+            .any(|m| m.contains("empty allowlist")));
+
+        let report = validate(
+            "class: authentication\nunmapped: { allow: [sid] }\nfields:\n  - { source: user, target: user.name }",
+            None,
+        )
+        .await;
+        assert!(messages(&report, "warnings")
+            .iter()
+            .any(|m| m.contains("only used with 'keep: allowlist'")));
+    }
+
+    #[tokio::test]
+    async fn test_class_scoped_rules() {
+        let mapping = r#"
+class_rules:
+  - when: { field: kind, equals: login }
+    class: authentication
+  - when: { field: kind, equals: exec }
+    class: process_activity
+skip_unmatched: true
+fields:
+  - { source: user, target: user.name, classes: [authentication] }
+  - { source: pid, target: process.pid, transforms: [{ cast: integer }], classes: [process_activity] }
+  - { source: user, target: actor.user.name, classes: [process_activity] }
+  - { source: host, target: device.hostname, classes: [http_activity] }
+"#;
+        let report = validate(
+            mapping,
+            Some(json!([
+                {"kind": "login", "user": "alice"},
+                {"kind": "exec", "user": "bob", "pid": "42"},
+                {"kind": "heartbeat"}
+            ])),
+        )
+        .await;
+
+        // Scoped rules resolve only in their own classes, and the heartbeat is skipped
+        let errors = messages(&report, "errors");
+        assert_eq!(
+            errors,
+            ["Class 'http_activity' is not one of the mapping's classes"],
+            "{report:#}"
+        );
+        let warnings = messages(&report, "warnings");
+        assert!(
+            !warnings.iter().any(|w| w.contains("is not defined")),
+            "{warnings:#?}"
+        );
+        // The device rule is scoped to a class the mapping does not produce
+        let process = report["classes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["class"] == "process_activity")
+            .unwrap();
+        assert_eq!(
+            process["missing"],
+            json!(["activity_id", "device", "severity_id", "time"])
+        );
+    }
+}
+
+#[cfg(test)]
+mod mapping_generation_tests {
+    use super::*;
+    use ocsf_mcp::ocsf::mapping::{MappingDefinition, Transform};
+    use ocsf_mcp::ocsf::parsers::LogFormat;
+    use serde_json::Value;
+
+    async fn map(sample: &str, mapping_format: Option<&str>) -> anyhow::Result<Value> {
+        let request = MapCustomRequest {
+            sample_log: sample.to_string(),
+            sample_format: None,
+            suggested_class: None,
+            version: Some("1.4.0".to_string()),
+            emit_mapping: Some(true),
+            mapping_format: mapping_format.map(str::to_string),
+            preview_count: None,
+        };
+        Ok(serde_json::from_str(&map_custom_impl(request).await?)?)
+    }
+
+    #[tokio::test]
+    async fn test_json_sample_emits_runnable_yaml_mapping() {
+        let response = map(
+            "{\"timestamp\": \"2025-01-15T10:30:00Z\", \"username\": \"john.doe\", \"src_ip\": \"192.168.1.100\", \"result\": \"Failure\"}\n\
+             {\"timestamp\": \"2025-01-15T10:31:00Z\", \"username\": \"amy\", \"src_ip\": \"10.1.1.1\", \"result\": \"Success\"}",
+            None,
+        )
+        .await
+        .unwrap();
+
+        let definition = MappingDefinition::parse(response["mapping"].as_str().unwrap()).unwrap();
+        assert_eq!(definition.format, Some(LogFormat::Json));
+        assert_eq!(definition.version.as_deref(), Some("1.4.0"));
+        let status = definition
+            .fields
+            .iter()
+            .find(|rule| rule.target == "status_id")
+            .expect("status_id rule");
+        assert!(matches!(status.transforms[0], Transform::Lookup { .. }));
+
+        let preview = response["preview"].as_array().unwrap();
+        assert_eq!(preview.len(), 2);
+        for record in preview {
+            assert_eq!(record["validation"]["is_valid"], true);
+        }
+        assert_eq!(preview[0]["event"]["user"]["name"], "john.doe");
+        assert_eq!(preview[0]["event"]["status_id"], 2);
+        assert_eq!(preview[1]["event"]["status_id"], 1);
+        assert_eq!(preview[0]["event"]["time"], 1736937000000_i64);
+    }
+
+    #[tokio::test]
+    async fn test_text_sample_emits_regex_rules() {
+        let response = map(
+            "2025-01-15 10:30:00 ERROR [auth] Failed login attempt for user 'admin' from 192.168.1.100\n\
+             2025-01-15 10:31:00 INFO [auth] Failed login attempt for user 'bob' from 10.0.0.9",
+            None,
+        )
+        .await
+        .unwrap();
+
+        let definition = MappingDefinition::parse(response["mapping"].as_str().unwrap()).unwrap();
+        assert_eq!(definition.format, Some(LogFormat::Text));
+        assert!(definition.fields.iter().all(|rule| {
+            rule.source.as_deref() == Some("message")
+                && matches!(rule.transforms.first(), Some(Transform::Regex { .. }))
+        }));
+
+        let preview = response["preview"].as_array().unwrap();
+        assert_eq!(preview.len(), 2);
+        assert_eq!(preview[1]["event"]["user"]["name"], "bob");
+        assert_eq!(preview[1]["event"]["src_endpoint"]["ip"], "10.0.0.9");
+    }
+
+    #[tokio::test]
+    async fn test_mapping_format_json_and_preview_count() {
+        let request = MapCustomRequest {
+            sample_log: "ts=1736937000 pid=4242 ppid=1 exe=/usr/bin/curl user=root\n\
+                         ts=1736937001 pid=4243 ppid=1 exe=/usr/bin/wget user=root"
+                .to_string(),
+            sample_format: None,
+            suggested_class: None,
+            version: Some("1.4.0".to_string()),
+            emit_mapping: Some(true),
+            mapping_format: Some("json".to_string()),
+            preview_count: Some(1),
+        };
+        let response: Value =
+            serde_json::from_str(&map_custom_impl(request).await.unwrap()).unwrap();
+
+        let mapping: Value = serde_json::from_str(response["mapping"].as_str().unwrap()).unwrap();
+        assert_eq!(mapping["format"], "key_value");
+        assert_eq!(mapping["class"], "process_activity");
+
+        let preview = response["preview"].as_array().unwrap();
+        assert_eq!(preview.len(), 1);
+        assert_eq!(preview[0]["event"]["process"]["pid"], 4242);
+        assert_eq!(
+            preview[0]["event"]["process"]["file"]["path"],
+            "/usr/bin/curl"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mapping_not_emitted_by_default_and_bad_format_rejected() {
+        let request = MapCustomRequest {
+            sample_log: "user=alice src=10.0.0.1".to_string(),
+            sample_format: None,
+            suggested_class: None,
+            version: Some("1.4.0".to_string()),
+            emit_mapping: None,
+            mapping_format: None,
+            preview_count: None,
+        };
+        let response: Value =
+            serde_json::from_str(&map_custom_impl(request).await.unwrap()).unwrap();
+        assert!(response.get("mapping").is_none());
+        assert!(response.get("preview").is_none());
+
+        assert!(map("user=alice src=10.0.0.1", Some("toml")).await.is_err());
+    }
+}
+
+#[cfg(test)]
+mod parser_tests {
+    use super::*;
+    use ocsf_mcp::ocsf::parsers::{detect_format, parse_input, parse_line, LogFormat};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_parse_rfc5424_syslog() {
+        let record = parse_line(
+            LogFormat::Syslog,
+            r#"<34>1 2003-10-11T22:14:15.003Z mymachine.example.com su - ID47 [exampleSDID@32473 iut="3" eventSource="Application"] 'su root' failed for lonvick"#,
+        )
+        .unwrap();
+        assert_eq!(record.fields["syslog.hostname"], "mymachine.example.com");
+        assert_eq!(record.fields["syslog.app_name"], "su");
+        assert_eq!(record.fields["syslog.msgid"], "ID47");
+        assert!(!record.fields.contains_key("syslog.procid"));
+        assert_eq!(record.fields["syslog.facility"], "auth");
+        assert_eq!(record.fields["syslog.severity"], "crit");
+        assert_eq!(
+            record.fields["syslog.sd.exampleSDID@32473.eventSource"],
+            "Application"
+        );
+        assert_eq!(record.fields["message"], "'su root' failed for lonvick");
+        assert_eq!(record.metadata["rfc"], "5424");
+        assert_eq!(record.metadata["priority"], 34);
+    }
+
+    #[test]
+    fn test_parse_rfc3164_syslog() {
+        let record = parse_line(
+            LogFormat::Syslog,
+            "<13>Oct 11 22:14:15 mymachine sshd[4242]: Accepted password for bob",
+        )
+        .unwrap();
+        assert_eq!(record.fields["syslog.timestamp"], "Oct 11 22:14:15");
+        assert_eq!(record.fields["syslog.app_name"], "sshd");
+        assert_eq!(record.fields["syslog.procid"], "4242");
+        assert_eq!(record.fields["syslog.severity"], "notice");
+        assert_eq!(record.metadata["rfc"], "3164");
+
+        // key=value pairs in the message become fields too
+        let record = parse_line(
+            LogFormat::Syslog,
+            "Jan 15 10:30:00 web01 sudo:    alice : TTY=pts/0 ; PWD=/home/alice ; USER=root ; COMMAND=/usr/bin/id",
+        )
+        .unwrap();
+        assert_eq!(record.fields["USER"], "root");
+        assert_eq!(record.fields["COMMAND"], "/usr/bin/id");
+        assert!(record.metadata.get("priority").is_none());
+
+        assert!(parse_line(LogFormat::Syslog, "not a syslog line").is_err());
+    }
+
+    #[test]
+    fn test_parse_cef() {
+        let record = parse_line(
+            LogFormat::Cef,
+            r"<134>Jan 15 10:30:00 fw01 CEF:0|Security|threat\|manager|1.0|100|worm stopped|10|src=10.0.0.1 spt=1232 msg=Detected a threat. No action needed cs1Label=Rule cs1=block\=all",
+        )
+        .unwrap();
+        assert_eq!(record.fields["cef.device_product"], "threat|manager");
+        assert_eq!(record.fields["cef.signature_id"], "100");
+        assert_eq!(record.fields["cef.severity"], "10");
+        assert_eq!(record.fields["msg"], "Detected a threat. No action needed");
+        assert_eq!(record.fields["cs1"], "block=all");
+        assert_eq!(record.fields["Rule"], "block=all");
+        assert_eq!(record.fields["syslog.hostname"], "fw01");
+        assert_eq!(record.metadata["cef_version"], "0");
+        assert_eq!(record.metadata["syslog"]["priority"], 134);
+
+        assert!(parse_line(LogFormat::Cef, "CEF:0|Vendor|Product").is_err());
+    }
+
+    #[test]
+    fn test_parse_leef() {
+        let record = parse_line(
+            LogFormat::Leef,
+            "LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=10.50.1.1\tdst=2.10.20.20\tusrName=jim",
+        )
+        .unwrap();
+        assert_eq!(record.fields["leef.vendor"], "Microsoft");
+        assert_eq!(record.fields["leef.event_id"], "15345");
+        assert_eq!(record.fields["usrName"], "jim");
+        assert_eq!(record.metadata["delimiter"], "\t");
+
+        let record = parse_line(
+            LogFormat::Leef,
+            "LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^sev=5",
+        )
+        .unwrap();
+        assert_eq!(record.fields["dst"], "10.0.0.5");
+        assert_eq!(record.fields["sev"], "5");
+        assert_eq!(record.metadata["leef_version"], "2.0");
+        assert_eq!(record.metadata["delimiter"], "^");
+
+        let record = parse_line(
+            LogFormat::Leef,
+            "LEEF:2.0|Vendor|Product|1.0|7|x7C|src=10.0.0.1|dst=10.0.0.2",
+        )
+        .unwrap();
+        assert_eq!(record.fields["dst"], "10.0.0.2");
+    }
+
+    #[test]
+    fn test_parse_key_value_and_csv() {
+        let record = parse_line(
+            LogFormat::KeyValue,
+            r#"ts=2025-01-15T10:00:00Z level=info msg="login \"ok\"" user=bob"#,
+        )
+        .unwrap();
+        let names: Vec<&str> = record.fields.keys().map(String::as_str).collect();
+        assert_eq!(names, ["ts", "level", "msg", "user"]);
+        assert_eq!(record.fields["msg"], r#"login "ok""#);
+
+        let records = parse_input(
+            "time,user,src_ip\n2025-01-15T10:00:00Z,alice,10.0.0.1\n\n2025-01-15T10:01:00Z,\"bob, jr\",10.0.0.2\n",
+            None,
+        )
+        .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].format, LogFormat::Csv);
+        assert_eq!(records[1].fields["user"], "bob, jr");
+        assert_eq!(records[1].line, 4);
+        assert_eq!(
+            records[0].metadata["columns"],
+            json!(["time", "user", "src_ip"])
+        );
+    }
+
+    #[test]
+    fn test_parse_json_array_lines() {
+        let input = "\n[\n  {\"user\": \"alice\", \"note\": \"a, {b}\"},\n  {\n    \"user\": \"bob\"\n  }, {\"user\": \"carol\"}\n]";
+        let records = parse_input(input, None).unwrap();
+        let lines: Vec<usize> = records.iter().map(|r| r.line).collect();
+        assert_eq!(lines, [3, 4, 6]);
+        assert_eq!(records[1].fields["user"], "bob");
+
+        let records = parse_input("\n\n{\"user\": \"alice\"}", None).unwrap();
+        assert_eq!(records[0].line, 3);
+    }
+
+    #[test]
+    fn test_detect_format() {
+        let cases = [
+            (r#"{"user": "bob"}"#, LogFormat::Json),
+            ("{\"a\": 1}\n{\"a\": 2}", LogFormat::Json),
+            (
+                "<13>1 2025-01-15T10:00:00Z host app 1 - - hello",
+                LogFormat::Syslog,
+            ),
+            (
+                "Jan 15 10:30:00 web01 sshd[1]: Accepted password",
+                LogFormat::Syslog,
+            ),
+            ("CEF:0|V|P|1|100|Name|5|src=10.0.0.1", LogFormat::Cef),
+            ("LEEF:1.0|V|P|1|100|src=10.0.0.1", LogFormat::Leef),
+            ("user=bob src=10.0.0.1", LogFormat::KeyValue),
+            ("a,b,c\n1,2,3\n4,5,6", LogFormat::Csv),
+            (
+                "2025-01-15 10:30:00 ERROR Failed login for user 'admin', again",
+                LogFormat::Text,
+            ),
+        ];
+        for (input, format) in cases {
+            assert_eq!(detect_format(input), format, "{input}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mapping_reads_parsed_records() {
+        let mapping = r#"
+name: firewall
+version: 1.4.0
+format: cef
+class: network_activity
+fields:
+  - { source: cef.name, target: message }
+  - { source: src, target: src_endpoint.ip }
+  - { source: spt, target: src_endpoint.port, transforms: [{ cast: integer }] }
+  - { source: syslog.hostname, target: device.hostname }
+  - { source: rt, target: time, transforms: [parse_timestamp] }
+"#;
+        let response = apply_mapping_impl(ApplyMappingRequest {
+            version: None,
+            mapping: Some(mapping.to_string()),
+            pack: None,
+            records: "<134>Jan 15 10:30:00 fw01 CEF:0|Acme|FW|1.0|100|Blocked|5|src=10.0.0.1 spt=1232 rt=1736937000000\n\
+                      CEF:0|Acme|FW|1.0|101|Allowed|1|src=10.0.0.2 spt=80 rt=1736937060000"
+                .to_string(),
+            include_validation: None,
+            unmapped: None,
+        })
+        .await
+        .unwrap();
+        let response: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["mapped_count"], 2);
+        let first = &response["records"][0]["event"];
+        assert_eq!(first["message"], "Blocked");
+        assert_eq!(first["src_endpoint"]["port"], 1232);
+        assert_eq!(first["device"]["hostname"], "fw01");
+        assert_eq!(first["time"], 1736937000000_i64);
+        assert!(response["records"][1]["event"].get("device").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_map_custom_uses_parsed_syslog() {
+        let request = MapCustomRequest {
+            sample_log: "Jan 15 10:30:00 web01 sshd[4242]: Failed password for invalid user admin from 192.168.1.100 port 52144 ssh2"
+                .to_string(),
+            sample_format: None,
+            suggested_class: Some("authentication".to_string()),
+            version: Some("1.4.0".to_string()),
+            emit_mapping: Some(true),
+            mapping_format: None,
+            preview_count: None,
+        };
+        let response: Value =
+            serde_json::from_str(&map_custom_impl(request).await.unwrap()).unwrap();
+        assert_eq!(response["sample_format"], "syslog");
+        assert_eq!(response["sample_metadata"]["rfc"], "3164");
+
+        let event = &response["preview"][0]["event"];
+        assert_eq!(event["device"]["hostname"], "web01");
+        assert_eq!(event["user"]["name"], "admin");
+        assert_eq!(event["src_endpoint"]["ip"], "192.168.1.100");
+        // The year-less timestamp parses with the year the mapping suggests
+        assert!(event["time"].is_i64());
+    }
+
+    #[test]
+    fn test_parse_zeek_tsv() {
+        let input = include_str!("fixtures/network/zeek_dns.log");
+        assert_eq!(detect_format(input), LogFormat::Zeek);
+        let records = parse_input(input, None).unwrap();
+        assert_eq!(records.len(), 2);
+        let first = &records[0];
+        assert_eq!(first.line, 9);
+        assert_eq!(first.metadata["path"], "dns");
+        assert_eq!(first.fields["_path"], "dns");
+        assert_eq!(first.fields["id.resp_p"], 53);
+        assert_eq!(first.fields["ts"], 1736937005.0);
+        assert_eq!(first.fields["RD"], true);
+        assert_eq!(
+            first.fields["answers"],
+            json!(["93.184.216.34", "93.184.216.35"])
+        );
+        assert_eq!(first.fields["TTLs"], json!([3600.0, 3600.0]));
+        // `-` is unset
+        assert!(!records[1].fields.contains_key("answers"));
+
+        // Concatenated logs switch headers; JSON lines are read as they are
+        let mixed = format!(
+            "{}{}",
+            include_str!("fixtures/network/zeek_http.log"),
+            r#"{"ts":1736937000.2,"uid":"C1","id.orig_h":"10.0.0.1","query":"example.org"}"#
+        );
+        let records = parse_input(&mixed, Some(LogFormat::Zeek)).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].fields["user_agent"], "Mozilla/5.0\t(X11)");
+        assert_eq!(records[1].fields["status_code"], 403);
+        assert_eq!(records[2].fields["id.orig_h"], "10.0.0.1");
+        assert_eq!(records[2].line, 12);
+        assert!(parse_line(LogFormat::Zeek, "1736937000.2\tC1").is_err());
+    }
+
+    #[test]
+    fn test_parse_auditd_groups_event_lines() {
+        let input = include_str!("fixtures/linux/audit.log");
+        assert_eq!(detect_format(input), LogFormat::Auditd);
+        let records = parse_input(input, None).unwrap();
+        assert_eq!(records.len(), 5);
+
+        let exec = &records[0];
+        assert_eq!(exec.line, 1);
+        assert_eq!(exec.fields["type"], "SYSCALL");
+        assert_eq!(exec.fields["node"], "web01");
+        assert_eq!(exec.fields["comm"], "curl");
+        assert_eq!(exec.fields["execve.cmd_line"], "curl -s http://example.com");
+        assert_eq!(exec.fields["cwd.cwd"], "/home/alice");
+        assert_eq!(exec.fields["path.name"], "/usr/bin/curl");
+        // Hex-encoded values are decoded, NUL separators become spaces
+        assert_eq!(
+            exec.fields["proctitle.proctitle"],
+            "curl -s http://example.com"
+        );
+        assert_eq!(
+            exec.fields["audit.records"],
+            json!(["SYSCALL", "EXECVE", "CWD", "PATH", "PROCTITLE"])
+        );
+        assert_eq!(exec.metadata["serial"], "4521");
+
+        // The parent directory stays under its item number only
+        let create = &records[1];
+        assert_eq!(create.line, 6);
+        assert_eq!(create.fields["path.0.nametype"], "PARENT");
+        assert_eq!(create.fields["path.nametype"], "CREATE");
+        assert_eq!(create.fields["path.name"], "/tmp/report.txt");
+
+        // USER_* records keep their fields inside msg='...'
+        let login = &records[2];
+        assert_eq!(login.fields["op"], "login");
+        assert_eq!(login.fields["res"], "success");
+        assert_eq!(login.fields["exe"], "/usr/sbin/sshd");
+    }
+
+    #[test]
+    fn test_parse_windows_event_xml() {
+        let input = include_str!("fixtures/windows/security.xml");
+        assert_eq!(detect_format(input), LogFormat::WindowsXml);
+        let records = parse_input(input, None).unwrap();
+        assert_eq!(records.len(), 9);
+
+        let logon = &records[0];
+        assert_eq!(logon.line, 2);
+        assert_eq!(logon.metadata["rendering"], "xml");
+        assert_eq!(logon.fields["System.EventID"], "4624");
+        assert_eq!(
+            logon.fields["System.Provider.Name"],
+            "Microsoft-Windows-Security-Auditing"
+        );
+        assert_eq!(
+            logon.fields["System.TimeCreated.SystemTime"],
+            "2025-03-10T08:15:02.4817263Z"
+        );
+        assert_eq!(logon.fields["System.Execution.ProcessID"], "744");
+        assert_eq!(logon.fields["EventData.TargetUserName"], "alice");
+        assert_eq!(logon.fields["EventData.LogonProcessName"], "User32");
+        assert_eq!(
+            logon.fields["RenderingInfo.Keywords.Keyword"],
+            "Audit Success"
+        );
+        // Empty elements and attributes are left out
+        assert!(!logon.fields.contains_key("System.Security"));
+        assert_eq!(records[1].line, 60);
+        assert!(!records[3].fields.contains_key("EventData.ProcessName"));
+
+        // One event per line, as written by `Get-WinEvent | % { $_.ToXml() }`
+        let line = r#"<Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event"><System><EventID>1102</EventID></System><UserData><LogFileCleared><SubjectUserName>alice</SubjectUserName></LogFileCleared></UserData><EventData><Data>a</Data><Data>b</Data></EventData></Event>"#;
+        let record = parse_line(LogFormat::WindowsXml, line).unwrap();
+        assert_eq!(
+            record.fields["UserData.LogFileCleared.SubjectUserName"],
+            "alice"
+        );
+        assert_eq!(record.fields["EventData.Data"], json!(["a", "b"]));
+        assert!(parse_line(LogFormat::WindowsXml, "<Event><System>").is_err());
+
+        // JSON renderings give the same fields, values as text
+        let input = include_str!("fixtures/windows/security.jsonl");
+        let records = parse_input(input, Some(LogFormat::WindowsXml)).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].metadata["rendering"], "json");
+        assert_eq!(records[0].fields["System.EventID"], "4625");
+        assert_eq!(records[0].fields["System.Execution.ProcessID"], "744");
+        assert_eq!(records[0].fields["EventData.LogonType"], "3");
+        assert!(!records[0].fields.contains_key("xmlns"));
+        // xmltodict names attributes `@Name` and keeps `Data` elements in a list
+        assert_eq!(
+            records[2].fields["System.TimeCreated.SystemTime"],
+            "2025-03-10T08:31:12.7340016Z"
+        );
+        assert_eq!(records[2].fields["EventData.SamAccountName"], "svc-backup");
+        assert_eq!(records[2].line, 3);
+    }
+}
+
+#[cfg(test)]
+mod mapping_pack_tests {
+    use super::*;
+    use serde_json::Value;
+
+    async fn apply_pack(pack: &str, records: &str) -> Value {
+        let request = ApplyMappingRequest {
+            version: None,
+            mapping: None,
+            pack: Some(pack.to_string()),
+            records: records.to_string(),
+            include_validation: Some(true),
+            unmapped: None,
+        };
+        serde_json::from_str(&apply_mapping_impl(request).await.unwrap()).unwrap()
+    }
+
+    fn events_of(response: &Value) -> Vec<&Value> {
+        response["records"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| &r["event"])
+            .collect()
+    }
+
+    fn assert_all_valid(response: &Value) {
+        assert_eq!(response["failed_count"], 0, "{response:#}");
+        assert_eq!(response["invalid_count"], 0, "{response:#}");
+    }
+
+    /// Every event carries at least the attributes of its expected counterpart
+    fn assert_matches_expected(response: &Value, expected: &str) {
+        let expected: Vec<Value> = serde_json::from_str(expected).unwrap();
+        let events = events_of(response);
+        assert_eq!(events.len(), expected.len());
+        for (index, (event, expected)) in events.iter().zip(&expected).enumerate() {
+            assert_subset(event, expected, &format!("records[{index}]"));
+        }
+    }
+
+    fn assert_subset(actual: &Value, expected: &Value, path: &str) {
+        match (actual, expected) {
+            (Value::Object(actual), Value::Object(expected)) => {
+                for (key, value) in expected {
+                    let Some(found) = actual.get(key) else {
+                        panic!("{path}.{key} is missing");
+                    };
+                    assert_subset(found, value, &format!("{path}.{key}"));
+                }
+            }
+            (Value::Array(actual), Value::Array(expected)) => {
+                assert_eq!(actual.len(), expected.len(), "{path}");
+                for (i, (actual, expected)) in actual.iter().zip(expected).enumerate() {
+                    assert_subset(actual, expected, &format!("{path}[{i}]"));
+                }
+            }
+            _ => assert_eq!(actual, expected, "{path}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sshd_pack_maps_auth_log() {
+        let response = apply_pack("sshd", include_str!("fixtures/linux/auth.log")).await;
+        assert_all_valid(&response);
+        assert_eq!(response["record_count"], 9);
+        // "Server listening" is not an authentication
+        assert_eq!(response["skipped_count"], 1);
+
+        let events = events_of(&response);
+        let accepted = events[0];
+        assert_eq!(accepted["class_uid"], 3002);
+        assert_eq!(accepted["activity_id"], 1);
+        assert_eq!(accepted["status_id"], 1);
+        assert_eq!(accepted["user"]["name"], "alice");
+        assert_eq!(accepted["src_endpoint"]["ip"], "203.0.113.10");
+        assert_eq!(accepted["src_endpoint"]["port"], 52144);
+        assert_eq!(accepted["auth_protocol"], "publickey");
+        assert_eq!(accepted["dst_endpoint"]["hostname"], "web01");
+        assert_eq!(accepted["logon_type_id"], 10);
+        assert_eq!(accepted["time"], 1736937000000_i64);
+
+        let invalid_user = events[2];
+        assert_eq!(invalid_user["status_id"], 2);
+        assert_eq!(invalid_user["status_detail"], "Failed password");
+        assert_eq!(invalid_user["user"]["name"], "admin");
+
+        let pam_failure = events[5];
+        assert_eq!(pam_failure["status_id"], 2);
+        assert_eq!(pam_failure["user"]["name"], "root");
+        assert_eq!(pam_failure["src_endpoint"]["ip"], "198.51.100.7");
+
+        let logoff = events[7];
+        assert_eq!(logoff["activity_id"], 2);
+        assert_eq!(logoff["user"]["name"], "alice");
+        assert_eq!(logoff["src_endpoint"]["ip"], "203.0.113.10");
+    }
+
+    #[tokio::test]
+    async fn test_sudo_pack_maps_commands() {
+        let response = apply_pack("sudo", include_str!("fixtures/linux/sudo.log")).await;
+        assert_all_valid(&response);
+        assert_eq!(response["mapped_count"], 3);
+        assert_eq!(response["skipped_count"], 1);
+
+        let events = events_of(&response);
+        let restart = events[0];
+        assert_eq!(restart["class_uid"], 1007);
+        assert_eq!(restart["actor"]["user"]["name"], "alice");
+        assert_eq!(
+            restart["process"]["cmd_line"],
+            "/usr/bin/systemctl restart nginx"
+        );
+        assert_eq!(restart["process"]["file"]["name"], "systemctl");
+        assert_eq!(restart["process"]["user"]["name"], "root");
+        assert_eq!(restart["process"]["working_directory"], "/home/alice");
+        assert_eq!(restart["status_id"], 1);
+
+        assert_eq!(events[1]["status_id"], 2);
+        assert_eq!(events[1]["status_detail"], "3 incorrect password attempts");
+        assert_eq!(events[2]["status_detail"], "user NOT in sudoers");
+    }
+
+    #[tokio::test]
+    async fn test_auditd_pack_maps_multi_line_events() {
+        let response = apply_pack("auditd", include_str!("fixtures/linux/audit.log")).await;
+        assert_all_valid(&response);
+        // Five events from twelve lines; SERVICE_START matches no class rule
+        assert_eq!(response["record_count"], 5);
+        assert_eq!(response["skipped_count"], 1);
+
+        let events = events_of(&response);
+        let exec = events[0];
+        assert_eq!(exec["class_uid"], 1007);
+        assert_eq!(exec["time"], 1736937000123_i64);
+        assert_eq!(exec["device"]["hostname"], "web01");
+        assert_eq!(exec["process"]["pid"], 2345);
+        assert_eq!(exec["process"]["cmd_line"], "curl -s http://example.com");
+        assert_eq!(exec["process"]["working_directory"], "/home/alice");
+        assert_eq!(exec["actor"]["user"]["uid"], "1000");
+        assert!(exec["actor"].get("process").is_none());
+
+        let create = events[1];
+        assert_eq!(create["class_uid"], 1001);
+        assert_eq!(create["activity_id"], 1);
+        assert_eq!(create["file"]["path"], "/tmp/report.txt");
+        assert_eq!(create["file"]["type_id"], 1);
+        assert_eq!(create["actor"]["process"]["name"], "touch");
+        assert!(create.get("process").is_none());
+
+        let login = events[2];
+        assert_eq!(login["class_uid"], 3002);
+        assert_eq!(login["status_id"], 1);
+        assert_eq!(login["src_endpoint"]["ip"], "203.0.113.10");
+
+        let failed = events[3];
+        assert_eq!(failed["status_id"], 2);
+        assert_eq!(failed["user"]["name"], "root");
+        // auid 4294967295 means no login uid
+        assert!(failed.get("actor").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_http_access_pack_maps_nginx_and_apache() {
+        let nginx = apply_pack(
+            "http_access",
+            include_str!("fixtures/linux/nginx_access.log"),
+        )
+        .await;
+        assert_all_valid(&nginx);
+        let nginx_events = events_of(&nginx);
+        let get = nginx_events[0];
+        assert_eq!(get["class_uid"], 4002);
+        assert_eq!(get["activity_id"], 3);
+        assert_eq!(get["time"], 1736937000000_i64);
+        assert_eq!(get["src_endpoint"]["ip"], "203.0.113.10");
+        assert_eq!(get["http_request"]["url"]["path"], "/index.html");
+        assert_eq!(get["http_request"]["url"]["query_string"], "lang=en");
+        assert_eq!(get["http_request"]["referrer"], "https://example.com/");
+        assert_eq!(get["http_response"]["code"], 200);
+        assert_eq!(get["http_response"]["length"], 612);
+        assert_eq!(get["status_id"], 1);
+        assert!(get.get("actor").is_none());
+
+        assert_eq!(nginx_events[1]["status_id"], 2);
+        assert!(nginx_events[1]["http_request"].get("referrer").is_none());
+        assert_eq!(nginx_events[2]["activity_id"], 2);
+        assert_eq!(nginx_events[2]["actor"]["user"]["name"], "alice");
+
+        let apache = apply_pack(
+            "http_access",
+            include_str!("fixtures/linux/apache_access.log"),
+        )
+        .await;
+        assert_all_valid(&apache);
+        let apache_events = events_of(&apache);
+        assert_eq!(apache_events[0]["time"], 1736935200000_i64);
+        assert_eq!(apache_events[0]["http_response"]["code"], 401);
+        assert!(apache_events[0]["http_request"].get("user_agent").is_none());
+        assert_eq!(apache_events[1]["activity_id"], 99);
+        assert!(apache_events[1]["http_response"].get("length").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_zeek_pack_maps_tsv_logs() {
+        let conn = apply_pack("zeek", include_str!("fixtures/network/zeek_conn.log")).await;
+        assert_all_valid(&conn);
+        let events = events_of(&conn);
+        assert_eq!(events.len(), 4);
+        let closed = events[0];
+        assert_eq!(closed["class_uid"], 4001);
+        assert_eq!(closed["activity_id"], 2);
+        assert_eq!(closed["time"], 1736937000123_i64);
+        assert_eq!(closed["metadata"]["log_name"], "conn");
+        assert_eq!(closed["src_endpoint"]["port"], 52144);
+        assert_eq!(closed["connection_info"]["uid"], "CHhAvVGS1DHFjwGM9");
+        assert_eq!(closed["connection_info"]["direction_id"], 2);
+        assert_eq!(closed["traffic"]["bytes_out"], 1024);
+        assert_eq!(closed["traffic"]["bytes_in"], 4096);
+        assert_eq!(events[1]["connection_info"]["direction_id"], 3);
+        // S0: no reply; unset columns are left out
+        assert_eq!(events[2]["activity_id"], 4);
+        assert!(events[2]["traffic"].get("bytes_out").is_none());
+        assert_eq!(events[3]["activity_id"], 5);
+
+        let dns = apply_pack("zeek", include_str!("fixtures/network/zeek_dns.log")).await;
+        assert_all_valid(&dns);
+        let events = events_of(&dns);
+        assert_eq!(events[0]["class_uid"], 4003);
+        assert_eq!(events[0]["query"]["hostname"], "example.com");
+        assert_eq!(events[0]["query"]["type"], "A");
+        assert_eq!(
+            events[0]["answers"],
+            serde_json::json!([{"rdata": "93.184.216.34"}, {"rdata": "93.184.216.35"}])
+        );
+        assert_eq!(events[1]["rcode_id"], 3);
+        assert!(events[1].get("answers").is_none());
+
+        let http = apply_pack("zeek", include_str!("fixtures/network/zeek_http.log")).await;
+        assert_all_valid(&http);
+        let events = events_of(&http);
+        assert_eq!(events[0]["class_uid"], 4002);
+        assert_eq!(events[0]["http_request"]["url"]["path"], "/index.html");
+        assert_eq!(events[0]["http_request"]["url"]["query_string"], "lang=en");
+        assert_eq!(
+            events[0]["http_request"]["user_agent"],
+            "Mozilla/5.0\t(X11)"
+        );
+        assert_eq!(events[1]["activity_id"], 6);
+        assert_eq!(events[1]["http_response"]["code"], 403);
+        assert_eq!(events[1]["status_id"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_zeek_pack_maps_json_logs() {
+        // No `_path`: the class comes from the fields
+        let ssl = apply_pack("zeek", include_str!("fixtures/network/zeek_ssl.json")).await;
+        assert_all_valid(&ssl);
+        let events = events_of(&ssl);
+        assert_eq!(events[0]["class_uid"], 4001);
+        assert_eq!(events[0]["tls"]["version"], "TLSv13");
+        assert_eq!(events[0]["tls"]["sni"], "example.com");
+        assert_eq!(events[0]["time"], 1736937000200_i64);
+
+        let conn = apply_pack("zeek", include_str!("fixtures/network/zeek_conn.json")).await;
+        assert_all_valid(&conn);
+        let events = events_of(&conn);
+        assert_eq!(events[0]["metadata"]["log_name"], "conn");
+        assert_eq!(events[0]["time"], 1736937000123_i64);
+        assert_eq!(events[0]["app_name"], "ssl");
+        assert!(events[1]["metadata"].get("log_name").is_none());
+        assert_eq!(events[1]["activity_id"], 5);
+        assert_eq!(events[1]["connection_info"]["direction_id"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_suricata_pack_maps_eve() {
+        let response = apply_pack(
+            "suricata",
+            include_str!("fixtures/network/suricata_eve.json"),
+        )
+        .await;
+        assert_all_valid(&response);
+        assert_eq!(response["record_count"], 8);
+        // `stats` is not an event
+        assert_eq!(response["skipped_count"], 1);
+        let events = events_of(&response);
+        let classes: Vec<&Value> = events.iter().map(|e| &e["class_uid"]).collect();
+        assert_eq!(classes, [2004, 2004, 4001, 4003, 4003, 4002, 4001]);
+
+        let scan = events[0];
+        assert_eq!(scan["time"], 1736937000123_i64);
+        assert_eq!(scan["finding_info"]["title"], "ET SCAN Potential SSH Scan");
+        assert_eq!(scan["finding_info"]["uid"], "1234567890123456:1:2001219");
+        assert_eq!(scan["finding_info"]["analytic"]["uid"], "2001219");
+        assert_eq!(scan["severity_id"], 3);
+        assert_eq!(scan["action_id"], 1);
+        assert_eq!(scan["evidences"][0]["src_endpoint"]["ip"], "203.0.113.50");
+        assert_eq!(scan["evidences"][0]["dst_endpoint"]["port"], 22);
+        assert!(scan.get("src_endpoint").is_none());
+        let blocked = events[1];
+        assert_eq!(blocked["severity_id"], 4);
+        assert_eq!(blocked["disposition_id"], 2);
+        assert_eq!(
+            blocked["evidences"][0]["http_request"]["url"]["path"],
+            "/gate.php"
+        );
+
+        let flow = events[2];
+        assert_eq!(flow["traffic"]["bytes_out"], 82);
+        assert_eq!(flow["end_time"], 1736937005012_i64);
+        assert_eq!(flow["connection_info"]["protocol_num"], 17);
+
+        assert_eq!(events[3]["activity_id"], 1);
+        let answer = events[4];
+        assert_eq!(answer["activity_id"], 2);
+        assert_eq!(
+            answer["answers"],
+            serde_json::json!([{"rdata": "93.184.216.34", "type": "A", "ttl": 3600}])
+        );
+        assert_eq!(answer["rcode_id"], 0);
+
+        assert_eq!(events[5]["http_response"]["code"], 200);
+        assert_eq!(events[5]["http_request"]["version"], "HTTP/1.1");
+
+        let tls = &events[6]["tls"];
+        assert_eq!(tls["version"], "TLS 1.3");
+        assert_eq!(tls["ja3_hash"]["value"], "e7d705a3286e19ea42f587b344ee6865");
+        assert_eq!(tls["certificate"]["fingerprints"][0]["algorithm_id"], 2);
+        assert_eq!(tls["certificate"]["expiration_time"], 1740873599000_i64);
+    }
+
+    #[tokio::test]
+    async fn test_cloudtrail_pack_maps_log_file() {
+        let log_file = include_str!("fixtures/cloud/cloudtrail.json");
+        let response = apply_pack("cloudtrail", log_file).await;
+        assert_all_valid(&response);
+        assert_eq!(response["record_count"], 8);
+        assert_matches_expected(
+            &response,
+            include_str!("fixtures/cloud/cloudtrail.expected.json"),
+        );
+        let events = events_of(&response);
+        let classes: Vec<&Value> = events.iter().map(|e| &e["class_uid"]).collect();
+        assert_eq!(classes, [6003, 6003, 6003, 6003, 3002, 3002, 3001, 3001]);
+        assert_eq!(
+            events[3]["src_endpoint"]["svc_name"],
+            "cloudtrail.amazonaws.com"
+        );
+
+        // The same records as NDJSON, outside the `Records` envelope
+        let log: Value = serde_json::from_str(log_file).unwrap();
+        let lines: Vec<String> = log["Records"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r.to_string())
+            .collect();
+        let ndjson = apply_pack("cloudtrail", &lines.join("\n")).await;
+        assert_eq!(ndjson["mapped_count"], 8);
+        assert_eq!(events_of(&ndjson), events);
+    }
+
+    #[tokio::test]
+    async fn test_azure_activity_pack_maps_export() {
+        let response = apply_pack(
+            "azure_activity",
+            include_str!("fixtures/cloud/azure_activity.json"),
+        )
+        .await;
+        assert_all_valid(&response);
+        assert_eq!(response["record_count"], 7);
+        // Service health notices are not audit events
+        assert_eq!(response["skipped_count"], 1);
+        assert_matches_expected(
+            &response,
+            include_str!("fixtures/cloud/azure_activity.expected.json"),
+        );
+        // Claim names contain dots
+        assert_eq!(
+            events_of(&response)[0]["actor"]["user"]["name"],
+            "alice@contoso.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gcp_audit_pack_maps_entries() {
+        let response = apply_pack("gcp_audit", include_str!("fixtures/cloud/gcp_audit.json")).await;
+        assert_all_valid(&response);
+        assert_eq!(response["record_count"], 8);
+        // A plain container log entry
+        assert_eq!(response["skipped_count"], 1);
+        assert_matches_expected(
+            &response,
+            include_str!("fixtures/cloud/gcp_audit.expected.json"),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_k8s_audit_pack_maps_audit_log() {
+        let response = apply_pack("k8s_audit", include_str!("fixtures/k8s/audit.log")).await;
+        assert_all_valid(&response);
+        assert_eq!(response["record_count"], 8);
+        // The RequestReceived stage of a request logged again at ResponseComplete
+        assert_eq!(response["skipped_count"], 1);
+        assert_matches_expected(&response, include_str!("fixtures/k8s/audit.expected.json"));
+
+        let events = events_of(&response);
+        assert!(events.iter().all(|e| e["class_uid"] == 6003));
+        assert!(events
+            .iter()
+            .all(|e| e["metadata"]["profiles"] == serde_json::json!(["container"])));
+        let from_pod = events[1];
+        assert_eq!(
+            from_pod["src_endpoint"]["container"]["name"],
+            "prometheus-k8s-0"
+        );
+        assert_eq!(from_pod["src_endpoint"]["hostname"], "worker-3");
+        assert_eq!(from_pod["resources"][0]["namespace"], "kube-system");
+        assert_eq!(from_pod["actor"]["authorizations"][0]["decision"], "Denied");
+        assert_eq!(from_pod["status_id"], 2);
+        let created = events[2];
+        assert_eq!(
+            created["api"]["request"]["containers"][1]["image"]["name"],
+            "envoyproxy/envoy:v1.29.1"
+        );
+        assert_eq!(created["dst_endpoint"]["name"], "prod-eu-1");
+        assert_eq!(events[3]["resources"][0]["type"], "pods/exec");
+        // Non-resource URLs have no object
+        assert!(events[6].get("resources").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_windows_security_pack_maps_rendered_xml() {
+        let response = apply_pack(
+            "windows_security",
+            include_str!("fixtures/windows/security.xml"),
+        )
+        .await;
+        assert_all_valid(&response);
+        assert_eq!(response["record_count"], 9);
+        // Object access on a registry key is not file activity
+        assert_eq!(response["skipped_count"], 1);
+        assert_matches_expected(
+            &response,
+            include_str!("fixtures/windows/security.expected.json"),
+        );
+
+        let events = events_of(&response);
+        let classes: Vec<&Value> = events.iter().map(|e| &e["class_uid"]).collect();
+        assert_eq!(classes, [3002, 3002, 1007, 1001, 1001, 3001, 3001, 3002]);
+        let failed = events[1];
+        assert_eq!(failed["status_id"], 2);
+        assert_eq!(failed["status_detail"], "Wrong password");
+        assert_eq!(failed["logon_type_id"], 3);
+        // The subject of a failed network logon is "-"
+        assert!(failed.get("actor").is_none());
+        let launch = events[2];
+        // Process ids are hex
+        assert_eq!(launch["process"]["pid"], 6700);
+        assert_eq!(launch["process"]["parent_process"]["pid"], 3916);
+        assert_eq!(launch["process"]["integrity_id"], 4);
+        assert_eq!(events[4]["activity_id"], 4);
+        assert_eq!(events[5]["user_result"]["full_name"], "Backup Service");
+
+        // JSON renderings of the same events map the same
+        let json = apply_pack(
+            "windows_security",
+            include_str!("fixtures/windows/security.jsonl"),
+        )
+        .await;
+        assert_all_valid(&json);
+        let expected: Vec<Value> =
+            serde_json::from_str(include_str!("fixtures/windows/security.expected.json")).unwrap();
+        for (event, index) in events_of(&json).iter().zip([1, 2, 5]) {
+            assert_subset(event, &expected[index], &format!("records[{index}]"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_packs_validate_against_schema() {
+        let listing: Value = serde_json::from_str(
+            &validate_mapping_impl(ValidateMappingRequest {
+                version: None,
+                mapping: None,
+                pack: None,
+                sample_records: None,
+            })
+            .await
+            .unwrap(),
+        )
+        .unwrap();
+        let names: Vec<&str> = listing
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "sshd",
+                "sudo",
+                "auditd",
+                "http_access",
+                "zeek",
+                "suricata",
+                "cloudtrail",
+                "azure_activity",
+                "gcp_audit",
+                "k8s_audit",
+                "windows_security"
+            ]
+        );
+        assert_eq!(
+            listing[2]["classes"],
+            serde_json::json!(["process_activity", "authentication", "file_activity"])
+        );
+
+        for (pack, fixture) in [
+            ("sshd", include_str!("fixtures/linux/auth.log")),
+            ("sudo", include_str!("fixtures/linux/sudo.log")),
+            ("auditd", include_str!("fixtures/linux/audit.log")),
+            (
+                "http_access",
+                include_str!("fixtures/linux/nginx_access.log"),
+            ),
+            ("cloudtrail", include_str!("fixtures/cloud/cloudtrail.json")),
+            (
+                "azure_activity",
+                include_str!("fixtures/cloud/azure_activity.json"),
+            ),
+            ("gcp_audit", include_str!("fixtures/cloud/gcp_audit.json")),
+            ("k8s_audit", include_str!("fixtures/k8s/audit.log")),
+            (
+                "windows_security",
+                include_str!("fixtures/windows/security.xml"),
+            ),
+        ] {
+            let report: Value = serde_json::from_str(
+                &validate_mapping_impl(ValidateMappingRequest {
+                    version: None,
+                    mapping: None,
+                    pack: Some(pack.to_string()),
+                    sample_records: Some(fixture.to_string()),
+                })
+                .await
+                .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(report["is_valid"], true, "{pack}: {report:#}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unknown_pack_lists_available() {
+        let error = apply_mapping_impl(ApplyMappingRequest {
+            version: None,
+            mapping: None,
+            pack: Some("iis".to_string()),
+            records: String::new(),
+            include_validation: None,
+            unmapped: None,
+        })
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("Unknown pack 'iis'"), "{error}");
+        assert!(
+            error.contains("sshd, sudo, auditd, http_access, zeek"),
+            "{error}"
+        );
+    }
+}
+
+#[cfg(test)]
+mod conversion_tests {
+    use super::*;
+    use ocsf_mcp::ocsf::conversion::{convert_stream, ConvertOptions, RecordReader};
+    use ocsf_mcp::ocsf::mapping::MappingDefinition;
+    use ocsf_mcp::ocsf::parsers::{parse_input, LogFormat};
+    use serde_json::Value;
+    use std::io::{Read, Write};
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ocsf-convert-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_record_reader_matches_whole_input_parsing() {
+        let cases = [
+            (LogFormat::Syslog, include_str!("fixtures/linux/auth.log")),
+            (LogFormat::Auditd, include_str!("fixtures/linux/audit.log")),
+            (
+                LogFormat::Zeek,
+                include_str!("fixtures/network/zeek_dns.log"),
+            ),
+            (
+                LogFormat::WindowsXml,
+                include_str!("fixtures/windows/security.xml"),
+            ),
+            (
+                LogFormat::WindowsXml,
+                include_str!("fixtures/windows/security.jsonl"),
+            ),
+        ];
+        for (format, input) in cases {
+            let expected = parse_input(input, Some(format)).unwrap();
+            let streamed: Vec<_> = RecordReader::new(input.as_bytes(), format, None)
+                .map(Result::unwrap)
+                .collect();
+            assert_eq!(streamed.len(), expected.len(), "{format:?}");
+            for (streamed, expected) in streamed.iter().zip(&expected) {
+                assert_eq!(streamed.line, expected.line, "{format:?}");
+                assert_eq!(streamed.record, expected.to_record(), "{format:?}");
+                assert_eq!(streamed.raw.as_deref(), Some(expected.raw.as_str()));
+            }
+        }
+
+        // Events sharing lines with each other and with the root element
+        let xml = include_str!("fixtures/windows/security.xml");
+        let events: Vec<&str> = xml
+            .split("<Event ")
+            .skip(1)
+            .map(|e| &e[..e.rfind("</Event>").unwrap() + "</Event>".len()])
+            .collect();
+        let packed = format!(
+            "<?xml version=\"1.0\"?><Events><Event {}<Event {}\n</Events>",
+            events[0].replace('\n', ""),
+            events[1..].join("<Event ")
+        );
+        let streamed: Vec<_> = RecordReader::new(packed.as_bytes(), LogFormat::WindowsXml, None)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(streamed.len(), events.len());
+        assert_eq!(streamed[0].line, 1);
+        assert_eq!(streamed[1].line, 1);
+        assert_eq!(streamed[1].record["System.EventID"], "4625");
+    }
+
+    #[test]
+    fn test_record_reader_reports_bad_records_and_goes_on() {
+        let input = "{\"a\": 1}\n{\"a\": \n\n[{\"a\": 2}, {\"a\": 3}]\n{\n  \"Records\": [\n    {\"a\": 4}\n  ]\n}\n";
+        let records: Vec<_> = RecordReader::new(
+            input.as_bytes(),
+            LogFormat::Json,
+            Some("Records".to_string()),
+        )
+        .collect();
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[0].as_ref().unwrap().record["a"], 1);
+        let error = records[1].as_ref().unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(
+            error.message.starts_with("Invalid JSON"),
+            "{}",
+            error.message
+        );
+        assert_eq!(records[3].as_ref().unwrap().record["a"], 3);
+        let last = records[4].as_ref().unwrap();
+        assert_eq!((last.line, &last.record["a"]), (5, &Value::from(4)));
+        assert!(last.raw.is_none());
+
+        let input = "time,user\n2025-01-01T00:00:00Z,alice\n\n\"unterminated,bob\n2025-01-01T00:00:02Z,carol\n";
+        let records: Vec<_> = RecordReader::new(input.as_bytes(), LogFormat::Csv, None).collect();
+        let users: Vec<_> = records
+            .iter()
+            .filter_map(|r| r.as_ref().ok())
+            .map(|r| (r.line, r.record["user"].clone()))
+            .collect();
+        assert_eq!(users[0], (2, Value::from("alice")));
+        assert_eq!(users.last().unwrap(), &(5, Value::from("carol")));
+        assert_eq!(
+            records.last().unwrap().as_ref().unwrap().raw.as_deref(),
+            Some("2025-01-01T00:00:02Z,carol")
+        );
+    }
+
+    #[test]
+    fn test_record_reader_skips_oversized_records() {
+        let records_line = format!("{{\"Records\": [{}]}}", vec!["{\"a\": 0}"; 20].join(", "));
+        let pretty = format!(
+            "{{\n  \"Records\": [\n{}\n  ]\n}}",
+            vec!["    {\"a\": 0},"; 20].join("\n")
+        );
+        let input = format!("{{\"a\": 1}}\n{records_line}\n{{\"a\": 2}}\n{pretty}\n{{\"a\": 3}}\n");
+        let records: Vec<_> = RecordReader::new(
+            input.as_bytes(),
+            LogFormat::Json,
+            Some("Records".to_string()),
+        )
+        .with_max_record_bytes(100)
+        .collect();
+
+        let outcome: Vec<_> = records
+            .iter()
+            .map(|r| match r {
+                Ok(record) => (record.line, record.record["a"].to_string()),
+                Err(error) => (error.line, error.message.clone()),
+            })
+            .collect();
+        assert_eq!(outcome.len(), 5, "{outcome:?}");
+        assert_eq!(outcome[0], (1, "1".to_string()));
+        assert_eq!(outcome[1].0, 2);
+        assert!(
+            outcome[1].1.contains("larger than 100 bytes"),
+            "{outcome:?}"
+        );
+        assert_eq!(outcome[2], (3, "2".to_string()));
+        assert_eq!(outcome[3].0, 4);
+        assert!(
+            outcome[3].1.contains("larger than 100 bytes"),
+            "{outcome:?}"
+        );
+        assert_eq!(outcome[4], (28, "3".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_convert_stream_counts_records() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let definition = MappingDefinition::parse(
+            r#"
+format: key_value
+class_rules:
+  - { when: { field: action, equals: login }, class: authentication }
+skip_unmatched: true
+fields:
+  - { source: ts, target: time, transforms: [parse_timestamp] }
+  - { source: user, target: user.name }
+  - { target: severity_id, value: 1 }
+  - { target: activity_id, value: 1 }
+  - { target: metadata.product.name, value: test }
+"#,
+        )
+        .unwrap();
+        let input = "ts=2025-01-01T00:00:00Z action=login user=alice src=10.0.0.1\n\
+                     ts=2025-01-01T00:00:01Z action=logout user=alice\n\
+                     ts=yesterday action=login user=bob\n\
+                     action=login user=carol\n";
+        let mut output = Vec::new();
+        let summary = convert_stream(
+            &schema,
+            &definition,
+            input.as_bytes(),
+            &mut output,
+            &ConvertOptions {
+                skip_invalid: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(summary.line_count, 4);
+        assert_eq!(summary.record_count, 4);
+        assert_eq!(summary.skipped_count, 1);
+        assert_eq!(summary.failed_count, 1);
+        assert_eq!(summary.errors[0].line, 3);
+        assert_eq!(summary.converted_count, 2);
+        assert_eq!(summary.invalid_count, 1, "carol has no time");
+        assert_eq!(summary.written_count, 1);
+        assert_eq!(summary.per_class["authentication"], 2);
+        assert_eq!(summary.unmapped_field_count, 3, "src, and action twice");
+
+        let written = String::from_utf8(output).unwrap();
+        assert_eq!(written.lines().count(), 1);
+        let event: Value = serde_json::from_str(written.lines().next().unwrap()).unwrap();
+        assert_eq!(event["user"]["name"], "alice");
+        assert_eq!(event["unmapped"]["src"], "10.0.0.1");
+    }
+
+    #[tokio::test]
+    async fn test_convert_log_file_tool_handles_compression() {
+        let auth_log = include_str!("fixtures/linux/auth.log");
+        let input = temp_path("auth.log.gz");
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(auth_log.as_bytes()).unwrap();
+        std::fs::write(&input, encoder.finish().unwrap()).unwrap();
+        let output = temp_path("auth.ocsf.ndjson.zst");
+
+        let request = ConvertLogFileRequest {
+            version: None,
+            mapping: None,
+            pack: Some("sshd".to_string()),
+            input_path: input.to_string_lossy().to_string(),
+            output_path: output.to_string_lossy().to_string(),
+            skip_invalid: None,
+            unmapped: None,
+            max_errors: None,
+        };
+        let response: Value =
+            serde_json::from_str(&convert_log_file_impl(request).await.unwrap()).unwrap();
+        let mut written = String::new();
+        zstd::stream::read::Decoder::new(std::fs::File::open(&output).unwrap())
+            .unwrap()
+            .read_to_string(&mut written)
+            .unwrap();
+        std::fs::remove_file(&input).unwrap();
+        std::fs::remove_file(&output).unwrap();
+
+        let applied: Value = serde_json::from_str(
+            &apply_mapping_impl(ApplyMappingRequest {
+                version: None,
+                mapping: None,
+                pack: Some("sshd".to_string()),
+                records: auth_log.to_string(),
+                include_validation: None,
+                unmapped: None,
+            })
+            .await
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(response["mapping"], "sshd");
+        assert_eq!(response["format"], "syslog");
+        assert_eq!(response["input_compression"], "gzip");
+        assert_eq!(response["line_count"], auth_log.lines().count());
+        assert_eq!(response["converted_count"], applied["mapped_count"]);
+        assert_eq!(response["skipped_count"], applied["skipped_count"]);
+        assert_eq!(response["invalid_count"], 0);
+        assert_eq!(response["failed_count"], 0);
+        assert_eq!(written.lines().count(), response["written_count"]);
+        let first: Value = serde_json::from_str(written.lines().next().unwrap()).unwrap();
+        let mut expected = applied["records"][0]["event"].clone();
+        expected["metadata"]["uid"] = first["metadata"]["uid"].clone();
+        assert_eq!(first, expected);
+    }
+
+    #[tokio::test]
+    async fn test_convert_log_file_refuses_to_overwrite_its_input() {
+        let input = temp_path("same.log");
+        std::fs::write(&input, "user=alice\n").unwrap();
+        let request = ConvertLogFileRequest {
+            version: Some("1.4.0".to_string()),
+            mapping: Some("class: authentication\nformat: key_value\nfields: []".to_string()),
+            pack: None,
+            input_path: input.to_string_lossy().to_string(),
+            output_path: input.to_string_lossy().to_string(),
+            skip_invalid: None,
+            unmapped: None,
+            max_errors: None,
+        };
+        let error = convert_log_file_impl(request)
+            .await
+            .unwrap_err()
+            .to_string();
+        let kept = std::fs::read_to_string(&input).unwrap();
+        std::fs::remove_file(&input).unwrap();
+        assert!(error.contains("input file"), "{error}");
+        assert_eq!(kept, "user=alice\n");
+    }
+}
+
+#[cfg(test)]
+mod file_validation_tests {
+    use super::*;
+    use ocsf_mcp::ocsf::bulk::{BulkGenerator, BulkOptions};
+    use ocsf_mcp::ocsf::conversion::MAX_RECORD_BYTES;
+    use ocsf_mcp::ocsf::file_validation::{validate_reader, FileValidationOptions};
+    use ocsf_mcp::ocsf::report_formats::ReportFormat;
+    use serde_json::Value;
+    use std::io::Write;
+
+    /// Twelve generated events: every third without `time`, every fourth with a wrong `type_uid`
+    async fn events() -> (OcsfSchema, Vec<Value>) {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let options = BulkOptions {
+            classes: vec!["authentication".to_string(), "file_activity".to_string()],
+            count: 12,
+            seed: 11,
+            ..Default::default()
+        };
+        let mut events: Vec<Value> = BulkGenerator::new(&schema, options)
+            .unwrap()
+            .map(|e| serde_json::to_value(e.unwrap()).unwrap())
+            .collect();
+        for (i, event) in events.iter_mut().enumerate() {
+            if i % 3 == 2 {
+                event.as_object_mut().unwrap().remove("time");
+            }
+            if i % 4 == 3 {
+                event["type_uid"] = Value::from(1);
+            }
+        }
+        (schema, events)
+    }
+
+    #[tokio::test]
+    async fn test_validate_ndjson_aggregates_errors() {
+        let (schema, events) = events().await;
+        let mut lines: Vec<String> = events.iter().map(Value::to_string).collect();
+        lines.insert(5, String::new());
+        lines.push("{not json".to_string());
+        let report = validate_reader(
+            &schema,
+            lines.join("\n").as_bytes(),
+            &FileValidationOptions { max_examples: 2 },
+        )
+        .unwrap();
+
+        // Events 2, 3, 5, 7, 8, 11 are broken, plus the last line
+        assert_eq!(report.event_count, 13);
+        assert_eq!(report.invalid_count, 7);
+        assert_eq!(report.valid_count, 6);
+        assert_eq!(report.pass_rate, 46.15);
+        assert_eq!(report.errors_by_rule["InvalidValue"], 3);
+        assert_eq!(report.errors_by_rule["InvalidJson"], 1);
+        assert_eq!(report.errors_by_field["type_uid"], 3);
+        assert!(report.errors_by_field["time"] >= 4);
+        assert_eq!(
+            report.errors_by_class.values().sum::<usize>(),
+            report.error_count
+        );
+        assert_eq!(report.errors_by_class["unknown"], 1);
+
+        let examples = &report.examples["InvalidValue"];
+        assert_eq!(examples.len(), 2);
+        assert_eq!((examples[0].index, examples[0].line), (3, Some(4)));
+        assert_eq!(
+            (examples[1].index, examples[1].line),
+            (7, Some(9)),
+            "after the blank line"
+        );
+        let invalid_json = &report.examples["InvalidJson"][0];
+        assert_eq!((invalid_json.index, invalid_json.line), (12, Some(14)));
+    }
+
+    #[tokio::test]
+    async fn test_validate_file_tool_reads_compressed_json_arrays() {
+        let (_, events) = events().await;
+        let path =
+            std::env::temp_dir().join(format!("ocsf-validate-{}.json.gz", std::process::id()));
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(serde_json::to_string_pretty(&events).unwrap().as_bytes())
+            .unwrap();
+        std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+        let request = ValidateFileRequest {
+            version: Some("1.4.0".to_string()),
+            input_path: path.to_string_lossy().to_string(),
+            max_examples: None,
+            format: None,
+        };
+        let response: Value =
+            serde_json::from_str(&validate_file_impl(request).await.unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(response["event_count"], 12);
+        assert_eq!(response["invalid_count"], 6);
+        assert_eq!(response["pass_rate"], 50.0);
+        let examples = response["examples"]["InvalidValue"].as_array().unwrap();
+        assert_eq!(examples.len(), 3);
+        assert_eq!(examples[0]["index"], 3);
+        assert!(examples[0].get("line").is_none());
+        assert!(examples[0]["event_class"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_validate_caps_event_size() {
+        let (schema, events) = events().await;
+        let huge = format!(r#"{{"message": "{}"}}"#, "x".repeat(MAX_RECORD_BYTES));
+        let options = FileValidationOptions::default();
+
+        let ndjson = format!("{}\n{}\n{}", events[0], huge, events[1]);
+        let report = validate_reader(&schema, ndjson.as_bytes(), &options).unwrap();
+        assert_eq!((report.event_count, report.valid_count), (3, 2));
+        let skipped = &report.examples["InvalidJson"][0];
+        assert_eq!((skipped.index, skipped.line), (1, Some(2)));
+        assert!(
+            skipped.message.contains("larger than"),
+            "{}",
+            skipped.message
+        );
+
+        // Array items are split without parsing, so brackets and commas in strings stay put
+        let mut tricky = events[1].clone();
+        tricky["message"] = Value::from(r#"a "quoted" ], {"not": [an, item"#);
+        let array = format!("[\n  {},\n  {},\n  {}\n]", events[0], huge, tricky);
+        let report = validate_reader(&schema, array.as_bytes(), &options).unwrap();
+        assert_eq!((report.event_count, report.valid_count), (3, 2));
+        assert_eq!(report.examples["InvalidJson"][0].index, 1);
+
+        let report = validate_reader(&schema, "[]".as_bytes(), &options).unwrap();
+        assert_eq!(report.event_count, 0);
+        assert!(validate_reader(&schema, "[{}, {}".as_bytes(), &options).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validation_reports_in_ci_formats() {
+        let (_, events) = events().await;
+        let mut lines: Vec<String> = events[..4].iter().map(Value::to_string).collect();
+        lines.push("{not json".to_string());
+        let path =
+            std::env::temp_dir().join(format!("ocsf-validate-{}.ndjson", std::process::id()));
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let input_path = path.to_string_lossy().to_string();
+        let validate = |format: &str| {
+            let request = ValidateFileRequest {
+                version: Some("1.4.0".to_string()),
+                input_path: input_path.clone(),
+                max_examples: None,
+                format: Some(serde_json::from_value(Value::from(format)).unwrap()),
+            };
+            validate_file_impl(request)
+        };
+        let sarif: Value = serde_json::from_str(&validate("sarif").await.unwrap()).unwrap();
+        let junit = validate("junit").await.unwrap();
+        let json_lines = validate("json_lines").await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["artifacts"][0]["location"]["uri"], input_path.as_str());
+        let results = run["results"].as_array().unwrap();
+        let type_uid = results
+            .iter()
+            .find(|r| r["ruleId"] == "InvalidValue")
+            .unwrap();
+        assert_eq!(type_uid["level"], "error");
+        let location = &type_uid["locations"][0];
+        assert_eq!(location["physicalLocation"]["region"]["startLine"], 4);
+        assert_eq!(
+            location["logicalLocations"][0]["fullyQualifiedName"],
+            "/type_uid"
+        );
+        let missing_time = results
+            .iter()
+            .find(|r| r["ruleId"] == "MissingRequired")
+            .unwrap();
+        assert_eq!(
+            missing_time["locations"][0]["properties"]["jsonPointer"],
+            "/time"
+        );
+        assert_eq!(missing_time["locations"][0]["properties"]["eventIndex"], 2);
+        let invalid_json = results
+            .iter()
+            .find(|r| r["ruleId"] == "InvalidJson")
+            .unwrap();
+        assert_eq!(
+            invalid_json["locations"][0]["physicalLocation"]["region"]["startLine"],
+            5
+        );
+        let rules: Vec<&Value> = run["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| &r["id"])
+            .collect();
+        assert!(results.iter().all(|r| rules.contains(&&r["ruleId"])));
+
+        let document = roxmltree::Document::parse(&junit).unwrap();
+        let suite = document
+            .descendants()
+            .find(|n| n.has_tag_name("testsuite"))
+            .unwrap();
+        assert_eq!(suite.attribute("tests"), Some("5"));
+        assert_eq!(suite.attribute("failures"), Some("3"));
+        let cases: Vec<_> = suite
+            .children()
+            .filter(|n| n.has_tag_name("testcase"))
+            .collect();
+        assert_eq!(cases.len(), 5);
+        assert_eq!(cases[0].attribute("name"), Some("event 0 (line 1)"));
+        assert!(cases[0].children().all(|n| !n.has_tag_name("failure")));
+        let failure = cases[3]
+            .children()
+            .find(|n| n.has_tag_name("failure"))
+            .unwrap();
+        assert_eq!(failure.attribute("type"), Some("InvalidValue"));
+        assert!(failure.text().unwrap().starts_with("/type_uid (1:"));
+
+        let json_lines: Vec<Value> = json_lines
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(json_lines.len(), 5);
+        assert_eq!(json_lines[0]["is_valid"], true);
+        assert_eq!(json_lines[2]["errors"][0]["field"], "time");
+        assert_eq!(json_lines[4]["line"], 5);
+        assert!(json_lines[4]["error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_validate_event_in_sarif() {
+        let (_, events) = events().await;
+        let event_json = serde_json::to_string_pretty(&events[3]).unwrap();
+        let request = ValidateEventRequest {
+            version: Some("1.4.0".to_string()),
+            event_json: event_json.clone(),
+            json_schema_cross_check: None,
+            format: Some(ReportFormat::Sarif),
+        };
+        let sarif: Value =
+            serde_json::from_str(&validate_event_impl(request).await.unwrap()).unwrap();
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], "InvalidValue");
+        // No file to point at, but the position within the event is kept
+        let physical = &result["locations"][0]["physicalLocation"];
+        assert!(physical.get("artifactLocation").is_none());
+        let type_uid_line = event_json
+            .lines()
+            .position(|l| l.trim_start().starts_with("\"type_uid\""))
+            .unwrap();
+        assert_eq!(physical["region"]["startLine"], type_uid_line + 1);
+        assert!(physical["region"]["startColumn"].is_u64());
+
+        let request = ValidateEventRequest {
+            version: Some("1.4.0".to_string()),
+            event_json: events[3].to_string(),
+            json_schema_cross_check: Some(true),
+            format: Some(ReportFormat::Junit),
+        };
+        assert!(validate_event_impl(request).await.is_err());
+    }
+}
+
+#[cfg(test)]
+mod fix_tests {
+    use super::*;
+    use ocsf_mcp::ocsf::repair::repair_event;
+    use serde_json::{json, Value};
+
+    fn rules(fixes: &Value) -> Vec<&str> {
+        fixes
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["rule"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_fix_event_repairs_mechanical_failures() {
+        let event = json!({
+            "class_uid": "3002",
+            "activity_name": "Logon",
+            "severity": "high",
+            "time": "2025-01-15T10:30:00Z",
+            "metadata": {"product": {"name": "sshd", "vendor_name": "OpenBSD"}},
+            "user": {"name": "john.doe"},
+            "dst_endpoint": {"ip": "10.0.0.1"}
+        });
+
+        let request = FixEventRequest {
+            version: Some("1.4.0".to_string()),
+            event_json: event.to_string(),
+        };
+        let response: Value =
+            serde_json::from_str(&fix_event_impl(request).await.unwrap()).unwrap();
+
+        let fixed = &response["event"];
+        assert_eq!(fixed["class_uid"], 3002);
+        assert_eq!(fixed["class_name"], "Authentication");
+        assert_eq!(fixed["category_uid"], 3);
+        assert_eq!(fixed["activity_id"], 1);
+        assert_eq!(fixed["type_uid"], 300201);
+        assert_eq!(fixed["type_name"], "Authentication: Logon");
+        assert_eq!(fixed["severity_id"], 4);
+        assert_eq!(fixed["severity"], "High");
+        assert_eq!(fixed["time"], 1736937000000i64);
+        assert_eq!(fixed["metadata"]["version"], "1.4.0");
+        assert_eq!(response["validation"]["is_valid"], true);
+
+        let fixes = &response["fixes"];
+        let rules = rules(fixes);
+        for rule in [
+            "integer",
+            "enum_id",
+            "timestamp",
+            "type_uid",
+            "metadata_version",
+        ] {
+            assert!(rules.contains(&rule), "{rule} missing from {rules:?}");
+        }
+        let time = fixes
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["rule"] == "timestamp")
+            .unwrap();
+        assert_eq!(time["pointer"], "/time");
+        assert_eq!(time["before"], "2025-01-15T10:30:00Z");
+    }
+
+    #[tokio::test]
+    async fn test_fix_event_leaves_ambiguous_failures() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        let event = json!({
+            "category_uid": 1,
+            "activity_id": 1,
+            "activity_name": "Logoff",
+            "type_uid": 300203,
+            "severity": "Dire",
+            "time": 1736937000000i64,
+            "metadata": {
+                "version": "1.4.0",
+                "event_class": "authentication",
+                "product": {"name": "sshd", "vendor_name": "OpenBSD"}
+            },
+            "observables": [{"name": "user.name", "type_id": "User Name", "value": "john.doe"}]
+        });
+
+        let repair = repair_event(event, &schema).unwrap();
+        let fixed = &repair.event;
+
+        assert_eq!(fixed["class_uid"], 3002);
+        assert!(fixed["metadata"].get("event_class").is_none());
+        assert_eq!(
+            repair
+                .fixes
+                .iter()
+                .filter(|f| f.rule == "legacy_metadata")
+                .count(),
+            1
+        );
+        assert_eq!(fixed["activity_name"], "Logoff");
+        assert!(fixed.get("type_name").is_none());
+        assert_eq!(fixed["category_uid"], 1);
+        assert_eq!(fixed["observables"][0]["type_id"], 4);
+        assert!(repair
+            .fixes
+            .iter()
+            .any(|f| f.pointer == "/observables/0/type_id" && f.rule == "enum_id"));
+
+        // type_uid disagrees with activity_id, category_uid with the class, and 'Dire' is no
+        // severity caption
+        assert_eq!(fixed["type_uid"], 300203);
+        assert!(fixed.get("severity_id").is_none());
+        let pointers: Vec<&str> = repair
+            .validation
+            .errors
+            .iter()
+            .map(|e| e.pointer.as_str())
+            .collect();
+        assert!(pointers.contains(&"/type_uid"), "{pointers:?}");
+        assert!(pointers.contains(&"/category_uid"), "{pointers:?}");
+        assert!(pointers.contains(&"/severity_id"), "{pointers:?}");
+    }
+    #[tokio::test]
+    async fn test_fix_event_keeps_timestamps_without_offset() {
+        let schema = OcsfSchema::load_version("1.4.0").await.unwrap();
+        // Neither says its time zone, and the syslog one not even its year
+        for time in ["Jan  5 10:00:00", "2025-01-15 10:00:00"] {
+            let event = json!({
+                "class_uid": 3002,
+                "activity_id": 1,
+                "severity_id": 1,
+                "time": time,
+                "metadata": {"version": "1.4.0", "product": {"name": "sshd", "vendor_name": "OpenBSD"}}
+            });
+            let repair = repair_event(event, &schema).unwrap();
+            assert_eq!(repair.event["time"], time);
+            assert!(repair.fixes.iter().all(|f| f.rule != "timestamp"));
+            assert!(repair
+                .validation
+                .errors
+                .iter()
+                .any(|e| e.pointer == "/time"));
+        }
+
+        let event = json!({"class_uid": 3002, "time": "Wed, 15 Jan 2025 12:30:00 +0200"});
+        let repair = repair_event(event, &schema).unwrap();
+        assert_eq!(repair.event["time"], 1736937000000i64);
+    }
+}